@@ -40,4 +40,6 @@ end
 
     println!("cargo:rerun-if-changed=src/cli.rs");
     println!("cargo:rerun-if-changed=src/main.rs");
+    // Templates are embedded with include_dir!, which does not notice new files
+    println!("cargo:rerun-if-changed=templates");
 }
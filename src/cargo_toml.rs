@@ -0,0 +1,359 @@
+//! Editing generated `Cargo.toml` files
+//!
+//! Project templates render a complete manifest; feature plugins then ask for
+//! more dependencies. [`ManifestBuilder`] adds those with `toml_edit`, so the
+//! rendered layout and comments survive, and a dependency requested twice is
+//! written once with the union of the requested features. Plugins can also
+//! declare Cargo features and put their dependencies behind them.
+
+use anyhow::{anyhow, Context, Result};
+use std::fmt;
+use toml_edit::{
+    Array, ArrayOfTables, DocumentMut, InlineTable, Item, Key, Table, TableLike, Value,
+};
+
+/// Which dependency table an entry goes in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyKind {
+    Normal,
+    Dev,
+    Build,
+}
+
+impl DependencyKind {
+    /// The manifest key for this table
+    pub fn table_name(&self) -> &'static str {
+        match self {
+            DependencyKind::Normal => "dependencies",
+            DependencyKind::Dev => "dev-dependencies",
+            DependencyKind::Build => "build-dependencies",
+        }
+    }
+}
+
+/// A `Cargo.toml` that dependencies can be merged into
+#[derive(Debug, Clone)]
+pub struct ManifestBuilder {
+    document: DocumentMut,
+}
+
+impl ManifestBuilder {
+    /// Start from an existing manifest, usually the one rendered from a template
+    pub fn parse(manifest: &str) -> Result<Self> {
+        let document = manifest
+            .parse::<DocumentMut>()
+            .context("Failed to parse Cargo.toml")?;
+        Ok(Self { document })
+    }
+
+    /// Whether the manifest has a `[package]` table, as opposed to a virtual workspace
+    pub fn has_package(&self) -> bool {
+        self.document.contains_key("package")
+    }
+
+    /// Add a dependency, merging it with any existing entry of the same name
+    ///
+    /// `spec` is a TOML value as written after `name =`: a version string
+    /// such as `"1.0"` or an inline table such as
+    /// `{ version = "1", features = ["full"] }`. A bare version without quotes
+    /// is accepted too. `target` is a `cfg(...)` expression or target triple
+    /// for a `[target.<target>.dependencies]` table.
+    ///
+    /// An existing entry keeps its version; the requested features are added
+    /// to its feature list, and `default-features = false` is dropped unless
+    /// both entries ask for it.
+    pub fn add_dependency(
+        &mut self,
+        kind: DependencyKind,
+        target: Option<&str>,
+        name: &str,
+        spec: &str,
+    ) -> Result<()> {
+        let spec = parse_spec(spec).with_context(|| format!("Invalid dependency {}", name))?;
+        let table = self.table_mut(kind, target)?;
+
+        match table.get_mut(name) {
+            None => {
+                table.insert(name, Item::Value(spec));
+            }
+            Some(existing) => merge_dependency(existing, spec)
+                .with_context(|| format!("Failed to merge dependency {}", name))?,
+        }
+        Ok(())
+    }
+
+    /// Add a dependency that only the Cargo feature `feature` pulls in
+    ///
+    /// The dependency is marked `optional = true` and `dep:<name>` is added
+    /// to the feature. A dependency the manifest already requires stays
+    /// required, and the feature is still declared so code gated on it
+    /// compiles.
+    pub fn add_optional_dependency(&mut self, feature: &str, name: &str, spec: &str) -> Result<()> {
+        let required = self
+            .table_mut(DependencyKind::Normal, None)?
+            .get(name)
+            .is_some_and(|existing| !is_optional(existing));
+        if required {
+            self.add_dependency(DependencyKind::Normal, None, name, spec)?;
+            return self.add_feature(feature, &[]);
+        }
+
+        let mut spec = parse_spec(spec).with_context(|| format!("Invalid dependency {}", name))?;
+        if let Some(version) = spec.as_str() {
+            let mut table = InlineTable::new();
+            table.insert("version", Value::from(version));
+            spec = Value::InlineTable(table);
+        }
+        if let Some(table) = spec.as_inline_table_mut() {
+            table.insert("optional", Value::from(true));
+            table.fmt();
+        }
+        self.add_dependency(DependencyKind::Normal, None, name, &spec.to_string())?;
+        self.add_feature(feature, &[&format!("dep:{}", name)])
+    }
+
+    /// Declare a Cargo feature, adding `members` to it if it already exists
+    ///
+    /// Adding to `default` turns features on by default.
+    pub fn add_feature(&mut self, name: &str, members: &[&str]) -> Result<()> {
+        let features = child_table(self.document.as_table_mut(), "features", false)?;
+        let requested: Array = members.iter().copied().collect();
+        merge_list(features, name, &Value::Array(requested))
+    }
+
+    /// Declare an example that needs Cargo features, as an `[[example]]` entry
+    ///
+    /// Features the manifest does not define yet are added to `[features]`
+    /// as empty features so the manifest stays valid. Declaring the same
+    /// example twice unions its required features.
+    pub fn add_example(&mut self, name: &str, required_features: &[&str]) -> Result<()> {
+        for feature in required_features {
+            let features = child_table(self.document.as_table_mut(), "features", false)?;
+            if !features.contains_key(feature) {
+                features.insert(feature, toml_edit::value(Array::new()));
+            }
+        }
+
+        let examples = self
+            .document
+            .entry("example")
+            .or_insert(Item::ArrayOfTables(ArrayOfTables::new()))
+            .as_array_of_tables_mut()
+            .ok_or_else(|| anyhow!("`example` in Cargo.toml is not an array of tables"))?;
+
+        let existing = examples
+            .iter()
+            .position(|example| example.get("name").and_then(Item::as_str) == Some(name));
+        let index = match existing {
+            Some(index) => index,
+            None => {
+                let mut example = Table::new();
+                example.insert("name", toml_edit::value(name));
+                examples.push(example);
+                examples.len() - 1
+            }
+        };
+        let example = examples.get_mut(index).expect("example index is in bounds");
+
+        if !required_features.is_empty() {
+            let requested: Array = required_features.iter().copied().collect();
+            merge_list(example, "required-features", &Value::Array(requested))?;
+        }
+        Ok(())
+    }
+
+    /// Names of every dependency, in all tables, targets and `[workspace.dependencies]`
+    ///
+    /// These are the names code refers to the crates by, so a renamed
+    /// dependency is listed under its key rather than its `package`.
+    pub fn dependency_names(&self) -> Vec<String> {
+        let root = self.document.as_table();
+        let mut parents = vec![root];
+        if let Some(targets) = root.get("target").and_then(Item::as_table) {
+            parents.extend(targets.iter().filter_map(|(_, target)| target.as_table()));
+        }
+        let mut tables: Vec<&Table> = parents
+            .iter()
+            .flat_map(|parent| {
+                [
+                    DependencyKind::Normal,
+                    DependencyKind::Dev,
+                    DependencyKind::Build,
+                ]
+                .into_iter()
+                .filter_map(|kind| parent.get(kind.table_name()).and_then(Item::as_table))
+            })
+            .collect();
+        if let Some(workspace) = root.get("workspace").and_then(Item::as_table) {
+            tables.extend(workspace.get("dependencies").and_then(Item::as_table));
+        }
+
+        let mut names: Vec<String> = tables
+            .iter()
+            .flat_map(|table| table.iter().map(|(name, _)| name.to_string()))
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// The `required-features` of every `[[example]]` entry, by example name
+    pub fn example_required_features(&self) -> Vec<(String, Vec<String>)> {
+        let Some(examples) = self
+            .document
+            .get("example")
+            .and_then(Item::as_array_of_tables)
+        else {
+            return Vec::new();
+        };
+        examples
+            .iter()
+            .filter_map(|example| {
+                let name = example.get("name")?.as_str()?.to_string();
+                let features = example
+                    .get("required-features")
+                    .and_then(Item::as_array)
+                    .map(|features| {
+                        features
+                            .iter()
+                            .filter_map(|f| f.as_str().map(String::from))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                Some((name, features))
+            })
+            .collect()
+    }
+
+    /// The dependency table for `kind` and `target`, created if missing
+    fn table_mut(&mut self, kind: DependencyKind, target: Option<&str>) -> Result<&mut Table> {
+        let mut parent = self.document.as_table_mut();
+        if let Some(target) = target {
+            parent = child_table(parent, "target", true)?;
+            parent = child_table(parent, target, true)?;
+        }
+        child_table(parent, kind.table_name(), false)
+    }
+}
+
+impl fmt::Display for ManifestBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.document)
+    }
+}
+
+/// The table at `key` under `parent`, created if missing
+fn child_table<'a>(parent: &'a mut Table, key: &str, implicit: bool) -> Result<&'a mut Table> {
+    // Quote keys like `cfg(unix)` with single quotes, as Cargo's docs do
+    let key = match key.parse::<Key>() {
+        Ok(parsed) if parsed.get() == key => parsed,
+        _ => format!("'{}'", key)
+            .parse::<Key>()
+            .unwrap_or_else(|_| Key::new(key)),
+    };
+    parent
+        .entry_format(&key)
+        .or_insert_with(|| {
+            let mut table = Table::new();
+            table.set_implicit(implicit);
+            Item::Table(table)
+        })
+        .as_table_mut()
+        .ok_or_else(|| anyhow!("`{}` in Cargo.toml is not a table", key.get()))
+}
+
+fn parse_spec(spec: &str) -> Result<Value> {
+    let value = match spec.trim().parse::<Value>() {
+        Ok(value) => value,
+        // A bare version such as `1.0`
+        Err(_) => Value::from(spec.trim()),
+    };
+    match value {
+        Value::String(_) | Value::InlineTable(_) => Ok(value),
+        // `1` and `1.0` parse as numbers
+        Value::Integer(_) | Value::Float(_) => Ok(Value::from(spec.trim())),
+        other => Err(anyhow!(
+            "expected a version string or an inline table, found {}",
+            other.type_name()
+        )),
+    }
+}
+
+/// Whether a dependency entry has `optional = true`
+fn is_optional(dependency: &Item) -> bool {
+    dependency
+        .as_table_like()
+        .and_then(|entry| entry.get("optional"))
+        .and_then(Item::as_bool)
+        .unwrap_or(false)
+}
+
+/// Fold `spec` into the existing dependency entry
+fn merge_dependency(existing: &mut Item, spec: Value) -> Result<()> {
+    let requested = match spec {
+        // A plain version adds nothing to an existing entry
+        Value::String(_) => return Ok(()),
+        Value::InlineTable(table) => table,
+        _ => unreachable!("parse_spec only returns strings and inline tables"),
+    };
+
+    // Turn `name = "1.0"` into `name = { version = "1.0" }` so it can hold features
+    if let Some(version) = existing.as_str() {
+        let mut table = InlineTable::new();
+        table.insert("version", Value::from(version));
+        *existing = Item::Value(Value::InlineTable(table));
+    }
+    let entry = existing
+        .as_table_like_mut()
+        .ok_or_else(|| anyhow!("expected a version string or a table"))?;
+
+    for (key, value) in requested.iter() {
+        match key {
+            "version" => {}
+            "features" => merge_list(entry, "features", value)?,
+            "default-features" | "default_features" => {}
+            _ => {
+                if !entry.contains_key(key) {
+                    entry.insert(key, Item::Value(value.clone()));
+                }
+            }
+        }
+    }
+
+    let wants_defaults = requested
+        .get("default-features")
+        .or_else(|| requested.get("default_features"))
+        .and_then(Value::as_bool)
+        .unwrap_or(true);
+    if wants_defaults {
+        entry.remove("default-features");
+        entry.remove("default_features");
+    }
+
+    if let Some(table) = existing.as_inline_table_mut() {
+        table.fmt();
+    }
+    Ok(())
+}
+
+/// Add the strings in `requested` that the list at `key` does not contain yet
+fn merge_list(entry: &mut dyn TableLike, key: &str, requested: &Value) -> Result<()> {
+    let requested = requested
+        .as_array()
+        .ok_or_else(|| anyhow!("`{}` must be an array", key))?;
+    if !entry.contains_key(key) {
+        entry.insert(key, Item::Value(Value::Array(Array::new())));
+    }
+    let features = entry
+        .get_mut(key)
+        .and_then(Item::as_array_mut)
+        .ok_or_else(|| anyhow!("`{}` must be an array", key))?;
+
+    for feature in requested.iter().filter_map(Value::as_str) {
+        if !features.iter().any(|f| f.as_str() == Some(feature)) {
+            features.push(feature);
+        }
+    }
+    features.fmt();
+    Ok(())
+}
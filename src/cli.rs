@@ -1,3 +1,7 @@
+use cargo_forge::conflict::ConflictStrategy;
+use cargo_forge::forge::OutputFormat;
+use cargo_forge::git::Vcs;
+use cargo_forge::{Edition, Framework};
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
@@ -38,7 +42,12 @@ pub enum Commands {
         description: Option<String>,
 
         /// License
-        #[arg(short, long, help = "License for the project")]
+        #[arg(
+            short,
+            long,
+            value_parser = parse_license,
+            help = "SPDX license expression, such as \"MIT OR Apache-2.0\", or \"proprietary\""
+        )]
         license: Option<String>,
 
         /// Non-interactive mode with defaults for CI usage
@@ -52,6 +61,104 @@ pub enum Commands {
         /// Dry run mode - preview without creating files
         #[arg(long, help = "Preview the project structure without creating files")]
         dry_run: bool,
+
+        /// Dry run output format
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = OutputFormat::Text,
+            requires = "dry_run",
+            help = "Output format for --dry-run"
+        )]
+        format: OutputFormat,
+
+        /// Include file contents in JSON output
+        #[arg(
+            long,
+            requires = "dry_run",
+            help = "Include each file's rendered contents in --format json output"
+        )]
+        include_contents: bool,
+
+        /// Scratch directory for dry runs
+        #[arg(
+            long,
+            value_name = "DIR",
+            requires = "dry_run",
+            help = "Also render the dry-run project into this directory for inspection"
+        )]
+        output_dir: Option<PathBuf>,
+
+        /// Web framework
+        #[arg(
+            long,
+            value_enum,
+            help = "Web framework for api-server projects [default: from config, else axum]"
+        )]
+        framework: Option<Framework>,
+
+        /// Rust edition
+        #[arg(
+            long,
+            value_enum,
+            help = "Rust edition for the generated project [default: 2021]"
+        )]
+        edition: Option<Edition>,
+
+        /// Minimum supported Rust version
+        #[arg(
+            long,
+            value_name = "VERSION",
+            help = "Minimum supported Rust version, pinned in rust-toolchain.toml"
+        )]
+        rust_version: Option<String>,
+
+        /// Version control
+        #[arg(
+            long,
+            value_enum,
+            help = "Initialize a repository in the project: git or none [default: from config, else none]"
+        )]
+        vcs: Option<Vcs>,
+
+        /// Initial commit
+        #[arg(
+            long,
+            help = "Commit the generated files when initializing a git repository"
+        )]
+        initial_commit: bool,
+
+        /// Verify the generated project
+        #[arg(
+            long,
+            conflicts_with = "dry_run",
+            help = "Check the generated project: parse its TOML and YAML, run cargo metadata and, with a local dependency cache, cargo check"
+        )]
+        verify: bool,
+
+        /// Verify against the registry index
+        #[arg(
+            long,
+            requires = "verify",
+            help = "Let --verify look up dependencies it cannot resolve offline in the registry index"
+        )]
+        verify_online: bool,
+
+        /// Optional features
+        #[arg(
+            long,
+            help = "Put feature dependencies behind optional Cargo features (library and cli-tool)"
+        )]
+        optional_features: bool,
+
+        /// Template variables
+        #[arg(
+            long = "var",
+            value_name = "KEY=VALUE",
+            value_parser = parse_var,
+            help = "Set a template variable (repeatable)"
+        )]
+        vars: Vec<(String, String)>,
     },
 
     /// Initialize a new project in the current directory
@@ -69,7 +176,12 @@ pub enum Commands {
         author: Option<String>,
 
         /// License
-        #[arg(short, long, help = "License for the project")]
+        #[arg(
+            short,
+            long,
+            value_parser = parse_license,
+            help = "SPDX license expression, such as \"MIT OR Apache-2.0\", or \"proprietary\""
+        )]
         license: Option<String>,
 
         /// Non-interactive mode with defaults for CI usage
@@ -83,6 +195,119 @@ pub enum Commands {
         /// Dry run mode - preview without creating files
         #[arg(long, help = "Preview the project structure without creating files")]
         dry_run: bool,
+
+        /// Dry run output format
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = OutputFormat::Text,
+            requires = "dry_run",
+            help = "Output format for --dry-run"
+        )]
+        format: OutputFormat,
+
+        /// Include file contents in JSON output
+        #[arg(
+            long,
+            requires = "dry_run",
+            help = "Include each file's rendered contents in --format json output"
+        )]
+        include_contents: bool,
+
+        /// Scratch directory for dry runs
+        #[arg(
+            long,
+            value_name = "DIR",
+            requires = "dry_run",
+            help = "Also render the dry-run project into this directory for inspection"
+        )]
+        output_dir: Option<PathBuf>,
+
+        /// Conflict strategy
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = ConflictStrategy::Error,
+            help = "What to do with files that already exist"
+        )]
+        on_conflict: ConflictStrategy,
+
+        /// Web framework
+        #[arg(
+            long,
+            value_enum,
+            help = "Web framework for api-server projects [default: from config, else axum]"
+        )]
+        framework: Option<Framework>,
+
+        /// Rust edition
+        #[arg(
+            long,
+            value_enum,
+            help = "Rust edition for the generated project [default: 2021]"
+        )]
+        edition: Option<Edition>,
+
+        /// Minimum supported Rust version
+        #[arg(
+            long,
+            value_name = "VERSION",
+            help = "Minimum supported Rust version, pinned in rust-toolchain.toml"
+        )]
+        rust_version: Option<String>,
+
+        /// Version control
+        #[arg(
+            long,
+            value_enum,
+            help = "Initialize a repository in the project: git or none [default: from config, else none]"
+        )]
+        vcs: Option<Vcs>,
+
+        /// Initial commit
+        #[arg(
+            long,
+            help = "Commit the generated files when initializing a git repository"
+        )]
+        initial_commit: bool,
+
+        /// Verify the generated project
+        #[arg(
+            long,
+            conflicts_with = "dry_run",
+            help = "Check the generated project: parse its TOML and YAML, run cargo metadata and, with a local dependency cache, cargo check"
+        )]
+        verify: bool,
+
+        /// Verify against the registry index
+        #[arg(
+            long,
+            requires = "verify",
+            help = "Let --verify look up dependencies it cannot resolve offline in the registry index"
+        )]
+        verify_online: bool,
+
+        /// Optional features
+        #[arg(
+            long,
+            help = "Put feature dependencies behind optional Cargo features (library and cli-tool)"
+        )]
+        optional_features: bool,
+
+        /// Template variables
+        #[arg(
+            long = "var",
+            value_name = "KEY=VALUE",
+            value_parser = parse_var,
+            help = "Set a template variable (repeatable)"
+        )]
+        vars: Vec<(String, String)>,
+    },
+
+    /// Work with project templates
+    Template {
+        #[command(subcommand)]
+        command: TemplateCommands,
     },
 
     /// Generate shell completions
@@ -92,3 +317,38 @@ pub enum Commands {
         shell: clap_complete::Shell,
     },
 }
+
+#[derive(Subcommand)]
+pub enum TemplateCommands {
+    /// Render every template across a feature matrix and check that the output parses
+    Check {
+        /// Project type
+        #[arg(
+            short,
+            long,
+            help = "Only check this project type (default: all of them)"
+        )]
+        project_type: Option<String>,
+
+        /// Template variables
+        #[arg(
+            long = "var",
+            value_name = "KEY=VALUE",
+            value_parser = parse_var,
+            help = "Set a template variable (repeatable)"
+        )]
+        vars: Vec<(String, String)>,
+    },
+}
+
+/// Parse a `--var key=value` assignment
+fn parse_var(input: &str) -> Result<(String, String), String> {
+    cargo_forge::templates::manifest::parse_assignment(input).map_err(|e| e.to_string())
+}
+
+/// Validate a `--license` expression and spell it the SPDX way
+fn parse_license(input: &str) -> Result<String, String> {
+    cargo_forge::license::License::parse(input)
+        .map(|license| license.to_string())
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,377 @@
+//! Resolving files that `init` would write over
+//!
+//! When a planned file already exists, [`ConflictStrategy`] decides whether to
+//! keep it, replace it or merge the generated content into it. Merging is
+//! only possible for files we know how to combine: `.gitignore` gets the
+//! missing patterns, `README.md` the missing sections and `Cargo.toml` the
+//! missing dependencies, features, targets and package metadata.
+
+use anyhow::{anyhow, Result};
+use similar::TextDiff;
+use std::collections::HashSet;
+use std::path::Path;
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table, TableLike};
+
+/// Dependency tables merged into an existing `Cargo.toml`
+const DEPENDENCY_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// `[[...]]` targets merged into an existing `Cargo.toml` by name
+const TARGET_KINDS: [&str; 4] = ["bin", "example", "test", "bench"];
+
+/// What to do when `init` would write a file that already exists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ConflictStrategy {
+    /// Stop without changing anything
+    #[default]
+    Error,
+    /// Keep the existing file
+    Skip,
+    /// Replace the existing file with the generated one
+    Overwrite,
+    /// Merge into files we know how to merge and keep the rest
+    Merge,
+    /// Show a diff and ask for each file
+    Prompt,
+}
+
+impl ConflictStrategy {
+    /// Decide what to do with each conflict, or fail if this strategy cannot decide
+    pub fn resolve(&self, conflicts: &[Conflict]) -> Result<Vec<Resolution>> {
+        if conflicts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let paths = || {
+            conflicts
+                .iter()
+                .map(|conflict| conflict.path.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match self {
+            ConflictStrategy::Error => Err(anyhow!(
+                "Refusing to overwrite existing files: {} (choose what to do with --on-conflict)",
+                paths()
+            )),
+            ConflictStrategy::Prompt => Err(anyhow!(
+                "--on-conflict prompt needs an interactive terminal (existing files: {})",
+                paths()
+            )),
+            ConflictStrategy::Skip => Ok(vec![Resolution::Keep; conflicts.len()]),
+            ConflictStrategy::Overwrite => Ok(vec![Resolution::Replace; conflicts.len()]),
+            ConflictStrategy::Merge => Ok(conflicts
+                .iter()
+                .map(|conflict| match conflict.merged {
+                    Some(_) => Resolution::Merge,
+                    None => Resolution::Keep,
+                })
+                .collect()),
+        }
+    }
+}
+
+/// How a single conflicting file is resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    Keep,
+    Replace,
+    Merge,
+}
+
+/// A planned file whose path already exists
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    /// Path relative to the project root
+    pub path: String,
+    pub existing: Vec<u8>,
+    pub generated: Vec<u8>,
+    /// The existing file with the generated content merged in, for files we can merge
+    pub merged: Option<Vec<u8>>,
+}
+
+impl Conflict {
+    pub fn new(path: &str, existing: Vec<u8>, generated: Vec<u8>) -> Self {
+        let merged = match (
+            std::str::from_utf8(&existing),
+            std::str::from_utf8(&generated),
+        ) {
+            (Ok(existing), Ok(generated)) => {
+                merge_file(path, existing, generated).map(String::into_bytes)
+            }
+            _ => None,
+        };
+        Self {
+            path: path.to_string(),
+            existing,
+            generated,
+            merged,
+        }
+    }
+
+    /// The contents to write for `resolution`, or `None` to leave the file alone
+    pub fn contents(&self, resolution: Resolution) -> Option<&[u8]> {
+        match resolution {
+            Resolution::Keep => None,
+            Resolution::Replace => Some(&self.generated),
+            Resolution::Merge => self.merged.as_deref(),
+        }
+    }
+
+    /// Unified diff from the existing file to what `resolution` would write
+    pub fn diff(&self, resolution: Resolution) -> String {
+        let Some(proposed) = self.contents(resolution) else {
+            return String::new();
+        };
+        let old = String::from_utf8_lossy(&self.existing);
+        let new = String::from_utf8_lossy(proposed);
+        TextDiff::from_lines(old.as_ref(), new.as_ref())
+            .unified_diff()
+            .header(&format!("a/{}", self.path), &format!("b/{}", self.path))
+            .to_string()
+    }
+}
+
+/// What `init` did with each planned file
+#[derive(Debug, Clone, Default)]
+pub struct InitSummary {
+    pub created: Vec<String>,
+    pub replaced: Vec<String>,
+    pub merged: Vec<String>,
+    /// Existing files left untouched
+    pub kept: Vec<String>,
+}
+
+/// Merge generated content into an existing file, for the files we know how to merge
+///
+/// Returns `None` when the file type is not mergeable or the existing file
+/// cannot be parsed.
+pub fn merge_file(path: &str, existing: &str, generated: &str) -> Option<String> {
+    match Path::new(path).file_name()?.to_str()? {
+        ".gitignore" => Some(merge_gitignore(existing, generated)),
+        "README.md" => Some(merge_readme(existing, generated)),
+        "Cargo.toml" => merge_cargo_toml(existing, generated),
+        _ => None,
+    }
+}
+
+/// Append the generated patterns that the existing `.gitignore` lacks
+pub fn merge_gitignore(existing: &str, generated: &str) -> String {
+    let present: HashSet<&str> = existing.lines().map(str::trim).collect();
+    let mut seen = HashSet::new();
+    let missing: Vec<&str> = generated
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter(|line| !present.contains(line) && seen.insert(*line))
+        .collect();
+
+    let mut merged = existing.to_string();
+    if missing.is_empty() {
+        return merged;
+    }
+    if !merged.is_empty() && !merged.ends_with('\n') {
+        merged.push('\n');
+    }
+    if !merged.is_empty() {
+        merged.push('\n');
+    }
+    merged.push_str("# Added by cargo-forge\n");
+    for line in missing {
+        merged.push_str(line);
+        merged.push('\n');
+    }
+    merged
+}
+
+/// Append the `##` sections of the generated README whose headings the existing one lacks
+pub fn merge_readme(existing: &str, generated: &str) -> String {
+    let headings: HashSet<String> = existing
+        .lines()
+        .filter(|line| line.starts_with('#'))
+        .map(heading_key)
+        .collect();
+
+    let mut merged = existing.to_string();
+    for section in readme_sections(generated) {
+        let heading = section.lines().next().unwrap_or_default();
+        if headings.contains(&heading_key(heading)) {
+            continue;
+        }
+        if !merged.is_empty() && !merged.ends_with('\n') {
+            merged.push('\n');
+        }
+        if !merged.is_empty() {
+            merged.push('\n');
+        }
+        merged.push_str(section.trim_end());
+        merged.push('\n');
+    }
+    merged
+}
+
+/// Heading text without the leading `#`s, for comparing headings across levels
+fn heading_key(line: &str) -> String {
+    line.trim_start_matches('#').trim().to_lowercase()
+}
+
+/// The `## ` sections of a README, each starting with its heading line
+fn readme_sections(readme: &str) -> Vec<String> {
+    let mut sections = Vec::new();
+    let mut current: Option<String> = None;
+    let mut in_code = false;
+
+    for line in readme.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        if !in_code && line.starts_with("## ") {
+            sections.extend(current.take());
+            current = Some(String::new());
+        }
+        if let Some(section) = current.as_mut() {
+            section.push_str(line);
+            section.push('\n');
+        }
+    }
+    sections.extend(current);
+    sections
+}
+
+/// Add the generated entries that the existing `Cargo.toml` lacks
+///
+/// Dependencies, including target-specific ones, and features are merged
+/// key by key, `[[bin]]`-style targets by name and `[package.metadata]` down
+/// to the keys each tool reads. Existing entries are never changed, and
+/// formatting and comments in the existing manifest are kept.
+pub fn merge_cargo_toml(existing: &str, generated: &str) -> Option<String> {
+    let mut document: DocumentMut = existing.parse().ok()?;
+    let generated: DocumentMut = generated.parse().ok()?;
+    let root = document.as_table_mut();
+
+    merge_dependency_tables(root, generated.as_table())?;
+    if let Some(targets) = generated.get("target").and_then(Item::as_table_like) {
+        for (platform, generated_target) in targets.iter() {
+            let Some(generated_target) = generated_target.as_table_like() else {
+                continue;
+            };
+            let target = implicit_table(implicit_table(root, "target")?, platform)?;
+            merge_dependency_tables(target, generated_target)?;
+        }
+    }
+
+    if let Some(features) = generated.get("features").and_then(Item::as_table_like) {
+        if !root.contains_key("features") {
+            root.insert("features", toml_edit::table());
+        }
+        add_missing(root.get_mut("features")?.as_table_like_mut()?, features);
+    }
+
+    let metadata = generated
+        .get("package")
+        .and_then(|package| package.get("metadata"))
+        .and_then(Item::as_table_like);
+    let package = root.get_mut("package").and_then(Item::as_table_like_mut);
+    if let (Some(metadata), Some(package)) = (metadata, package) {
+        merge_missing(implicit_table(package, "metadata")?, metadata);
+    }
+
+    for kind in TARGET_KINDS {
+        let Some(generated_targets) = generated.get(kind).and_then(Item::as_array_of_tables) else {
+            continue;
+        };
+        if !root.contains_key(kind) {
+            root.insert(kind, Item::ArrayOfTables(ArrayOfTables::new()));
+        }
+        let targets = root.get_mut(kind)?.as_array_of_tables_mut()?;
+        let names: HashSet<String> = targets
+            .iter()
+            .filter_map(|target| target.get("name")?.as_str().map(str::to_string))
+            .collect();
+        for target in generated_targets.iter() {
+            match target.get("name").and_then(Item::as_str) {
+                Some(name) if !names.contains(name) => {
+                    let mut target = target.clone();
+                    move_to_end(&mut target);
+                    targets.push(target);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Some(document.to_string())
+}
+
+/// Add the dependencies of each kind that `table` lacks
+fn merge_dependency_tables(table: &mut dyn TableLike, generated: &dyn TableLike) -> Option<()> {
+    for table_name in DEPENDENCY_TABLES {
+        let Some(generated_table) = generated.get(table_name).and_then(Item::as_table_like) else {
+            continue;
+        };
+        if !table.contains_key(table_name) {
+            table.insert(table_name, toml_edit::table());
+        }
+        add_missing(
+            table.get_mut(table_name)?.as_table_like_mut()?,
+            generated_table,
+        );
+    }
+    Some(())
+}
+
+/// Add the keys of `generated` that `table` lacks
+fn add_missing(table: &mut dyn TableLike, generated: &dyn TableLike) {
+    for (key, item) in generated.iter() {
+        if !table.contains_key(key) {
+            table.insert(key, detached(item));
+        }
+    }
+}
+
+/// Add the keys of `generated` that `table` lacks, descending into tables both have
+fn merge_missing(table: &mut dyn TableLike, generated: &dyn TableLike) {
+    for (key, item) in generated.iter() {
+        let existing = table.get_mut(key).and_then(Item::as_table_like_mut);
+        match (existing, item.as_table_like()) {
+            (Some(existing), Some(item)) => merge_missing(existing, item),
+            _ => {
+                if !table.contains_key(key) {
+                    table.insert(key, detached(item));
+                }
+            }
+        }
+    }
+}
+
+/// The table under `key`, added without a header of its own when missing
+fn implicit_table<'a>(table: &'a mut dyn TableLike, key: &str) -> Option<&'a mut dyn TableLike> {
+    if !table.contains_key(key) {
+        let mut implicit = Table::new();
+        implicit.set_implicit(true);
+        table.insert(key, Item::Table(implicit));
+    }
+    table.get_mut(key)?.as_table_like_mut()
+}
+
+/// A copy of `item` that is written after the existing tables
+fn detached(item: &Item) -> Item {
+    let mut item = item.clone();
+    match &mut item {
+        Item::Table(table) => move_to_end(table),
+        Item::ArrayOfTables(tables) => tables.iter_mut().for_each(move_to_end),
+        _ => {}
+    }
+    item
+}
+
+/// Drop the position `table` and its subtables had in the generated manifest
+fn move_to_end(table: &mut Table) {
+    table.set_position(usize::MAX);
+    for (_, item) in table.iter_mut() {
+        match item {
+            Item::Table(table) => move_to_end(table),
+            Item::ArrayOfTables(tables) => tables.iter_mut().for_each(move_to_end),
+            _ => {}
+        }
+    }
+}
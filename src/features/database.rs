@@ -54,17 +54,20 @@ impl DatabasePlugin {
     }
 
     fn generate_database_module(&self) -> String {
+        let database = match self.db_type {
+            DatabaseType::PostgreSQL => "Postgres",
+            DatabaseType::SQLite => "Sqlite",
+            DatabaseType::MySQL => "MySql",
+        };
         format!(
-            r#"use sqlx::{{pool, prelude::*}};
-
-#[derive(Clone)]
+            r#"#[derive(Clone)]
 pub struct Database {{
-    pool: sqlx::Pool<sqlx::{}>,
+    pool: sqlx::Pool<sqlx::{database}>,
 }}
 
 impl Database {{
     pub async fn new(database_url: &str) -> Result<Self, sqlx::Error> {{
-        let pool = sqlx::{}::PoolOptions::new()
+        let pool = sqlx::pool::PoolOptions::<sqlx::{database}>::new()
             .max_connections(5)
             .connect(database_url)
             .await?;
@@ -72,32 +75,18 @@ impl Database {{
         Ok(Self {{ pool }})
     }}
     
+    #[cfg(feature = "migrations")]
     pub async fn run_migrations(&self) -> Result<(), sqlx::migrate::MigrateError> {{
         sqlx::migrate!("./migrations")
             .run(&self.pool)
             .await
     }}
     
-    pub fn pool(&self) -> &sqlx::Pool<sqlx::{}> {{
+    pub fn pool(&self) -> &sqlx::Pool<sqlx::{database}> {{
         &self.pool
     }}
 }}
-"#,
-            match self.db_type {
-                DatabaseType::PostgreSQL => "Postgres",
-                DatabaseType::SQLite => "Sqlite",
-                DatabaseType::MySQL => "MySql",
-            },
-            match self.db_type {
-                DatabaseType::PostgreSQL => "postgres",
-                DatabaseType::SQLite => "sqlite",
-                DatabaseType::MySQL => "mysql",
-            },
-            match self.db_type {
-                DatabaseType::PostgreSQL => "Postgres",
-                DatabaseType::SQLite => "Sqlite",
-                DatabaseType::MySQL => "MySql",
-            }
+"#
         )
     }
 
@@ -168,13 +157,26 @@ impl Plugin for DatabasePlugin {
             .map(|f| format!(r#""{}""#, f))
             .collect::<Vec<_>>()
             .join(", ");
-        context.add_dependency(
+        context.add_feature_dependency(
+            "database",
             "sqlx",
             &format!(r#"{{ version = "0.7", features = [{}] }}"#, features_str),
         );
 
-        context.add_dependency("tokio", r#"{ version = "1", features = ["full"] }"#);
-        context.add_dependency("dotenv", r#""0.15""#);
+        context.add_feature_dependency(
+            "database",
+            "tokio",
+            r#"{ version = "1", features = ["full"] }"#,
+        );
+        context.add_feature_dependency("database", "dotenvy", r#""0.15""#);
+
+        // `migrations` gates `Database::run_migrations`, which needs the migrations directory
+        let migration_members: &[&str] = if context.optional_features {
+            &["database"]
+        } else {
+            &[]
+        };
+        context.add_feature("migrations", migration_members, self.with_migrations);
 
         context.add_to_gitignore(".env");
         context.add_to_gitignore("*.db");
@@ -184,7 +186,8 @@ impl Plugin for DatabasePlugin {
         let env_content = format!("DATABASE_URL={}\n", self.get_database_url_example());
         context.add_template_file(".env.example", env_content);
 
-        context.add_template_file("src/database.rs", self.generate_database_module());
+        let module = context.gate_module("database", self.generate_database_module());
+        context.add_template_file("src/database.rs", module);
 
         if self.with_migrations {
             context.create_directory("migrations");
@@ -199,9 +202,12 @@ impl Plugin for DatabasePlugin {
         }
 
         let example_code = format!(
-            r#"use dotenv::dotenv;
+            r#"use dotenvy::dotenv;
 use std::env;
 
+// The example only uses part of the module
+#[allow(dead_code)]
+#[path = "../src/database.rs"]
 mod database;
 use database::Database;
 
@@ -225,7 +231,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {{
             self.db_type
         );
 
-        context.add_example("database_connection", example_code);
+        if context.optional_features {
+            context.add_example_with_features("database_connection", example_code, &["database"]);
+        } else {
+            context.add_example("database_connection", example_code);
+        }
 
         Ok(())
     }
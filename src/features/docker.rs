@@ -12,6 +12,7 @@ pub struct DockerPlugin {
     build_stage: DockerBuildStage,
     with_compose: bool,
     expose_port: Option<u16>,
+    copy_dirs: Vec<String>,
 }
 
 impl DockerPlugin {
@@ -20,6 +21,7 @@ impl DockerPlugin {
             build_stage: DockerBuildStage::MultiStage,
             with_compose: false,
             expose_port: None,
+            copy_dirs: Vec::new(),
         }
     }
 
@@ -38,6 +40,20 @@ impl DockerPlugin {
         self
     }
 
+    /// Copy a directory besides `src` into the build, such as the `migrations` `sqlx::migrate!` embeds
+    pub fn copy_dir(mut self, dir: &str) -> Self {
+        self.copy_dirs.push(dir.to_string());
+        self
+    }
+
+    /// `COPY` lines for the source directories, `src` first
+    fn copy_sources(&self) -> String {
+        std::iter::once("src")
+            .chain(self.copy_dirs.iter().map(String::as_str))
+            .map(|dir| format!("COPY {} ./{}\n", dir, dir))
+            .collect()
+    }
+
     fn generate_dockerfile(&self, project_name: &str) -> String {
         match self.build_stage {
             DockerBuildStage::Simple => self.generate_simple_dockerfile(project_name),
@@ -53,11 +69,11 @@ impl DockerPlugin {
 WORKDIR /app
 
 COPY Cargo.toml Cargo.lock ./
-COPY src ./src
-
+{}
 RUN cargo build --release
 
-"#
+"#,
+            self.copy_sources()
         );
 
         if let Some(port) = self.expose_port {
@@ -84,8 +100,7 @@ RUN cargo build --release
 RUN rm -rf src
 
 # Copy source code
-COPY src ./src
-
+{}
 # Build application
 RUN touch src/main.rs
 RUN cargo build --release
@@ -103,7 +118,9 @@ WORKDIR /app
 COPY --from=builder /app/target/release/{} /app/{}
 
 "#,
-            project_name, project_name
+            self.copy_sources(),
+            project_name,
+            project_name
         );
 
         if let Some(port) = self.expose_port {
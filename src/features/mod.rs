@@ -10,11 +10,28 @@ pub struct ProjectContext {
     pub name: String,
     pub dependencies: HashMap<String, String>,
     pub dev_dependencies: HashMap<String, String>,
+    pub build_dependencies: HashMap<String, String>,
+    /// Dependencies for `[target.<cfg>.dependencies]`, keyed by target
+    pub target_dependencies: HashMap<String, HashMap<String, String>>,
     pub template_files: HashMap<String, String>,
+    /// Name of the plugin that added each entry in `template_files`
+    pub template_file_plugins: HashMap<String, String>,
     pub directories: Vec<String>,
     pub gitignore_entries: Vec<String>,
     pub readme_sections: Vec<String>,
     pub examples: HashMap<String, String>,
+    /// Cargo features each example needs, for its `required-features`
+    pub example_features: HashMap<String, Vec<String>>,
+    /// Name of the plugin that added each entry in `examples`
+    pub example_plugins: HashMap<String, String>,
+    /// Put each plugin's dependencies and code behind a Cargo feature
+    pub optional_features: bool,
+    /// Cargo features to declare, with the features and `dep:` entries they enable
+    pub features: HashMap<String, Vec<String>>,
+    /// Optional dependencies by the Cargo feature that enables them
+    pub optional_dependencies: HashMap<String, HashMap<String, String>>,
+    /// Features listed in `default`
+    pub default_features: Vec<String>,
 }
 
 impl ProjectContext {
@@ -23,11 +40,20 @@ impl ProjectContext {
             name: name.to_string(),
             dependencies: HashMap::new(),
             dev_dependencies: HashMap::new(),
+            build_dependencies: HashMap::new(),
+            target_dependencies: HashMap::new(),
             template_files: HashMap::new(),
+            template_file_plugins: HashMap::new(),
             directories: Vec::new(),
             gitignore_entries: Vec::new(),
             readme_sections: Vec::new(),
             examples: HashMap::new(),
+            example_features: HashMap::new(),
+            example_plugins: HashMap::new(),
+            optional_features: false,
+            features: HashMap::new(),
+            optional_dependencies: HashMap::new(),
+            default_features: Vec::new(),
         }
     }
 
@@ -41,6 +67,60 @@ impl ProjectContext {
             .insert(name.to_string(), version.to_string());
     }
 
+    pub fn add_build_dependency(&mut self, name: &str, version: &str) {
+        self.build_dependencies
+            .insert(name.to_string(), version.to_string());
+    }
+
+    /// Add a dependency that only applies to `target`, such as `cfg(unix)`
+    pub fn add_target_dependency(&mut self, target: &str, name: &str, version: &str) {
+        self.target_dependencies
+            .entry(target.to_string())
+            .or_default()
+            .insert(name.to_string(), version.to_string());
+    }
+
+    /// Add a dependency that the Cargo feature `feature` enables
+    ///
+    /// Without [`optional_features`](Self::optional_features) this is an
+    /// ordinary dependency.
+    pub fn add_feature_dependency(&mut self, feature: &str, name: &str, version: &str) {
+        if !self.optional_features {
+            self.add_dependency(name, version);
+            return;
+        }
+        self.add_feature(feature, &[], true);
+        self.optional_dependencies
+            .entry(feature.to_string())
+            .or_default()
+            .insert(name.to_string(), version.to_string());
+    }
+
+    /// Declare a Cargo feature that enables `members`, optionally on by default
+    pub fn add_feature(&mut self, name: &str, members: &[&str], default: bool) {
+        let entry = self.features.entry(name.to_string()).or_default();
+        for member in members {
+            if !entry.iter().any(|m| m == member) {
+                entry.push(member.to_string());
+            }
+        }
+        if default && !self.default_features.iter().any(|f| f == name) {
+            self.default_features.push(name.to_string());
+        }
+    }
+
+    /// Wrap a module's source so it only compiles with `feature` enabled
+    ///
+    /// Returns the source unchanged without
+    /// [`optional_features`](Self::optional_features).
+    pub fn gate_module(&self, feature: &str, source: String) -> String {
+        if self.optional_features {
+            format!("#![cfg(feature = \"{}\")]\n\n{}", feature, source)
+        } else {
+            source
+        }
+    }
+
     pub fn add_template_file(&mut self, path: &str, content: String) {
         self.template_files.insert(path.to_string(), content);
     }
@@ -60,6 +140,15 @@ impl ProjectContext {
     pub fn add_example(&mut self, name: &str, code: String) {
         self.examples.insert(name.to_string(), code);
     }
+
+    /// Add an example that only builds with the given Cargo features enabled
+    pub fn add_example_with_features(&mut self, name: &str, code: String, features: &[&str]) {
+        self.add_example(name, code);
+        self.example_features.insert(
+            name.to_string(),
+            features.iter().map(|f| f.to_string()).collect(),
+        );
+    }
 }
 
 pub trait Plugin {
@@ -97,8 +186,24 @@ impl PluginManager {
 
     pub fn configure_all(&self, context: &mut ProjectContext) -> Result<(), Box<dyn Error>> {
         for plugin in &self.plugins {
-            println!("Configuring plugin: {}", plugin.name());
+            eprintln!("Configuring plugin: {}", plugin.name());
+            let existing: Vec<String> = context.template_files.keys().cloned().collect();
+            let existing_examples: Vec<String> = context.examples.keys().cloned().collect();
             plugin.configure(context)?;
+            for path in context.template_files.keys() {
+                if !existing.contains(path) {
+                    context
+                        .template_file_plugins
+                        .insert(path.clone(), plugin.name().to_string());
+                }
+            }
+            for name in context.examples.keys() {
+                if !existing_examples.contains(name) {
+                    context
+                        .example_plugins
+                        .insert(name.clone(), plugin.name().to_string());
+                }
+            }
         }
 
         for plugin in &self.plugins {
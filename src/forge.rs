@@ -1,5 +1,17 @@
-use crate::{Config, Generator, ProjectConfig, ProjectType};
+use crate::conflict::{Conflict, ConflictStrategy, Resolution};
+use crate::git::{self, GitConfig, GitIdentity, Vcs, VcsOptions};
+use crate::license::License;
+use crate::project_name::{self, NameError};
+use crate::templates::check::TemplateChecker;
+use crate::templates::filters;
+use crate::templates::manifest::{
+    resolve_variables, TemplateManifest, TemplateVariable, VariableType,
+};
+use crate::toolchain::{validate_rust_version, Edition};
+use crate::verify::{Verifier, VerifyStatus};
+use crate::{Config, Framework, GenerationPlan, Generator, ProjectConfig, ProjectType};
 use anyhow::{anyhow, Ok, Result};
+use chrono::{Datelike, Local};
 use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
 use inquire::{Confirm, MultiSelect, Select, Text};
@@ -20,8 +32,16 @@ pub struct ProjectContext {
     pub description: Option<String>,
     pub license: Option<String>,
     pub edition: String,
+    /// Minimum supported Rust version, written as `rust-version` and pinned in `rust-toolchain.toml`
+    pub rust_version: Option<String>,
+    /// Repository to set up once the project is generated
+    pub vcs: VcsOptions,
     pub target: Option<String>,
     pub esp32_chip: Option<String>,
+    /// Web framework of an API server; only set for API servers
+    pub framework: Option<Framework>,
+    /// Values for variables declared by custom template manifests
+    pub variables: HashMap<String, serde_json::Value>,
 }
 
 impl ProjectContext {
@@ -34,6 +54,17 @@ impl ProjectContext {
             serde_json::json!(self.project_type.to_string()),
         );
         context.insert("features".to_string(), serde_json::json!(self.features));
+        // The sqlx backend behind the database features; the last one wins, as
+        // its plugin writes `src/database.rs` last
+        let database_backend = self.features.iter().rev().find_map(|f| match f.as_str() {
+            "database" | "postgres" => Some("postgres"),
+            "sqlite" => Some("sqlite"),
+            "mysql" => Some("mysql"),
+            _ => None,
+        });
+        if let Some(backend) = database_backend {
+            context.insert("database_backend".to_string(), serde_json::json!(backend));
+        }
 
         if let Some(author) = &self.author {
             context.insert("author".to_string(), serde_json::json!(author));
@@ -42,8 +73,26 @@ impl ProjectContext {
             context.insert("description".to_string(), serde_json::json!(description));
         }
         if let Some(license) = &self.license {
-            context.insert("license".to_string(), serde_json::json!(license));
+            // Invalid expressions are reported by the generator; render them as given
+            match License::parse(license).ok() {
+                Some(parsed) => {
+                    let files: Vec<String> = parsed.files().into_iter().map(|f| f.path).collect();
+                    context.insert(
+                        "license".to_string(),
+                        serde_json::json!(parsed.expression()),
+                    );
+                    context.insert(
+                        "proprietary".to_string(),
+                        serde_json::json!(parsed.is_proprietary()),
+                    );
+                    context.insert("license_files".to_string(), serde_json::json!(files));
+                }
+                None => {
+                    context.insert("license".to_string(), serde_json::json!(license));
+                }
+            }
         }
+        context.insert("year".to_string(), serde_json::json!(Local::now().year()));
 
         if let Some(target) = &self.target {
             context.insert("target".to_string(), serde_json::json!(target));
@@ -54,9 +103,84 @@ impl ProjectContext {
         }
 
         context.insert("edition".to_string(), serde_json::json!(self.edition));
+        let edition: Edition = self.edition.parse().unwrap_or_default();
+        context.insert(
+            "resolver".to_string(),
+            serde_json::json!(edition.resolver()),
+        );
+        if let Some(rust_version) = &self.rust_version {
+            context.insert("msrv".to_string(), serde_json::json!(rust_version));
+        }
+
+        // Identifier forms of the project name used in generated code
+        let crate_name = filters::crate_ident(&self.name);
+        let struct_name = filters::pascal_case(&self.name);
+        context.insert("name".to_string(), serde_json::json!(self.name));
+        context.insert("crate_name".to_string(), serde_json::json!(crate_name));
+        context.insert("crate_ident".to_string(), serde_json::json!(crate_name));
+        context.insert("struct_name".to_string(), serde_json::json!(struct_name));
+        // The version READMEs ask for: the MSRV if one was chosen
+        let rust_version = match (&self.rust_version, edition) {
+            (Some(version), _) => version.as_str(),
+            (None, Edition::E2024) => edition.min_rust_version(),
+            (None, _) => "1.70",
+        };
+        context.insert("rust_version".to_string(), serde_json::json!(rust_version));
+
+        // Project-type specific defaults referenced by the templates
+        match self.project_type {
+            ProjectType::ApiServer => {
+                let framework = self.framework.unwrap_or_default();
+                context.insert(
+                    "framework".to_string(),
+                    serde_json::json!(framework.to_string()),
+                );
+                context.insert(
+                    "framework_name".to_string(),
+                    serde_json::json!(framework.display_name()),
+                );
+                context.insert("host".to_string(), serde_json::json!("0.0.0.0"));
+                context.insert("port".to_string(), serde_json::json!(3000));
+            }
+            ProjectType::WasmApp => {
+                context.insert("framework".to_string(), serde_json::json!("none"));
+                context.insert("port".to_string(), serde_json::json!(8080));
+            }
+            ProjectType::Embedded => {
+                context
+                    .entry("target".to_string())
+                    .or_insert_with(|| serde_json::json!("thumbv7em-none-eabihf"));
+            }
+            _ => {}
+        }
+
+        // Template variables may override any of the defaults above
+        for (name, value) in &self.variables {
+            context.insert(name.clone(), value.clone());
+        }
+
         context
     }
 
+    /// Build a context from a generator configuration
+    pub fn from_project_config(config: &ProjectConfig) -> Result<Self> {
+        Ok(Self {
+            name: config.name.clone(),
+            project_type: config.project_type.parse()?,
+            features: config.features.clone(),
+            author: Some(config.author.clone()),
+            description: config.description.clone(),
+            license: None,
+            edition: Edition::default().to_string(),
+            rust_version: None,
+            vcs: VcsOptions::default(),
+            target: config.target.clone(),
+            esp32_chip: config.esp32_chip.clone(),
+            framework: None,
+            variables: HashMap::new(),
+        })
+    }
+
     /// Convert to ProjectConfig for generator
     pub fn to_project_config(&self) -> ProjectConfig {
         ProjectConfig {
@@ -79,6 +203,18 @@ pub struct ForgeConfig {
     pub preferred_project_types: Vec<String>,
     pub default_features: HashMap<String, Vec<String>>,
     pub edition: Option<String>,
+    /// Version control for new projects when `--vcs` is not given
+    #[serde(default)]
+    pub vcs: Option<Vcs>,
+    /// First branch of new repositories, instead of git's `init.defaultBranch` or `main`
+    #[serde(default)]
+    pub default_branch: Option<String>,
+    /// Commit the generated files in new repositories
+    #[serde(default)]
+    pub initial_commit: bool,
+    /// Web framework for new API servers when `--framework` is not given
+    #[serde(default)]
+    pub framework: Option<Framework>,
 }
 
 impl Default for ForgeConfig {
@@ -89,6 +225,10 @@ impl Default for ForgeConfig {
             preferred_project_types: vec!["cli-tool".to_string()],
             default_features: HashMap::new(),
             edition: Some("2021".to_string()),
+            vcs: None,
+            default_branch: None,
+            initial_commit: false,
+            framework: None,
         }
     }
 }
@@ -133,9 +273,41 @@ impl ForgeConfig {
     }
 }
 
+/// Output format for `--dry-run`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Colored summary and directory tree
+    #[default]
+    Text,
+    /// Machine-readable list of every planned file
+    Json,
+}
+
+/// How a dry run reports the planned project
+#[derive(Debug, Clone, Default)]
+pub struct DryRunOptions {
+    pub format: OutputFormat,
+    /// Include each file's rendered contents in JSON output
+    pub include_contents: bool,
+    /// Also write the planned files into this scratch directory
+    pub output_dir: Option<PathBuf>,
+}
+
 pub struct Forge {
     base_path: PathBuf,
     config: Config,
+    variables: HashMap<String, String>,
+    dry_run: DryRunOptions,
+    on_conflict: ConflictStrategy,
+    optional_features: bool,
+    edition: Option<Edition>,
+    rust_version: Option<String>,
+    license: Option<String>,
+    framework: Option<Framework>,
+    vcs: Option<Vcs>,
+    initial_commit: bool,
+    verify: bool,
+    verify_online: bool,
 }
 
 impl Forge {
@@ -144,9 +316,93 @@ impl Forge {
         Self {
             base_path: base_path.as_ref().to_path_buf(),
             config,
+            variables: HashMap::new(),
+            dry_run: DryRunOptions::default(),
+            on_conflict: ConflictStrategy::default(),
+            optional_features: false,
+            edition: None,
+            rust_version: None,
+            license: None,
+            framework: None,
+            vcs: None,
+            initial_commit: false,
+            verify: false,
+            verify_online: false,
         }
     }
 
+    /// Set template variables passed on the command line with `--var key=value`
+    pub fn with_variables(mut self, variables: HashMap<String, String>) -> Self {
+        self.variables = variables;
+        self
+    }
+
+    /// Choose what `init` does with files that already exist
+    pub fn with_conflict_strategy(mut self, strategy: ConflictStrategy) -> Self {
+        self.on_conflict = strategy;
+        self
+    }
+
+    /// Put the selected features behind optional Cargo features
+    pub fn with_optional_features(mut self, enabled: bool) -> Self {
+        self.optional_features = enabled;
+        self
+    }
+
+    /// Use this edition instead of asking or reading it from the config file
+    pub fn with_edition(mut self, edition: Option<Edition>) -> Self {
+        self.edition = edition;
+        self
+    }
+
+    /// Set the minimum supported Rust version instead of asking for one
+    pub fn with_rust_version(mut self, rust_version: Option<String>) -> Self {
+        self.rust_version = rust_version;
+        self
+    }
+
+    /// Use this license instead of asking or reading it from the config file
+    pub fn with_license(mut self, license: Option<String>) -> Self {
+        self.license = license;
+        self
+    }
+
+    /// Build API servers on this framework instead of asking or reading it from the config file
+    pub fn with_framework(mut self, framework: Option<Framework>) -> Self {
+        self.framework = framework;
+        self
+    }
+
+    /// Set up this version control system instead of the configured one
+    pub fn with_vcs(mut self, vcs: Option<Vcs>) -> Self {
+        self.vcs = vcs;
+        self
+    }
+
+    /// Commit the generated files when a git repository is initialized
+    pub fn with_initial_commit(mut self, initial_commit: bool) -> Self {
+        self.initial_commit = initial_commit;
+        self
+    }
+
+    /// Check the generated project with `cargo` and by parsing its files
+    pub fn with_verify(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+
+    /// Let verification look dependencies up in the registry index
+    pub fn with_verify_online(mut self, online: bool) -> Self {
+        self.verify_online = online;
+        self
+    }
+
+    /// Choose how `run_dry_run` and `run_init_dry_run` report the planned project
+    pub fn with_dry_run_options(mut self, options: DryRunOptions) -> Self {
+        self.dry_run = options;
+        self
+    }
+
     pub fn run(&self) -> Result<()> {
         println!("{}", "Let's create your new Rust project.".bright_white());
 
@@ -193,6 +449,8 @@ impl Forge {
             (None, None)
         };
 
+        let framework = self.prompt_framework(project_type)?;
+
         // Feature selection based on project type
         let features = self.prompt_features(&project_type, target.clone())?;
 
@@ -201,22 +459,42 @@ impl Forge {
         let description = self.prompt_optional_field("Description", "A new Rust project")?;
         let license = self.prompt_license_with_config(&mut config)?;
 
-        // Save config if any choices were remembered
-        if config.remember_choices {
-            let _ = config.save_to_home(); // Ignore errors for user experience
-        }
+        let (edition, rust_version) = self.prompt_toolchain()?;
 
-        Ok(ProjectContext {
+        // Variables declared by custom template manifests
+        let variables = self.resolve_template_variables(project_type, true)?;
+        let vcs = self.prompt_vcs()?;
+
+        let mut context = ProjectContext {
             name,
             project_type,
             features,
             author,
             description,
             license,
-            edition: "2021".to_string(),
+            edition,
+            rust_version,
+            vcs,
             target,
             esp32_chip,
-        })
+            framework,
+            variables,
+        };
+
+        // Only now are the project's dependencies known to check the name against
+        while let Err(error) = self.check_dependency_clash(&context) {
+            context.name = match self.offer_suggestion(error)? {
+                Some(name) => name,
+                None => self.prompt_project_name()?,
+            };
+        }
+
+        // Save config if any choices were remembered
+        if config.remember_choices {
+            let _ = config.save_to_home(); // Ignore errors for user experience
+        }
+
+        Ok(context)
     }
 
     /// Prompt for Embedded Target selection
@@ -244,47 +522,40 @@ impl Forge {
                 .with_help_message("Must be a valid Rust package name (lowercase, no spaces)")
                 .prompt()?;
 
-            // Validate manually
-            if name.is_empty() {
-                eprintln!("{}", "❌ Project name cannot be empty".red());
-                continue;
-            }
-            if name.len() > 64 {
-                eprintln!(
-                    "{}",
-                    "❌ Project name is too long (max 64 characters)".red()
-                );
-                continue;
-            }
-            if name != name.to_lowercase() {
-                eprintln!("{}", "❌ Project name must be lowercase".red());
-                continue;
-            }
-            if name.starts_with(|c: char| c.is_numeric()) {
-                eprintln!("{}", "❌ Project name cannot start with a number".red());
-                continue;
-            }
-            if !name
-                .chars()
-                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
-            {
-                eprintln!(
-                    "{}",
-                    "❌ Project name can only contain letters, numbers, '-', and '_'".red()
-                );
-                continue;
+            if let Some(name) = self.check_name_or_suggest(&name)? {
+                return Ok(name);
             }
+        }
+    }
+
+    /// The name if it is valid, or the suggested fix if the user accepts it
+    fn check_name_or_suggest(&self, name: &str) -> Result<Option<String>> {
+        match project_name::validate(name) {
+            std::result::Result::Ok(()) => Ok(Some(name.to_string())),
+            Err(error) => self.offer_suggestion(error),
+        }
+    }
 
-            return Ok(name);
+    /// Report why a name was rejected, and the suggested fix if the user accepts it
+    fn offer_suggestion(&self, error: NameError) -> Result<Option<String>> {
+        eprintln!("{}", format!("❌ {}", error.message).red());
+        if let Some(suggestion) = error.suggestion {
+            let accept = Confirm::new(&format!("Use '{}' instead?", suggestion))
+                .with_default(true)
+                .prompt()?;
+            if accept {
+                return Ok(Some(suggestion));
+            }
         }
+        Ok(None)
     }
 
     /// Interactive project type selection
     fn prompt_project_type_interactive(&self) -> Result<ProjectType> {
-        let options = vec![
+        let options = [
             (
                 "API Server",
-                "RESTful API with Axum framework",
+                "RESTful API with Axum, actix-web, Poem or Rocket",
                 ProjectType::ApiServer,
             ),
             (
@@ -330,84 +601,59 @@ impl Forge {
         Ok(project_type)
     }
 
+    /// Ask which framework an API server is built on, unless it was given on the command line
+    fn prompt_framework(&self, project_type: ProjectType) -> Result<Option<Framework>> {
+        if project_type != ProjectType::ApiServer || self.framework.is_some() {
+            return self.framework_or(project_type, None);
+        }
+
+        let options: Vec<String> = Framework::ALL
+            .iter()
+            .map(|framework| format!("{} - {}", framework.display_name(), framework.description()))
+            .collect();
+        let selection = Select::new("Web framework:", options.clone())
+            .with_help_message("Decides the dependencies, router, middleware and tests")
+            .prompt()?;
+        let index = options
+            .iter()
+            .position(|option| *option == selection)
+            .ok_or_else(|| anyhow!("Invalid framework selection"))?;
+        Ok(Some(Framework::ALL[index]))
+    }
+
+    /// The framework from the command line, else the configured one, else Axum
+    ///
+    /// Only API servers have a framework, so one given on the command line for
+    /// any other project type is an error.
+    fn framework_or(
+        &self,
+        project_type: ProjectType,
+        configured: Option<Framework>,
+    ) -> Result<Option<Framework>> {
+        if project_type != ProjectType::ApiServer {
+            return match self.framework {
+                Some(framework) => Err(anyhow!(
+                    "--framework {} only applies to api-server projects, not {}",
+                    framework,
+                    project_type
+                )),
+                None => Ok(None),
+            };
+        }
+        Ok(Some(self.framework.or(configured).unwrap_or_default()))
+    }
+
     /// Prompt for features based on project type
     fn prompt_features(
         &self,
         project_type: &ProjectType,
         target: Option<String>,
     ) -> Result<Vec<String>> {
-        let available_features = match project_type {
-            ProjectType::ApiServer => vec![
-                ("axum", "Web framework", true),
-                ("tokio", "Async runtime", true),
-                ("serde", "Serialization", true),
-                ("tower", "Middleware framework", true),
-                ("sqlx", "SQL toolkit", false),
-                ("jwt", "JWT authentication", false),
-                ("cors", "CORS support", false),
-                ("tracing", "Structured logging", false),
-            ],
-            ProjectType::CliTool => vec![
-                ("clap", "CLI argument parsing", true),
-                ("anyhow", "Error handling", true),
-                ("env_logger", "Logging", true),
-                ("tokio", "Async runtime", false),
-                ("serde", "Serialization", false),
-                ("indicatif", "Progress bars", false),
-                ("colored", "Colored output", false),
-            ],
-            ProjectType::Library => vec![
-                ("serde", "Serialization", false),
-                ("thiserror", "Error types", false),
-                ("async-trait", "Async traits", false),
-                ("criterion", "Benchmarking", false),
-            ],
-            ProjectType::WasmApp => vec![
-                ("wasm-bindgen", "JS bindings", true),
-                ("web-sys", "Web APIs", true),
-                ("js-sys", "JS APIs", true),
-                ("wee_alloc", "Small allocator", false),
-                ("console_error_panic_hook", "Better panic messages", false),
-            ],
-            ProjectType::GameEngine => vec![
-                ("bevy", "Game engine framework", true),
-                ("audio", "Audio support", false),
-                ("networking", "Multiplayer networking", false),
-                ("physics", "Physics simulation", false),
-                ("ui", "UI framework", false),
-            ],
-            ProjectType::Embedded => {
-                // Handle different Embedded Targets
-                match target {
-                    Some(target_str) if target_str == "esp32" => {
-                        return Ok(Vec::new());
-                    }
-                    _ => {
-                        // Show Cortex-M specific features
-                        vec![
-                            ("cortex-m", "ARM Cortex-M support", true),
-                            ("cortex-m-rt", "Runtime support", true),
-                            ("panic-halt", "Halt on panic", true),
-                            ("panic-rtt", "RTT panic messages", false),
-                            ("rtt", "Real-time transfer debugging", false),
-                            ("semihosting", "Semihosting debug output", false),
-                            ("stm32f4", "STM32F4 HAL", false),
-                            ("stm32f1", "STM32F1 HAL", false),
-                            ("rp2040", "Raspberry Pi Pico support", false),
-                        ]
-                    }
-                }
-            }
-            ProjectType::Workspace => vec![
-                ("tokio", "Async runtime", true),
-                ("serde", "Serialization", true),
-                ("anyhow", "Error handling", true),
-                ("database", "Database support", false),
-                ("web", "Web framework", false),
-                ("clap", "CLI support", false),
-                ("testing", "Advanced testing", false),
-            ],
-        };
+        // ESP32 projects are generated by esp-generate, which has its own options
+        if *project_type == ProjectType::Embedded && target.as_deref() == Some("esp32") {
+            return Ok(Vec::new());
+        }
+        let available_features = project_type.available_features();
 
         let _default_features: Vec<String> = available_features
             .iter()
@@ -445,6 +691,49 @@ impl Forge {
         Ok(features)
     }
 
+    /// Prompt for the edition and MSRV unless they were given on the command line
+    fn prompt_toolchain(&self) -> Result<(String, Option<String>)> {
+        let edition = match self.edition {
+            Some(edition) => edition,
+            None => {
+                let default = Edition::ALL
+                    .iter()
+                    .position(|edition| *edition == Edition::default())
+                    .unwrap_or(0);
+                Select::new("Rust edition:", Edition::ALL.to_vec())
+                    .with_starting_cursor(default)
+                    .prompt()?
+            }
+        };
+
+        if self.rust_version.is_some() {
+            return Ok((edition.to_string(), self.rust_version.clone()));
+        }
+        loop {
+            let rust_version = Text::new("Minimum supported Rust version (optional):")
+                .with_placeholder(edition.min_rust_version())
+                .with_help_message("Leave empty to not pin a toolchain")
+                .prompt()?;
+            let rust_version = rust_version.trim();
+            if rust_version.is_empty() {
+                return Ok((edition.to_string(), None));
+            }
+            if let Err(e) = validate_rust_version(rust_version, edition) {
+                eprintln!("{}", format!("❌ {}", e).red());
+                continue;
+            }
+            return Ok((edition.to_string(), Some(rust_version.to_string())));
+        }
+    }
+
+    /// The edition from the command line, else the configured one, else the default
+    fn edition_or(&self, configured: Option<String>) -> String {
+        match self.edition {
+            Some(edition) => edition.to_string(),
+            None => configured.unwrap_or_else(|| Edition::default().to_string()),
+        }
+    }
+
     /// Prompt for optional fields
     fn prompt_optional_field(&self, field_name: &str, placeholder: &str) -> Result<Option<String>> {
         let include = Confirm::new(&format!("Include {}?", field_name.to_lowercase()))
@@ -461,50 +750,206 @@ impl Forge {
         }
     }
 
+    /// Prompt for the author, offering the identity from git first
+    fn prompt_author(&self) -> Result<Option<String>> {
+        if let Some(author) = self.git_author() {
+            let use_git = Confirm::new(&format!("Use git author '{}'?", author))
+                .with_default(true)
+                .prompt()?;
+            if use_git {
+                return Ok(Some(author));
+            }
+        }
+        self.prompt_optional_field("Author", "Name <email>")
+    }
+
+    /// `Name <email>` from the git configuration, if a name is set
+    fn git_author(&self) -> Option<String> {
+        GitIdentity::detect(&self.base_path).author()
+    }
+
+    /// Repository settings from the command line, then `config`, then git's own config
+    fn vcs_options(&self, config: &ForgeConfig) -> VcsOptions {
+        let branch = config.default_branch.clone().or_else(|| {
+            GitConfig::detect(&self.base_path)
+                .get("init.defaultBranch")
+                .map(String::from)
+        });
+        VcsOptions {
+            vcs: self.vcs.or(config.vcs).unwrap_or_default(),
+            branch: branch.unwrap_or_else(|| VcsOptions::default().branch),
+            initial_commit: self.initial_commit || config.initial_commit,
+        }
+    }
+
+    /// Ask whether to initialize a git repository, unless `--vcs` was given
+    fn prompt_vcs(&self) -> Result<VcsOptions> {
+        let mut options = self.vcs_options(&ForgeConfig::load().unwrap_or_default());
+        if self.vcs.is_none() {
+            let init = Confirm::new("Initialize a git repository?")
+                .with_default(options.vcs == Vcs::Git)
+                .prompt()?;
+            options.vcs = if init { Vcs::Git } else { Vcs::None };
+        }
+        Ok(options)
+    }
+
+    /// Set up version control in a freshly generated project
+    ///
+    /// Nothing happens inside an existing work tree. Failures are reported
+    /// without failing the run, since the project itself was created.
+    fn init_vcs(&self, context: &ProjectContext, dir: &Path) {
+        if context.vcs.vcs != Vcs::Git {
+            return;
+        }
+        if git::find_git_dir(dir).is_some() {
+            println!(
+                "{}",
+                "ℹ️  Already inside a git repository, skipping git init".bright_black()
+            );
+            return;
+        }
+
+        match git::init_repository(dir, &context.vcs, context.author.as_deref()) {
+            std::result::Result::Ok(()) => {
+                let committed = if context.vcs.initial_commit {
+                    " with an initial commit"
+                } else {
+                    ""
+                };
+                println!(
+                    "{} Initialized git repository on branch {}{}",
+                    "✓".bright_green().bold(),
+                    context.vcs.branch.bright_white(),
+                    committed
+                );
+            }
+            Err(e) => eprintln!(
+                "{}",
+                format!("⚠️  Could not initialize a git repository: {}", e).yellow()
+            ),
+        }
+    }
+
     /// Prompt for license selection
     fn prompt_license(&self) -> Result<Option<String>> {
+        if self.license.is_some() {
+            return Ok(self.license.clone());
+        }
+
         let include_license = Confirm::new("Include license?")
             .with_default(true)
             .prompt()?;
 
         if include_license {
-            let licenses = vec![
-                "MIT",
-                "Apache-2.0",
-                "GPL-3.0",
-                "BSD-3-Clause",
-                "Unlicense",
-                "Other",
-            ];
-            let license = Select::new("License:", licenses).prompt()?;
-
-            if license == "Other" {
-                let custom = Text::new("Custom license:")
-                    .with_placeholder("AGPL-3.0")
-                    .prompt()?;
-                Ok(Some(custom))
-            } else {
-                Ok(Some(license.to_string()))
-            }
+            self.select_license().map(Some)
         } else {
             Ok(None)
         }
     }
 
+    /// Pick a license from the bundled ones, or type any SPDX expression
+    fn select_license(&self) -> Result<String> {
+        let licenses = vec![
+            "MIT",
+            "Apache-2.0",
+            "MIT OR Apache-2.0",
+            "BSD-3-Clause",
+            "MPL-2.0",
+            "GPL-3.0",
+            "Unlicense",
+            "Proprietary",
+            "Other",
+        ];
+        let license = Select::new("License:", licenses).prompt()?;
+        if license != "Other" {
+            return Ok(License::parse(license)?.to_string());
+        }
+
+        loop {
+            let custom = Text::new("SPDX license expression:")
+                .with_placeholder("AGPL-3.0")
+                .prompt()?;
+            match License::parse(&custom) {
+                std::result::Result::Ok(license) => return Ok(license.to_string()),
+                Err(e) => eprintln!("{}", format!("❌ {}", e).red()),
+            }
+        }
+    }
+
+    /// Resolve values for the variables declared by custom template manifests
+    fn resolve_template_variables(
+        &self,
+        project_type: ProjectType,
+        interactive: bool,
+    ) -> Result<HashMap<String, serde_json::Value>> {
+        let manifests =
+            TemplateManifest::discover(&self.config.custom_template_dirs, project_type)?;
+        resolve_variables(&manifests, &self.variables, |name, variable| {
+            if interactive {
+                self.prompt_template_variable(name, variable).map(Some)
+            } else {
+                Ok(None)
+            }
+        })
+    }
+
+    /// Prompt for a single template variable
+    fn prompt_template_variable(&self, name: &str, variable: &TemplateVariable) -> Result<String> {
+        let message = format!("{}:", variable.prompt_text(name));
+        let default = variable.default_value(name)?.map(|value| match value {
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        });
+
+        if !variable.choices.is_empty() {
+            let cursor = default
+                .as_ref()
+                .and_then(|d| variable.choices.iter().position(|c| c == d))
+                .unwrap_or(0);
+            let choice = Select::new(&message, variable.choices.clone())
+                .with_starting_cursor(cursor)
+                .prompt()?;
+            return Ok(choice);
+        }
+
+        if variable.var_type == VariableType::Bool {
+            let answer = Confirm::new(&message)
+                .with_default(default.as_deref() == Some("true"))
+                .prompt()?;
+            return Ok(answer.to_string());
+        }
+
+        loop {
+            let mut prompt = Text::new(&message);
+            if let Some(default) = &default {
+                prompt = prompt.with_default(default);
+            }
+            let answer = prompt.prompt()?;
+
+            match variable.parse_value(name, &answer) {
+                std::result::Result::Ok(_) => return Ok(answer),
+                Err(e) => eprintln!("{}", format!("❌ {}", e).red()),
+            }
+        }
+    }
+
     /// Prompt for author with config defaults and remember choice functionality
     fn prompt_author_with_config(&self, config: &mut Config) -> Result<Option<String>> {
-        // Use config default if available
-        let default_author = config.default_author.as_deref();
+        // Use config default if available, else the identity from git
+        let (default_author, source) = match &config.default_author {
+            Some(author) => (Some(author.clone()), "saved"),
+            None => (self.git_author(), "git"),
+        };
 
-        let include = if default_author.is_some() {
-            // If we have a config default, ask if they want to use it or change it
-            let use_default =
-                Confirm::new(&format!("Use saved author '{}'?", default_author.unwrap()))
-                    .with_default(true)
-                    .prompt()?;
+        let include = if let Some(default_author) = &default_author {
+            // If we have a default, ask if they want to use it or change it
+            let use_default = Confirm::new(&format!("Use {} author '{}'?", source, default_author))
+                .with_default(true)
+                .prompt()?;
 
             if use_default {
-                return Ok(config.default_author.clone());
+                return Ok(Some(default_author.clone()));
             } else {
                 true // They want to change it, so include author field
             }
@@ -517,7 +962,7 @@ impl Forge {
 
         if include {
             let author = Text::new("Author:")
-                .with_placeholder("your-name")
+                .with_placeholder("Name <email>")
                 .prompt()?;
 
             // Ask if they want to remember this choice
@@ -539,17 +984,18 @@ impl Forge {
 
     /// Prompt for license with config defaults and remember choice functionality
     fn prompt_license_with_config(&self, config: &mut Config) -> Result<Option<String>> {
+        if self.license.is_some() {
+            return Ok(self.license.clone());
+        }
+
         // Use config default if available
         let default_license = config.default_license.as_deref();
 
-        let include_license = if default_license.is_some() {
+        let include_license = if let Some(default_license) = default_license {
             // If we have a config default, ask if they want to use it or change it
-            let use_default = Confirm::new(&format!(
-                "Use saved license '{}'?",
-                default_license.unwrap()
-            ))
-            .with_default(true)
-            .prompt()?;
+            let use_default = Confirm::new(&format!("Use saved license '{}'?", default_license))
+                .with_default(true)
+                .prompt()?;
 
             if use_default {
                 return Ok(config.default_license.clone());
@@ -564,24 +1010,7 @@ impl Forge {
         };
 
         if include_license {
-            let licenses = vec![
-                "MIT",
-                "Apache-2.0",
-                "GPL-3.0",
-                "BSD-3-Clause",
-                "Unlicense",
-                "Other",
-            ];
-            let license = Select::new("License:", licenses).prompt()?;
-
-            let final_license = if license == "Other" {
-                let custom = Text::new("Custom license:")
-                    .with_placeholder("AGPL-3.0")
-                    .prompt()?;
-                custom
-            } else {
-                license.to_string()
-            };
+            let final_license = self.select_license()?;
 
             // Ask if they want to remember this choice
             if config.remember_choices {
@@ -602,6 +1031,8 @@ impl Forge {
 
     /// Create the project with progress indicators
     fn create_project(&self, context: ProjectContext) -> Result<()> {
+        self.check_dependency_clash(&context)?;
+
         // Special handling for ESP32 projects - don't create directory structure first
         if let Some(target) = &context.target {
             if target == "esp32" {
@@ -621,7 +1052,9 @@ impl Forge {
 
                 // For ESP32, use parent directory and let esp-generate create the project directory
                 let config = context.to_project_config();
-                let generator = Generator::new();
+                let generator = Generator::new()
+                    .with_template_dirs(self.config.custom_template_dirs.clone())
+                    .with_variables(context.variables.clone());
                 generator.generate(&config, &self.base_path)?; // Use base_path, not project_path
 
                 pb.set_position(100);
@@ -640,14 +1073,6 @@ impl Forge {
         // For non-ESP32 projects, continue with normal flow
         let project_path = self.base_path.join(&context.name);
 
-        // Check if directory already exists (only for non-ESP32 projects)
-        if project_path.exists() {
-            return Err(anyhow!(
-                "Project directory already exists: {}",
-                context.name
-            ));
-        }
-
         println!("{}", "🔨 Creating your project...".bright_yellow());
 
         // Progress bar for project generation
@@ -660,16 +1085,11 @@ impl Forge {
         );
         pb.set_prefix("Progress");
 
-        // Initialize project structure
-        pb.set_message("Creating project directory...");
-        std::fs::create_dir_all(&project_path)?;
-        pb.set_position(20);
-        std::thread::sleep(Duration::from_millis(100));
-
-        // Generate project using generator
+        // Generate project using generator; the directory only appears once
+        // every file has been written
         pb.set_message("Generating project files...");
         let config = context.to_project_config();
-        let generator = Generator::new();
+        let generator = self.generator_for(&context)?;
 
         // Simulate progress during generation
         pb.set_position(40);
@@ -689,6 +1109,8 @@ impl Forge {
             "✅".bright_green().bold(),
             "Project created successfully!".bright_green()
         );
+        self.init_vcs(&context, &project_path);
+        self.verify_project(&project_path)?;
         self.show_next_steps(&context, false)?;
         Ok(())
     }
@@ -708,69 +1130,24 @@ impl Forge {
     }
 
     pub fn validate_project_name(&self, name: &str) -> Result<()> {
-        if name.is_empty() {
-            return Err(anyhow!("Project name cannot be empty"));
-        }
-
-        // Check length
-        if name.len() > 64 {
-            return Err(anyhow!("Project name is too long (max 64 characters)"));
-        }
-
-        // Check for reserved names
-        let reserved_names = [
-            "test", "main", "build", "cargo", "rust", "src", "target", "bin", "lib",
-        ];
-        if reserved_names.contains(&name) {
-            return Err(anyhow!("'{}' is a reserved name", name));
-        }
-
-        // Check for invalid characters and patterns
-        if name.contains(' ') {
-            return Err(anyhow!("Project name cannot contain spaces"));
-        }
-
-        if name.contains('/') || name.contains('\\') {
-            return Err(anyhow!("Project name cannot contain slashes"));
-        }
-
-        // Must be lowercase
-        if name != name.to_lowercase() {
-            return Err(anyhow!("Project name must be lowercase"));
-        }
-
-        // Cannot start with a number
-        if name.starts_with(|c: char| c.is_numeric()) {
-            return Err(anyhow!("Project name cannot start with a number"));
-        }
-
-        // Cannot start or end with dash/underscore
-        if name.starts_with('-') || name.starts_with('_') {
-            return Err(anyhow!("Project name cannot start with '-' or '_'"));
-        }
-
-        if name.ends_with('-') || name.ends_with('_') {
-            return Err(anyhow!("Project name cannot end with '-' or '_'"));
-        }
-
-        // Check for valid characters (alphanumeric, dash, underscore)
-        if !name
-            .chars()
-            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
-        {
-            return Err(anyhow!(
-                "Project name can only contain letters, numbers, '-', and '_'"
-            ));
-        }
+        project_name::validate(name)?;
+        Ok(())
+    }
 
-        // Check for double dashes or underscores
-        if name.contains("--") || name.contains("__") {
-            return Err(anyhow!(
-                "Project name cannot contain consecutive dashes or underscores"
-            ));
+    /// Reject a name that clashes with a dependency of the project `context` describes
+    ///
+    /// Any other problem with the project is left for generation to report.
+    fn check_dependency_clash(&self, context: &ProjectContext) -> Result<(), NameError> {
+        let plan = self
+            .generator_for(context)
+            .and_then(|generator| generator.plan(&context.to_project_config()));
+        match plan {
+            Err(error) => match error.downcast::<NameError>() {
+                std::result::Result::Ok(error) => Err(error),
+                Err(_) => std::result::Result::Ok(()),
+            },
+            _ => std::result::Result::Ok(()),
         }
-
-        Ok(())
     }
 
     /// Run in non-interactive mode with defaults
@@ -798,6 +1175,7 @@ impl Forge {
         let project_type_str = project_type.unwrap_or_else(|| "cli-tool".to_string());
         let project_type = self.parse_project_type(&project_type_str)?;
 
+        let vcs = self.vcs_options(&config);
         let context = ProjectContext {
             name: project_name,
             project_type,
@@ -806,12 +1184,18 @@ impl Forge {
                 .get(&project_type_str)
                 .cloned()
                 .unwrap_or_default(),
-            author: author.or(config.default_author),
+            author: author
+                .or(config.default_author)
+                .or_else(|| self.git_author()),
             description,
-            license: config.default_license,
-            edition: config.edition.unwrap_or_else(|| "2021".to_string()),
+            license: self.license.clone().or(config.default_license),
+            edition: self.edition_or(config.edition),
+            rust_version: self.rust_version.clone(),
+            vcs,
             target: None,
             esp32_chip: None,
+            framework: self.framework_or(project_type, config.framework)?,
+            variables: self.resolve_template_variables(project_type, false)?,
         };
 
         self.create_project(context)?;
@@ -834,16 +1218,21 @@ impl Forge {
         let project_type_str = project_type.ok_or_else(|| anyhow!("Project type is required"))?;
         let project_type = self.parse_project_type(&project_type_str)?;
 
+        let vcs = self.vcs_options(&ForgeConfig::load().unwrap_or_default());
         let context = ProjectContext {
             name: project_name,
             project_type,
             features: vec![], // Default features
-            author,
+            author: author.or_else(|| self.git_author()),
             description,
-            license: Some("MIT".to_string()),
-            edition: "2021".to_string(),
+            license: self.license.clone().or_else(|| Some("MIT".to_string())),
+            edition: self.edition_or(None),
+            rust_version: self.rust_version.clone(),
+            vcs,
             target: None,
             esp32_chip: None,
+            framework: self.framework_or(project_type, None)?,
+            variables: self.resolve_template_variables(project_type, false)?,
         };
 
         self.create_project(context)?;
@@ -873,6 +1262,7 @@ impl Forge {
             .unwrap_or_else(|| "cli-tool".to_string());
         let project_type = self.parse_project_type(&project_type_str)?;
 
+        let vcs = self.vcs_options(&config);
         let context = ProjectContext {
             name: project_name,
             project_type,
@@ -881,12 +1271,18 @@ impl Forge {
                 .get(&project_type_str)
                 .cloned()
                 .unwrap_or_default(),
-            author: author.or(config.default_author),
+            author: author
+                .or(config.default_author)
+                .or_else(|| self.git_author()),
             description,
-            license: config.default_license,
-            edition: config.edition.unwrap_or_else(|| "2021".to_string()),
+            license: self.license.clone().or(config.default_license),
+            edition: self.edition_or(config.edition),
+            rust_version: self.rust_version.clone(),
+            vcs,
             target: None,
             esp32_chip: None,
+            framework: self.framework_or(project_type, config.framework)?,
+            variables: self.resolve_template_variables(project_type, false)?,
         };
 
         self.create_project(context)?;
@@ -918,6 +1314,7 @@ impl Forge {
             let project_type_str = project_type.unwrap_or_else(|| "cli-tool".to_string());
             let project_type = self.parse_project_type(&project_type_str)?;
 
+            let vcs = self.vcs_options(&config);
             let context = ProjectContext {
                 name: project_name,
                 project_type,
@@ -926,12 +1323,18 @@ impl Forge {
                     .get(&project_type_str)
                     .cloned()
                     .unwrap_or_default(),
-                author: author.or(config.default_author),
+                author: author
+                    .or(config.default_author)
+                    .or_else(|| self.git_author()),
                 description,
-                license: config.default_license,
-                edition: config.edition.unwrap_or_else(|| "2021".to_string()),
+                license: self.license.clone().or(config.default_license),
+                edition: self.edition_or(config.edition),
+                rust_version: self.rust_version.clone(),
+                vcs,
                 target: None,
                 esp32_chip: None,
+                framework: self.framework_or(project_type, config.framework)?,
+                variables: self.resolve_template_variables(project_type, false)?,
             };
 
             self.preview_project(&context)
@@ -967,9 +1370,13 @@ impl Forge {
             .unwrap_or("my-project")
             .to_string();
 
+        // The directory name becomes the crate name, so it has to be a valid one
+        self.validate_project_name(&project_name)?;
+
         let project_type_str = project_type.unwrap_or_else(|| "cli-tool".to_string());
         let project_type = self.parse_project_type(&project_type_str)?;
 
+        let vcs = self.vcs_options(&config);
         let context = ProjectContext {
             name: project_name,
             project_type,
@@ -978,12 +1385,16 @@ impl Forge {
                 .get(&project_type_str)
                 .cloned()
                 .unwrap_or_default(),
-            author: config.default_author,
+            author: config.default_author.or_else(|| self.git_author()),
             description: None,
-            license: config.default_license,
-            edition: config.edition.unwrap_or_else(|| "2021".to_string()),
+            license: self.license.clone().or(config.default_license),
+            edition: self.edition_or(config.edition),
+            rust_version: self.rust_version.clone(),
+            vcs,
             target: None,
             esp32_chip: None,
+            framework: self.framework_or(project_type, config.framework)?,
+            variables: self.resolve_template_variables(project_type, false)?,
         };
 
         self.init_project_in_current_dir(context)?;
@@ -1005,6 +1416,15 @@ impl Forge {
             .and_then(|name| name.to_str())
             .unwrap_or("my-project")
             .to_string();
+        let project_name = match self.check_name_or_suggest(&project_name)? {
+            Some(name) => name,
+            None => {
+                return Err(anyhow!(
+                    "'{}' cannot be used as a project name",
+                    project_name
+                ))
+            }
+        };
 
         let project_type = if let Some(pt) = project_type {
             self.parse_project_type(&pt)?
@@ -1012,23 +1432,42 @@ impl Forge {
             self.prompt_project_type_interactive()?
         };
 
+        let framework = self.prompt_framework(project_type)?;
         let features = self.prompt_features(&project_type, None)?;
-        let author = self.prompt_optional_field("Author", "your-name")?;
+        let author = self.prompt_author()?;
         let description = self.prompt_optional_field("Description", "A new Rust project")?;
         let license = self.prompt_license()?;
+        let (edition, rust_version) = self.prompt_toolchain()?;
 
-        let context = ProjectContext {
+        let vcs = self.prompt_vcs()?;
+        let mut context = ProjectContext {
             name: project_name,
             project_type,
             features,
             author,
             description,
             license,
-            edition: "2021".to_string(),
+            edition,
+            rust_version,
+            vcs,
             target: None,
             esp32_chip: None,
+            framework,
+            variables: self.resolve_template_variables(project_type, true)?,
         };
 
+        while let Err(error) = self.check_dependency_clash(&context) {
+            context.name = match self.offer_suggestion(error)? {
+                Some(name) => name,
+                None => {
+                    return Err(anyhow!(
+                        "'{}' cannot be used as a project name",
+                        context.name
+                    ))
+                }
+            };
+        }
+
         self.init_project_in_current_dir(context)?;
         Ok(())
     }
@@ -1050,11 +1489,14 @@ impl Forge {
             .unwrap_or("my-project")
             .to_string();
 
+        self.validate_project_name(&project_name)?;
+
         let project_type_str = project_type
             .or_else(|| config.preferred_project_types.first().cloned())
             .unwrap_or_else(|| "cli-tool".to_string());
         let project_type = self.parse_project_type(&project_type_str)?;
 
+        let vcs = self.vcs_options(&config);
         let context = ProjectContext {
             name: project_name,
             project_type,
@@ -1063,12 +1505,16 @@ impl Forge {
                 .get(&project_type_str)
                 .cloned()
                 .unwrap_or_default(),
-            author: config.default_author,
+            author: config.default_author.or_else(|| self.git_author()),
             description: None,
-            license: config.default_license,
-            edition: config.edition.unwrap_or_else(|| "2021".to_string()),
+            license: self.license.clone().or(config.default_license),
+            edition: self.edition_or(config.edition),
+            rust_version: self.rust_version.clone(),
+            vcs,
             target: None,
             esp32_chip: None,
+            framework: self.framework_or(project_type, config.framework)?,
+            variables: self.resolve_template_variables(project_type, false)?,
         };
 
         self.init_project_in_current_dir(context)?;
@@ -1089,6 +1535,8 @@ impl Forge {
             .unwrap_or("my-project")
             .to_string();
 
+        self.validate_project_name(&project_name)?;
+
         if non_interactive {
             let config = if let Some(config_path) = from_config {
                 ForgeConfig::load_from(config_path)?
@@ -1099,6 +1547,7 @@ impl Forge {
             let project_type_str = project_type.unwrap_or_else(|| "cli-tool".to_string());
             let project_type = self.parse_project_type(&project_type_str)?;
 
+            let vcs = self.vcs_options(&config);
             let context = ProjectContext {
                 name: project_name,
                 project_type,
@@ -1107,12 +1556,16 @@ impl Forge {
                     .get(&project_type_str)
                     .cloned()
                     .unwrap_or_default(),
-                author: config.default_author,
+                author: config.default_author.or_else(|| self.git_author()),
                 description: None,
-                license: config.default_license,
-                edition: config.edition.unwrap_or_else(|| "2021".to_string()),
+                license: self.license.clone().or(config.default_license),
+                edition: self.edition_or(config.edition),
+                rust_version: self.rust_version.clone(),
+                vcs,
                 target: None,
                 esp32_chip: None,
+                framework: self.framework_or(project_type, config.framework)?,
+                variables: self.resolve_template_variables(project_type, false)?,
             };
 
             self.preview_init(&context)
@@ -1123,11 +1576,14 @@ impl Forge {
                 self.prompt_project_type_interactive()?
             };
 
+            let framework = self.prompt_framework(project_type)?;
             let features = self.prompt_features(&project_type, None)?;
-            let author = self.prompt_optional_field("Author", "your-name")?;
+            let author = self.prompt_author()?;
             let description = self.prompt_optional_field("Description", "A new Rust project")?;
             let license = self.prompt_license()?;
+            let (edition, rust_version) = self.prompt_toolchain()?;
 
+            let vcs = self.vcs_options(&ForgeConfig::load().unwrap_or_default());
             let context = ProjectContext {
                 name: project_name,
                 project_type,
@@ -1135,15 +1591,100 @@ impl Forge {
                 author,
                 description,
                 license,
-                edition: "2021".to_string(),
+                edition,
+                rust_version,
+                vcs,
                 target: None,
                 esp32_chip: None,
+                framework,
+                variables: self.resolve_template_variables(project_type, true)?,
             };
 
             self.preview_init(&context)
         }
     }
 
+    /// Render every template across a feature matrix and report files that fail to parse
+    pub fn run_template_check(&self, project_type: Option<String>) -> Result<()> {
+        let mut checker = TemplateChecker::new()
+            .with_template_dirs(self.config.custom_template_dirs.clone())
+            .with_variables(self.variables.clone());
+        if let Some(project_type) = project_type {
+            checker = checker.with_project_types(vec![self.parse_project_type(&project_type)?]);
+        }
+
+        println!("{}", "🔍 Checking templates...".bright_cyan().bold());
+        let report = checker.run()?;
+
+        for failure in &report.failures {
+            println!("  {} {}", "✗".bright_red(), failure);
+        }
+
+        let summary = format!(
+            "{} files checked across {} feature sets",
+            report.files_checked, report.combinations
+        );
+        if !report.is_clean() {
+            return Err(anyhow!(
+                "{} template problem(s) found ({})",
+                report.failures.len(),
+                summary
+            ));
+        }
+
+        println!("{} {}", "✅ All templates passed:".bright_green(), summary);
+        Ok(())
+    }
+
+    /// With `--verify`, check the generated project and print a table of the results
+    fn verify_project(&self, dir: &Path) -> Result<()> {
+        if !self.verify {
+            return Ok(());
+        }
+
+        println!(
+            "\n{}",
+            "🔍 Verifying the generated project...".bright_cyan().bold()
+        );
+        let report = Verifier::new().with_online(self.verify_online).run(dir);
+        let width = report
+            .checks
+            .iter()
+            .map(|check| check.name.len())
+            .max()
+            .unwrap_or(0);
+        for check in &report.checks {
+            let name = format!("{:width$}", check.name, width = width);
+            match &check.status {
+                VerifyStatus::Passed => {
+                    println!(
+                        "  {} {}  {}",
+                        "✓".bright_green(),
+                        name,
+                        "pass".bright_green()
+                    )
+                }
+                VerifyStatus::Skipped(reason) => println!(
+                    "  {} {}  {}",
+                    "-".bright_black(),
+                    name,
+                    format!("skipped: {}", reason).bright_black()
+                ),
+                VerifyStatus::Failed(output) => {
+                    println!("  {} {}  {}", "✗".bright_red(), name, "fail".bright_red());
+                    for line in output.lines() {
+                        println!("      {}", line.bright_black());
+                    }
+                }
+            }
+        }
+
+        if !report.passed() {
+            return Err(anyhow!("The generated project failed verification"));
+        }
+        Ok(())
+    }
+
     /// Helper method to parse project type string
     fn parse_project_type(&self, project_type_str: &str) -> Result<ProjectType> {
         match project_type_str.to_lowercase().as_str() {
@@ -1160,6 +1701,11 @@ impl Forge {
 
     /// Preview project structure without creating files
     fn preview_project(&self, context: &ProjectContext) -> Result<()> {
+        let plan = self.plan_preview(context)?;
+        if self.dry_run.format == OutputFormat::Json {
+            return self.print_plan_json(context, &plan);
+        }
+
         println!("\n{}", "📋 Project Preview".bright_white().bold());
         println!("{}", "─".repeat(50).bright_black());
 
@@ -1173,6 +1719,13 @@ impl Forge {
             "🏗️  Type:".bright_cyan(),
             context.project_type.to_string().bright_white()
         );
+        if let Some(framework) = context.framework {
+            println!(
+                "{} {}",
+                "🧱 Framework:".bright_cyan(),
+                framework.display_name().bright_white()
+            );
+        }
 
         if let Some(author) = &context.author {
             println!("{} {}", "👤 Author:".bright_cyan(), author.bright_white());
@@ -1209,7 +1762,7 @@ impl Forge {
         }
 
         println!("\n{}", "📁 Directory Structure:".bright_white().bold());
-        self.preview_directory_structure(context);
+        self.preview_directory_structure(context, &plan);
 
         println!(
             "\n{}",
@@ -1224,6 +1777,11 @@ impl Forge {
 
     /// Preview init structure
     fn preview_init(&self, context: &ProjectContext) -> Result<()> {
+        let plan = self.plan_preview(context)?;
+        if self.dry_run.format == OutputFormat::Json {
+            return self.print_plan_json(context, &plan);
+        }
+
         println!("\n{}", "📋 Initialization Preview".bright_white().bold());
         println!("{}", "─".repeat(50).bright_black());
 
@@ -1264,7 +1822,7 @@ impl Forge {
         }
 
         println!("\n{}", "📁 Files to be created:".bright_white().bold());
-        self.preview_directory_structure(context);
+        self.preview_directory_structure(context, &plan);
 
         println!(
             "\n{}",
@@ -1276,47 +1834,79 @@ impl Forge {
         Ok(())
     }
 
-    /// Preview directory structure
-    fn preview_directory_structure(&self, context: &ProjectContext) {
-        println!("  {}/", context.name.bright_yellow());
-        println!("  ├── {}", "Cargo.toml".bright_green());
-        println!("  ├── {}", "README.md".bright_green());
-
-        if context.license.is_some() {
-            println!("  ├── {}", "LICENSE".bright_green());
-        }
-
-        println!("  ├── {}/ ", "src".bright_blue());
+    /// A generator for `context` with the options this forge was built with
+    fn generator_for(&self, context: &ProjectContext) -> Result<Generator> {
+        Ok(Generator::new()
+            .with_template_dirs(self.config.custom_template_dirs.clone())
+            .with_variables(context.variables.clone())
+            .with_optional_features(self.optional_features)
+            .with_edition(context.edition.parse()?)
+            .with_rust_version(context.rust_version.clone())
+            .with_license(context.license.clone())
+            .with_framework(context.framework))
+    }
 
-        match context.project_type {
-            ProjectType::Library => {
-                println!("  │   └── {}", "lib.rs".bright_green());
-            }
-            _ => {
-                println!("  │   └── {}", "main.rs".bright_green());
+    /// Plan the project for a dry run, writing it to the scratch directory if one was given
+    fn plan_preview(&self, context: &ProjectContext) -> Result<GenerationPlan> {
+        let plan = self
+            .generator_for(context)?
+            .plan(&context.to_project_config())?;
+
+        if let Some(output_dir) = &self.dry_run.output_dir {
+            if output_dir.exists() && output_dir.read_dir()?.next().is_some() {
+                return Err(anyhow!(
+                    "Directory '{}' already exists and is not empty",
+                    output_dir.display()
+                ));
             }
+            fs::create_dir_all(output_dir)?;
+            plan.execute(output_dir)?;
         }
 
-        if context.features.contains(&"testing".to_string()) {
-            println!("  ├── {}/ ", "tests".bright_blue());
-            println!("  │   └── {}", "integration_tests.rs".bright_green());
+        Ok(plan)
+    }
+
+    /// Print the planned project as JSON on stdout
+    fn print_plan_json(&self, context: &ProjectContext, plan: &GenerationPlan) -> Result<()> {
+        let mut report = plan.to_json(self.dry_run.include_contents);
+        report["name"] = serde_json::json!(context.name);
+        report["project_type"] = serde_json::json!(context.project_type.to_string());
+        if let Some(framework) = context.framework {
+            report["framework"] = serde_json::json!(framework);
         }
+        report["features"] = serde_json::json!(context.features);
+        report["output_dir"] = serde_json::json!(self.dry_run.output_dir);
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        Ok(())
+    }
 
-        if context.project_type == ProjectType::WasmApp {
-            println!("  └── {}", "index.html".bright_green());
+    /// Preview directory structure from the generation plan
+    fn preview_directory_structure(&self, context: &ProjectContext, plan: &GenerationPlan) {
+        println!("  {}/", context.name.bright_yellow());
+        for line in plan.tree() {
+            if line.is_dir {
+                println!("  {}{}/", line.prefix, line.name.bright_blue());
+            } else {
+                println!("  {}{}", line.prefix, line.name.bright_green());
+            }
         }
+        println!(
+            "  {}",
+            format!("{} files, {} bytes", plan.files().len(), plan.total_size()).bright_black()
+        );
 
-        if context.project_type == ProjectType::GameEngine {
-            println!("  └── {}/ ", "assets".bright_blue());
-            println!("      ├── {}/ ", "models".bright_blue());
-            println!("      ├── {}/ ", "shaders".bright_blue());
-            println!("      ├── {}/ ", "sounds".bright_blue());
-            println!("      └── {}/ ", "textures".bright_blue());
+        if let Some(output_dir) = &self.dry_run.output_dir {
+            println!(
+                "\n{} {}",
+                "📂 Rendered into:".bright_cyan(),
+                output_dir.display().to_string().bright_white()
+            );
         }
     }
 
     /// Initialize project in current directory
     fn init_project_in_current_dir(&self, context: ProjectContext) -> Result<()> {
+        self.check_dependency_clash(&context)?;
         let current_dir = std::env::current_dir()?;
 
         println!("\n{}", "Creating project files...".bright_yellow());
@@ -1332,19 +1922,40 @@ impl Forge {
 
         pb.set_message("Generating project files...");
         let config = context.to_project_config();
-        let generator = Generator::new();
+        let generator = self
+            .generator_for(&context)?
+            .with_conflict_strategy(self.on_conflict);
 
         pb.set_position(50);
-        generator.generate(&config, &current_dir)?;
+        let summary = if self.on_conflict == ConflictStrategy::Prompt {
+            pb.finish_and_clear();
+            generator.generate_in_place_with(&config, &current_dir, |conflicts| {
+                conflicts.iter().map(prompt_conflict).collect()
+            })?
+        } else {
+            generator.generate_in_place(&config, &current_dir)?
+        };
 
         pb.set_position(100);
         pb.finish_and_clear();
 
+        for (label, files) in [
+            ("Merged into", &summary.merged),
+            ("Overwrote", &summary.replaced),
+            ("Kept existing", &summary.kept),
+        ] {
+            if !files.is_empty() {
+                println!("  {} {}", label.bright_cyan(), files.join(", "));
+            }
+        }
+
         println!(
             "\n{} {}",
             "✓".bright_green().bold(),
             "Project initialized successfully!".bright_green()
         );
+        self.init_vcs(&context, &current_dir);
+        self.verify_project(&current_dir)?;
         self.show_next_steps(&context, true)?;
 
         Ok(())
@@ -1401,7 +2012,7 @@ impl Forge {
         match context.project_type {
             ProjectType::ApiServer => {
                 println!("\n{}", "💡 API Server Tips:".bright_blue().bold());
-                println!("  • Edit src/main.rs to define your API routes");
+                println!("  • Add routes in src/routes.rs and handlers in src/handlers.rs");
                 println!("  • Run with: cargo run");
                 println!("  • Test endpoints at: http://localhost:3000");
             }
@@ -1451,3 +2062,44 @@ impl Forge {
         Ok(())
     }
 }
+
+/// Show the diff for a conflicting file and ask what to do with it
+fn prompt_conflict(conflict: &Conflict) -> Result<Resolution> {
+    let mut choices = Vec::new();
+    if conflict.merged.is_some() {
+        choices.push(("Merge", Resolution::Merge));
+    }
+    choices.push(("Keep existing", Resolution::Keep));
+    choices.push(("Overwrite", Resolution::Replace));
+
+    let preview = if conflict.merged.is_some() {
+        Resolution::Merge
+    } else {
+        Resolution::Replace
+    };
+    println!(
+        "\n{} {}",
+        "⚠️  Already exists:".bright_yellow(),
+        conflict.path.bright_white()
+    );
+    for line in conflict.diff(preview).lines() {
+        if line.starts_with('+') && !line.starts_with("+++") {
+            println!("{}", line.green());
+        } else if line.starts_with('-') && !line.starts_with("---") {
+            println!("{}", line.red());
+        } else {
+            println!("{}", line.bright_black());
+        }
+    }
+
+    let selection = Select::new(
+        &format!("What should happen to {}?", conflict.path),
+        choices.iter().map(|(label, _)| *label).collect(),
+    )
+    .prompt()?;
+    Ok(choices
+        .into_iter()
+        .find(|(label, _)| *label == selection)
+        .map(|(_, resolution)| resolution)
+        .unwrap_or(Resolution::Keep))
+}
@@ -1,11 +1,23 @@
+use crate::cargo_toml::{DependencyKind, ManifestBuilder};
+use crate::conflict::{Conflict, ConflictStrategy, InitSummary, Resolution};
 use crate::external_generators;
 use crate::features::ci::CIPlugin;
 use crate::features::database::DatabasePlugin;
 use crate::features::docker::{DockerBuildStage, DockerPlugin};
 use crate::features::{PluginManager, ProjectContext as FeatureContext};
-use anyhow::{anyhow, Result};
+use crate::forge::ProjectContext as ForgeContext;
+use crate::license::License;
+use crate::plan::{FileSource, GenerationPlan, PlannedFile};
+use crate::project_name;
+use crate::project_types::{Framework, ProjectType};
+use crate::templates::TemplateEngine;
+use crate::toolchain::{self, Edition};
+use crate::transaction::{InitJournal, StagingDir};
+use anyhow::{anyhow, Context as _, Result};
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use tera::Context;
 
 #[derive(Debug, Clone)]
 pub struct ProjectConfig {
@@ -18,14 +30,94 @@ pub struct ProjectConfig {
     pub esp32_chip: Option<String>,
 }
 
-pub struct Generator;
+pub struct Generator {
+    template_dirs: Vec<PathBuf>,
+    variables: HashMap<String, serde_json::Value>,
+    on_conflict: ConflictStrategy,
+    optional_features: bool,
+    edition: Edition,
+    rust_version: Option<String>,
+    license: Option<String>,
+    framework: Option<Framework>,
+}
 
 impl Generator {
     pub fn new() -> Self {
-        Self
+        Self {
+            template_dirs: Vec::new(),
+            variables: HashMap::new(),
+            on_conflict: ConflictStrategy::default(),
+            optional_features: false,
+            edition: Edition::default(),
+            rust_version: None,
+            license: None,
+            framework: None,
+        }
+    }
+
+    /// Layer templates from these directories over the bundled ones
+    ///
+    /// A file in a directory shadows the bundled template with the same
+    /// relative path; later directories take precedence over earlier ones.
+    pub fn with_template_dirs(mut self, dirs: Vec<PathBuf>) -> Self {
+        self.template_dirs = dirs;
+        self
+    }
+
+    /// Extra template variables, such as those declared by a template manifest
+    pub fn with_variables(mut self, variables: HashMap<String, serde_json::Value>) -> Self {
+        self.variables = variables;
+        self
+    }
+
+    /// How [`generate_in_place`](Self::generate_in_place) treats files that already exist
+    pub fn with_conflict_strategy(mut self, strategy: ConflictStrategy) -> Self {
+        self.on_conflict = strategy;
+        self
+    }
+
+    /// Put each feature plugin's dependencies and code behind a Cargo feature
+    ///
+    /// The selected features become optional dependencies gated by Cargo
+    /// features that are all on by default. Only library and CLI tool
+    /// projects support this.
+    pub fn with_optional_features(mut self, enabled: bool) -> Self {
+        self.optional_features = enabled;
+        self
+    }
+
+    /// The edition written to every generated manifest
+    pub fn with_edition(mut self, edition: Edition) -> Self {
+        self.edition = edition;
+        self
+    }
+
+    /// Set `rust-version` in the manifest and pin it in `rust-toolchain.toml`
+    pub fn with_rust_version(mut self, rust_version: Option<String>) -> Self {
+        self.rust_version = rust_version;
+        self
+    }
+
+    /// The project's license: an SPDX expression such as `MIT OR Apache-2.0`, or `proprietary`
+    ///
+    /// The expression is written to the manifest, and licenses with a bundled
+    /// text get a `LICENSE` file.
+    pub fn with_license(mut self, license: Option<String>) -> Self {
+        self.license = license;
+        self
+    }
+
+    /// The web framework of an API server, Axum unless set
+    ///
+    /// Only API server projects accept a framework.
+    pub fn with_framework(mut self, framework: Option<Framework>) -> Self {
+        self.framework = framework;
+        self
     }
 
     pub fn generate(&self, config: &ProjectConfig, output_dir: &Path) -> Result<()> {
+        project_name::validate(&config.name)?;
+
         // For ESP32 projects do not create the directory structure. esp-generate will handle it
         if let Some(target) = &config.target {
             if target == "esp32" {
@@ -35,11 +127,11 @@ impl Generator {
                     }
                 }
                 // Call esp-generate directory without creating project structure
-                return self.generate_embedded(config, output_dir);
+                return self.generate_esp32(config, output_dir);
             }
         }
 
-        // Create directory if it doesn't exist, but check for conflicts first
+        // Refuse to overwrite anything that is already there
         if output_dir.exists() {
             if !output_dir.is_dir() {
                 return Err(anyhow!(
@@ -49,33 +141,145 @@ impl Generator {
             }
             // Directory exists and is a directory - check if it's empty for safety
             if output_dir.read_dir()?.next().is_some() {
-                return Err(anyhow!("Directory '{}' is not empty", output_dir.display()));
+                return Err(anyhow!(
+                    "Directory '{}' already exists and is not empty",
+                    output_dir.display()
+                ));
+            }
+        }
+
+        // Build the project next to its destination and move it into place only
+        // once every step succeeded, so a failure leaves nothing behind
+        let staging = StagingDir::next_to(output_dir)?;
+        self.write_project(config, staging.path())?;
+        staging.commit(output_dir)
+    }
+
+    /// Generate a project into an existing directory, as `cargo-forge init` does
+    ///
+    /// Planned files that already exist are handled by the conflict strategy;
+    /// with the default strategy nothing is written if there are any. If a
+    /// write fails, the directory is restored to how it was.
+    pub fn generate_in_place(&self, config: &ProjectConfig, dir: &Path) -> Result<InitSummary> {
+        let strategy = self.on_conflict;
+        self.generate_in_place_with(config, dir, |conflicts| strategy.resolve(conflicts))
+    }
+
+    /// Like [`generate_in_place`](Self::generate_in_place), with `resolve` deciding conflicts
+    ///
+    /// `resolve` is called once, before anything is written, and returns one
+    /// resolution per conflict. Files identical to the planned ones are not
+    /// conflicts.
+    pub fn generate_in_place_with<F>(
+        &self,
+        config: &ProjectConfig,
+        dir: &Path,
+        resolve: F,
+    ) -> Result<InitSummary>
+    where
+        F: FnOnce(&[Conflict]) -> Result<Vec<Resolution>>,
+    {
+        if !dir.is_dir() {
+            return Err(anyhow!("Not a directory: {}", dir.display()));
+        }
+
+        let plan = self.plan(config)?;
+        let mut conflicts = Vec::new();
+        for file in plan.files() {
+            let target = dir.join(&file.path);
+            if target.is_dir() {
+                return Err(anyhow!(
+                    "Cannot write {}: a directory with that name already exists",
+                    file.path
+                ));
+            }
+            if target.exists() {
+                let existing = fs::read(&target)
+                    .with_context(|| format!("Failed to read {}", target.display()))?;
+                if existing != file.contents {
+                    conflicts.push(Conflict::new(&file.path, existing, file.contents.clone()));
+                }
             }
-        } else {
-            // Create the directory
-            fs::create_dir_all(output_dir)?;
         }
 
+        let resolutions = resolve(&conflicts)?;
+        if resolutions.len() != conflicts.len() {
+            return Err(anyhow!(
+                "Expected {} conflict resolutions, got {}",
+                conflicts.len(),
+                resolutions.len()
+            ));
+        }
+
+        let mut journal = InitJournal::new(dir)?;
+        let mut summary = InitSummary::default();
+        if let Err(err) = apply_plan(
+            &plan,
+            dir,
+            &conflicts,
+            &resolutions,
+            &mut journal,
+            &mut summary,
+        ) {
+            journal
+                .rollback()
+                .context("Failed to restore the directory after an error")?;
+            return Err(err);
+        }
+        journal.commit();
+        Ok(summary)
+    }
+
+    /// Work out every file the project consists of, without writing anything
+    ///
+    /// ESP32 projects are produced by `esp-generate` and cannot be planned.
+    pub fn plan(&self, config: &ProjectConfig) -> Result<GenerationPlan> {
+        if config.target.as_deref() == Some("esp32") {
+            return Err(anyhow!(
+                "ESP32 projects are generated by esp-generate and cannot be previewed"
+            ));
+        }
+
+        if let Some(rust_version) = &self.rust_version {
+            toolchain::validate_rust_version(rust_version, self.edition)?;
+        }
+        let license = self.license.as_deref().map(License::parse).transpose()?;
+
+        let mut plan = GenerationPlan::new();
+
         // Only create src and tests directories for non-workspace projects
         if config.project_type != "workspace" {
-            fs::create_dir_all(output_dir.join("src"))?;
-            fs::create_dir_all(output_dir.join("tests"))?;
+            plan.add_directory("src");
+            plan.add_directory("tests");
         }
 
-        // Generate files based on project type
-        match config.project_type.as_str() {
-            "api-server" => self.generate_api_server(config, output_dir)?,
-            "cli-tool" => self.generate_cli_tool(config, output_dir)?,
-            "library" => self.generate_library(config, output_dir)?,
-            "wasm-app" => self.generate_wasm_app(config, output_dir)?,
-            "game-engine" => self.generate_game_engine(config, output_dir)?,
-            "embedded" => self.generate_embedded(config, output_dir)?,
-            "workspace" => self.generate_workspace(config, output_dir)?,
-            _ => return Err(anyhow!("Unknown project type: {}", config.project_type)),
+        // Render the project type's templates
+        let project_type: ProjectType = config
+            .project_type
+            .parse()
+            .map_err(|_| anyhow!("Unknown project type: {}", config.project_type))?;
+        if self.optional_features
+            && !matches!(project_type, ProjectType::Library | ProjectType::CliTool)
+        {
+            return Err(anyhow!(
+                "Optional features are only supported for library and cli-tool projects, not {}",
+                config.project_type
+            ));
+        }
+        if let Some(framework) = self.framework {
+            if project_type != ProjectType::ApiServer {
+                return Err(anyhow!(
+                    "A web framework ({}) can only be chosen for api-server projects, not {}",
+                    framework,
+                    config.project_type
+                ));
+            }
         }
+        self.plan_templates(config, project_type, license.as_ref(), &mut plan)?;
 
         // Create feature context and apply plugins before generating common files
         let mut feature_context = FeatureContext::new(&config.name);
+        feature_context.optional_features = self.optional_features;
         if !config.features.is_empty() {
             let mut plugin_manager = PluginManager::new();
 
@@ -93,6 +297,15 @@ impl Generator {
                         if let Some(p) = port {
                             docker_plugin = docker_plugin.expose_port(p);
                         }
+                        // Migrations are embedded at compile time, so the build needs them;
+                        // a workspace root gets none
+                        if config.project_type != "workspace"
+                            && config.features.iter().any(|f| {
+                                matches!(f.as_str(), "database" | "postgres" | "sqlite" | "mysql")
+                            })
+                        {
+                            docker_plugin = docker_plugin.copy_dir("migrations");
+                        }
                         plugin_manager.register(Box::new(docker_plugin));
                     }
                     "ci" | "github-actions" => {
@@ -130,56 +343,211 @@ impl Generator {
                 .map_err(|e| anyhow!("Plugin configuration failed: {}", e))?;
         }
 
+        // Add the dependencies plugins asked for to the manifest
+        merge_plugin_dependencies(&feature_context, &mut plan)?;
+        check_dependency_names(&config.name, &plan)?;
+
         // Generate common files with feature integration
-        self.generate_cargo_toml(config, output_dir)?;
-        self.generate_gitignore_with_features(config, output_dir, &feature_context)?;
-        self.generate_readme_with_features(config, output_dir, &feature_context)?;
+        self.plan_gitignore(config, &feature_context, &mut plan);
+        self.plan_toolchain(config, &mut plan);
+        self.append_readme_sections(&feature_context, &mut plan);
+
+        // A virtual workspace root has no package to build plugin code with
+        let has_package = match plan.file("Cargo.toml") {
+            Some(file) => {
+                ManifestBuilder::parse(&String::from_utf8_lossy(&file.contents))?.has_package()
+            }
+            None => false,
+        };
 
         // Generate feature-specific files
         if !config.features.is_empty() {
-            self.generate_feature_files(&feature_context, output_dir)?;
+            self.plan_feature_files(&feature_context, has_package, &mut plan);
         }
 
-        Ok(())
+        // Plugin examples, and a README list of every runnable example
+        if has_package {
+            self.plan_examples(&feature_context, &mut plan)?;
+        }
+
+        Ok(plan)
+    }
+
+    /// Write every project file into `output_dir`, which must already exist
+    fn write_project(&self, config: &ProjectConfig, output_dir: &Path) -> Result<()> {
+        self.plan(config)?.execute(output_dir)
     }
 
-    fn generate_feature_files(
+    /// Plugin directories and files; without a package, plugin sources and
+    /// migrations are left out, since no crate would build them
+    fn plan_feature_files(
         &self,
         feature_context: &FeatureContext,
-        output_dir: &Path,
-    ) -> Result<()> {
+        has_package: bool,
+        plan: &mut GenerationPlan,
+    ) {
+        let needs_package = |path: &str| {
+            path == "src"
+                || path == "migrations"
+                || path.starts_with("src/")
+                || path.starts_with("migrations/")
+        };
+
         // Create directories specified by plugins
         for dir in &feature_context.directories {
-            fs::create_dir_all(output_dir.join(dir))?;
+            if has_package || !needs_package(dir) {
+                plan.add_directory(dir);
+            }
         }
 
-        // Write template files from plugins
-        for (path, content) in &feature_context.template_files {
-            let file_path = output_dir.join(path);
-            if let Some(parent) = file_path.parent() {
-                fs::create_dir_all(parent)?;
+        // Write template files from plugins, in a stable order
+        let mut paths: Vec<&String> = feature_context.template_files.keys().collect();
+        paths.sort();
+        for path in paths {
+            if !has_package && needs_package(path) {
+                continue;
             }
-            fs::write(&file_path, content)?;
-
+            // A project template that writes the same file already covers the
+            // feature, like the api-server `.env.example` with `DATABASE_URL`
+            if matches!(
+                plan.file(path).map(|file| &file.source),
+                Some(FileSource::Template(_))
+            ) {
+                continue;
+            }
+            let plugin = feature_context
+                .template_file_plugins
+                .get(path)
+                .cloned()
+                .unwrap_or_default();
             // Make scripts executable on Unix
-            #[cfg(unix)]
-            if path.starts_with("scripts/") && path.ends_with(".sh") {
-                use std::os::unix::fs::PermissionsExt;
-                let mut perms = fs::metadata(&file_path)?.permissions();
-                perms.set_mode(0o755);
-                fs::set_permissions(&file_path, perms)?;
+            let mode = if path.starts_with("scripts/") && path.ends_with(".sh") {
+                Some(0o755)
+            } else {
+                None
+            };
+            plan.add_file(PlannedFile {
+                path: path.clone(),
+                source: FileSource::Plugin(plugin),
+                contents: feature_context.template_files[path].clone().into_bytes(),
+                mode,
+            });
+        }
+    }
+
+    fn plan_examples(
+        &self,
+        feature_context: &FeatureContext,
+        plan: &mut GenerationPlan,
+    ) -> Result<()> {
+        let mut names: Vec<&String> = feature_context.examples.keys().collect();
+        names.sort();
+        for name in &names {
+            let plugin = feature_context
+                .example_plugins
+                .get(*name)
+                .cloned()
+                .unwrap_or_default();
+            plan.add_file(PlannedFile {
+                path: format!("examples/{}.rs", name),
+                source: FileSource::Plugin(plugin),
+                contents: feature_context.examples[*name].clone().into_bytes(),
+                mode: None,
+            });
+        }
+
+        let mut required_features = Vec::new();
+        if let Some(file) = plan.file_mut("Cargo.toml") {
+            let mut manifest = ManifestBuilder::parse(&String::from_utf8_lossy(&file.contents))?;
+            if manifest.has_package() {
+                let mut updated = false;
+                for name in &names {
+                    if let Some(features) = feature_context.example_features.get(*name) {
+                        if !features.is_empty() {
+                            let features: Vec<&str> = features.iter().map(String::as_str).collect();
+                            manifest.add_example(name, &features)?;
+                            updated = true;
+                        }
+                    }
+                }
+                if updated {
+                    file.contents = manifest.to_string().into_bytes();
+                }
+                required_features = manifest.example_required_features();
             }
         }
 
+        // Examples cargo discovers: `examples/<name>.rs` and `examples/<name>/main.rs`
+        let mut examples: Vec<String> = plan
+            .files()
+            .iter()
+            .filter_map(|file| {
+                let rest = file.path.strip_prefix("examples/")?;
+                match rest.split_once('/') {
+                    None => rest.strip_suffix(".rs").map(String::from),
+                    Some((dir, "main.rs")) => Some(dir.to_string()),
+                    Some(_) => None,
+                }
+            })
+            .collect();
+        examples.sort();
+        if examples.is_empty() {
+            return Ok(());
+        }
+        let Some(readme) = plan.file_mut("README.md") else {
+            return Ok(());
+        };
+
+        let mut section = String::from("## Running Examples\n\n```bash\n");
+        for example in &examples {
+            section.push_str(&format!("cargo run --example {}", example));
+            if let Some((_, features)) = required_features
+                .iter()
+                .find(|(name, features)| name == example && !features.is_empty())
+            {
+                section.push_str(&format!(" --features {}", features.join(",")));
+            }
+            section.push('\n');
+        }
+        section.push_str("```\n");
+
+        let content = &mut readme.contents;
+        if !content.is_empty() && !content.ends_with(b"\n") {
+            content.push(b'\n');
+        }
+        content.push(b'\n');
+        content.extend_from_slice(section.as_bytes());
         Ok(())
     }
 
-    fn generate_gitignore_with_features(
+    /// Pin the MSRV in `rust-toolchain.toml`, unless a template already provides one
+    fn plan_toolchain(&self, config: &ProjectConfig, plan: &mut GenerationPlan) {
+        let Some(rust_version) = &self.rust_version else {
+            return;
+        };
+        if plan.file("rust-toolchain.toml").is_some() {
+            return;
+        }
+
+        let targets: Vec<&str> = match config.project_type.as_str() {
+            "embedded" => vec![config.target.as_deref().unwrap_or("thumbv7em-none-eabihf")],
+            "wasm-app" => vec!["wasm32-unknown-unknown"],
+            _ => Vec::new(),
+        };
+        plan.add_file(PlannedFile {
+            path: "rust-toolchain.toml".to_string(),
+            source: FileSource::Generated,
+            contents: toolchain::toolchain_file(rust_version, &targets).into_bytes(),
+            mode: None,
+        });
+    }
+
+    fn plan_gitignore(
         &self,
         config: &ProjectConfig,
-        output_dir: &Path,
         feature_context: &FeatureContext,
-    ) -> Result<()> {
+        plan: &mut GenerationPlan,
+    ) {
         let mut content = String::from("/target\n**/*.rs.bk\n.DS_Store\n");
 
         // Add Cargo.lock for libraries
@@ -217,492 +585,230 @@ impl Generator {
             content.push('\n');
         }
 
-        fs::write(output_dir.join(".gitignore"), content)?;
-        Ok(())
+        plan.add_file(PlannedFile {
+            path: ".gitignore".to_string(),
+            source: FileSource::Generated,
+            contents: content.into_bytes(),
+            mode: None,
+        });
     }
 
-    fn generate_readme_with_features(
-        &self,
-        config: &ProjectConfig,
-        output_dir: &Path,
-        feature_context: &FeatureContext,
-    ) -> Result<()> {
-        let mut content = format!("# {}\n\n", config.name);
-
-        if let Some(desc) = &config.description {
-            content.push_str(desc);
-            content.push_str("\n\n");
+    fn append_readme_sections(&self, feature_context: &FeatureContext, plan: &mut GenerationPlan) {
+        if feature_context.readme_sections.is_empty() {
+            return;
         }
 
-        // Add project-type specific content
-        match config.project_type.as_str() {
-            "api-server" => {
-                content.push_str("## API Server\n\n");
-                content.push_str("This is a REST API server built with Axum.\n\n");
-                content.push_str("### Endpoints\n\n");
-                content.push_str("- `GET /` - Health check endpoint\n");
-                content.push_str("- More endpoints coming soon...\n\n");
-                content.push_str("### Running\n\n");
-                content.push_str("```bash\ncargo run\n```\n\n");
-                content.push_str("The server will start on `http://localhost:3000`\n");
-            }
-            "cli-tool" => {
-                content.push_str("## CLI Tool\n\n");
-                content.push_str("### Usage\n\n");
-                content.push_str("```bash\ncargo run -- --help\n```\n\n");
-                content.push_str("### Commands\n\n");
-                content.push_str("Available commands and arguments will be shown in help.\n");
-            }
-            "library" => {
-                content.push_str("## Library\n\n");
-                content.push_str("### Usage\n\n");
-                content.push_str("Add this to your `Cargo.toml`:\n\n");
-                content.push_str("```toml\n[dependencies]\n");
-                content.push_str(&format!("{} = \"0.1.0\"\n", config.name));
-                content.push_str("```\n\n");
-                content.push_str("### Example\n\n");
-                content.push_str("```rust\n// Example usage\n```\n\n");
-                content.push_str("### API Documentation\n\n");
-                content.push_str("Run `cargo doc --open` to view the documentation.\n");
-            }
-            "wasm-app" => {
-                content.push_str("## WebAssembly App\n\n");
-                content.push_str("This project compiles Rust to WebAssembly for the browser.\n\n");
-                content.push_str("### Building\n\n");
-                content.push_str("```bash\nwasm-pack build --target web\n```\n\n");
-                content.push_str("### Running locally\n\n");
-                content.push_str("Serve the project directory and open `index.html`:\n\n");
-                content.push_str("```bash\npython3 -m http.server 8080\n```\n\n");
-                content.push_str("### Testing\n\n");
-                content.push_str("```bash\nwasm-pack test --headless --firefox\n```\n");
-            }
-            "game-engine" => {
-                content.push_str("## Game Engine Project\n\n");
-                content.push_str("A game built on the Bevy engine.\n\n");
-                content.push_str("### Running\n\n");
-                content.push_str("```bash\ncargo run\n```\n\n");
-                content.push_str("### Assets\n\n");
-                content.push_str("Place sprites, sounds, and other assets in the `assets/` directory.\n\n");
-                content.push_str("### Release builds\n\n");
-                content.push_str("```bash\ncargo build --release\n```\n\n");
-                content.push_str("Release builds enable optimizations for smooth frame rates.\n");
-            }
-            "embedded" => {
-                content.push_str("## Embedded Project\n\n");
-                content.push_str("Bare-metal firmware for microcontrollers (no_std).\n\n");
-                content.push_str("### Building\n\n");
-                content.push_str("```bash\ncargo build --release\n```\n\n");
-                content.push_str("### Flashing\n\n");
-                content.push_str("```bash\ncargo run --release  # via probe-rs runner\n```\n\n");
-                content.push_str("### Target\n\n");
-                content.push_str("See `.cargo/config.toml` for the configured target triple and runner.\n");
-            }
-            "workspace" => {
-                content.push_str("## Cargo Workspace\n\n");
-                content.push_str("A multi-crate workspace layout.\n\n");
-                content.push_str("### Structure\n\n");
-                content.push_str("- `crates/` — member crates\n");
-                content.push_str("- `Cargo.toml` — workspace manifest\n\n");
-                content.push_str("### Building everything\n\n");
-                content.push_str("```bash\ncargo build --workspace\n```\n\n");
-                content.push_str("### Testing everything\n\n");
-                content.push_str("```bash\ncargo test --workspace\n```\n");
-            }
-            _ => {}
+        if plan.file("README.md").is_none() {
+            plan.add_file(PlannedFile {
+                path: "README.md".to_string(),
+                source: FileSource::Generated,
+                contents: Vec::new(),
+                mode: None,
+            });
+        }
+        let readme = plan
+            .file_mut("README.md")
+            .expect("README.md was just planned");
+        let content = &mut readme.contents;
+        if !content.is_empty() && !content.ends_with(b"\n") {
+            content.push(b'\n');
         }
 
         // Add feature-specific readme sections
         for section in &feature_context.readme_sections {
-            content.push_str(section);
-            content.push_str("\n");
+            content.push(b'\n');
+            content.extend_from_slice(section.as_bytes());
+            content.push(b'\n');
         }
-
-        fs::write(output_dir.join("README.md"), content)?;
-        Ok(())
     }
 
-    fn generate_api_server(&self, _config: &ProjectConfig, output_dir: &Path) -> Result<()> {
-        // Create API server specific files
-        fs::write(output_dir.join("src/main.rs"), "fn main() {}\n")?;
-        fs::write(output_dir.join("src/routes.rs"), "")?;
-        fs::write(output_dir.join("src/handlers.rs"), "")?;
-        fs::write(output_dir.join("src/models.rs"), "")?;
+    /// Render the templates for a project type into the plan
+    fn plan_templates(
+        &self,
+        config: &ProjectConfig,
+        project_type: ProjectType,
+        license: Option<&License>,
+        plan: &mut GenerationPlan,
+    ) -> Result<()> {
+        let mut project_context = ForgeContext::from_project_config(config)?;
+        project_context.edition = self.edition.to_string();
+        project_context.rust_version = self.rust_version.clone();
+        project_context.license = license.map(|license| license.expression().to_string());
+        project_context.framework = self.framework;
+        project_context.variables = self.variables.clone();
+        let mut context = Context::from_serialize(project_context.build_template_context())?;
+        // Lets templates put plugin modules behind their Cargo features
+        context.insert("optional_features", &self.optional_features);
+        let engine = TemplateEngine::with_features(config.features.clone())?
+            .with_template_dirs(&self.template_dirs)?;
+
+        for (template, file) in
+            engine.try_render_directory(project_type.template_dir(), &context)?
+        {
+            let file = file?;
+            // Keep copied permissions, and make rendered scripts executable
+            let mode = match file.mode {
+                Some(mode) => Some(mode),
+                None if file.path.ends_with(".sh") => Some(0o755),
+                None => None,
+            };
+            plan.add_file(PlannedFile {
+                path: file.path,
+                source: FileSource::Template(template),
+                contents: file.contents,
+                mode,
+            });
+        }
 
-        fs::create_dir_all(output_dir.join("config"))?;
-        fs::write(output_dir.join("config/default.toml"), "")?;
-        fs::write(output_dir.join(".env.example"), "")?;
+        // License texts, unless the project's templates bring their own
+        for file in license.map(License::files).unwrap_or_default() {
+            if plan.file(&file.path).is_some() {
+                continue;
+            }
+            let contents = engine.render(&file.template, &context)?;
+            plan.add_file(PlannedFile {
+                path: file.path,
+                source: FileSource::Template(file.template),
+                contents: contents.into_bytes(),
+                mode: None,
+            });
+        }
 
         Ok(())
     }
 
-    fn generate_cli_tool(&self, _config: &ProjectConfig, output_dir: &Path) -> Result<()> {
-        // Create CLI tool specific files
-        fs::write(output_dir.join("src/main.rs"), "fn main() {}\n")?;
-        fs::write(output_dir.join("src/cli.rs"), "")?;
-        fs::write(output_dir.join("src/commands.rs"), "")?;
-
-        Ok(())
+    fn generate_esp32(&self, config: &ProjectConfig, output_dir: &Path) -> Result<()> {
+        let chip = config.esp32_chip.as_deref().unwrap_or("esp32");
+        println!("🔧 Generating project for chip : {}", chip);
+        external_generators::generate_esp32_project(&config.name, chip, output_dir)
     }
+}
 
-    fn generate_library(&self, config: &ProjectConfig, output_dir: &Path) -> Result<()> {
-        // Create library specific files
-        let lib_content = format!(
-            "//! {}\n\n",
-            config.description.as_deref().unwrap_or("A Rust library")
-        );
-        fs::write(output_dir.join("src/lib.rs"), lib_content)?;
-
-        fs::create_dir_all(output_dir.join("examples"))?;
-        fs::write(output_dir.join("examples/basic.rs"), "fn main() {}\n")?;
-
-        Ok(())
+impl Default for Generator {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    fn generate_wasm_app(&self, _config: &ProjectConfig, output_dir: &Path) -> Result<()> {
-        // Create WASM app specific files
-        fs::write(output_dir.join("src/lib.rs"), "")?;
-        fs::write(output_dir.join("index.html"), "")?;
-        fs::write(output_dir.join("index.js"), "")?;
-        fs::write(output_dir.join("package.json"), "{}")?;
-        fs::write(output_dir.join("webpack.config.js"), "")?;
-        fs::write(output_dir.join("build.sh"), "#!/bin/bash\n")?;
+/// Reject a project named after one of its own dependencies
+fn check_dependency_names(name: &str, plan: &GenerationPlan) -> Result<()> {
+    let Some(file) = plan.file("Cargo.toml") else {
+        return Ok(());
+    };
+    let manifest = ManifestBuilder::parse(&String::from_utf8_lossy(&file.contents))?;
+    let dependencies = manifest.dependency_names();
+    project_name::check_dependencies(name, dependencies.iter().map(String::as_str))?;
+    Ok(())
+}
 
-        Ok(())
+/// Merge the dependencies requested by plugins into the planned `Cargo.toml`
+///
+/// Virtual workspace manifests have no `[package]` and are left alone.
+fn merge_plugin_dependencies(
+    feature_context: &FeatureContext,
+    plan: &mut GenerationPlan,
+) -> Result<()> {
+    let Some(file) = plan.file_mut("Cargo.toml") else {
+        return Ok(());
+    };
+    let mut manifest = ManifestBuilder::parse(&String::from_utf8_lossy(&file.contents))?;
+    if !manifest.has_package() {
+        return Ok(());
     }
 
-    fn generate_game_engine(&self, _config: &ProjectConfig, output_dir: &Path) -> Result<()> {
-        // Create game engine specific files
-        fs::write(output_dir.join("src/main.rs"), "fn main() {}\n")?;
-
-        // Create assets directory structure
-        fs::create_dir_all(output_dir.join("assets/models"))?;
-        fs::create_dir_all(output_dir.join("assets/textures"))?;
-        fs::create_dir_all(output_dir.join("assets/sounds"))?;
-        fs::create_dir_all(output_dir.join("assets/shaders"))?;
-
-        // Create basic asset README
-        fs::write(
-            output_dir.join("assets/README.md"),
-            "# Assets\n\nPlace your game assets here.",
-        )?;
-
-        // Create GitHub Actions for WASM builds
-        fs::create_dir_all(output_dir.join(".github/workflows"))?;
-        fs::write(output_dir.join(".github/workflows/wasm.yml"), "")?;
-
-        Ok(())
+    let mut tables: Vec<(DependencyKind, Option<&str>, &HashMap<String, String>)> = vec![
+        (DependencyKind::Normal, None, &feature_context.dependencies),
+        (DependencyKind::Dev, None, &feature_context.dev_dependencies),
+        (
+            DependencyKind::Build,
+            None,
+            &feature_context.build_dependencies,
+        ),
+    ];
+    let mut targets: Vec<_> = feature_context.target_dependencies.iter().collect();
+    targets.sort_by_key(|(target, _)| target.as_str());
+    for (target, dependencies) in targets {
+        tables.push((DependencyKind::Normal, Some(target), dependencies));
     }
 
-    fn generate_embedded(&self, config: &ProjectConfig, output_dir: &Path) -> Result<()> {
-        // Check if it is an esp32 project
-        if let Some(target) = &config.target {
-            if target == "esp32" {
-                let chip = config.esp32_chip.as_deref().unwrap_or("esp32");
-                println!("🔧 Generating project for chip : {}", chip);
-                return external_generators::generate_esp32_project(&config.name, chip, output_dir);
-            }
+    for (kind, target, dependencies) in tables {
+        let mut names: Vec<&String> = dependencies.keys().collect();
+        names.sort();
+        for name in names {
+            manifest.add_dependency(kind, target, name, &dependencies[name])?;
         }
-
-        // Default to Cortex-M embedded project (existing logic)
-        println!("🔧 Generating Cortex-M embedded project");
-        self.generate_cortex_m_embedded(config, output_dir)
     }
 
-    fn generate_cortex_m_embedded(&self, _config: &ProjectConfig, output_dir: &Path) -> Result<()> {
-        // Create embedded specific files with proper no_std setup
-        let main_content = r#"#![no_std]
-#![no_main]
-
-use panic_halt as _; // panic handler
-
-use cortex_m_rt::entry;
-
-#[entry]
-fn main() -> ! {
-    // Initialize the allocator BEFORE you use the heap
-    
-    // Main application logic
-    loop {
-        // Your code here
+    // Declare `default` first so it heads a newly created `[features]` table
+    if !feature_context.default_features.is_empty() {
+        let defaults: Vec<&str> = feature_context
+            .default_features
+            .iter()
+            .map(String::as_str)
+            .collect();
+        manifest.add_feature("default", &defaults)?;
     }
-}
-"#;
-        fs::write(output_dir.join("src/main.rs"), main_content)?;
-
-        // Create cargo config
-        fs::create_dir_all(output_dir.join(".cargo"))?;
-        let cargo_config_content = r#"[target.thumbv7em-none-eabihf]
-runner = "probe-rs-cli run --chip STM32F401RETx"
-
-[build]
-target = "thumbv7em-none-eabihf"
-
-[env]
-DEFMT_LOG = "debug"
-"#;
-        fs::write(output_dir.join(".cargo/config.toml"), cargo_config_content)?;
-
-        // Create memory layout file
-        let memory_x_content = r#"/* Linker script for the STM32F401RET6 */
-MEMORY
-{
-  /* NOTE 1 K = 1 KiBi = 1024 bytes */
-  FLASH : ORIGIN = 0x08000000, LENGTH = 512K
-  RAM : ORIGIN = 0x20000000, LENGTH = 96K
-}
-
-/* This is where the call stack will be allocated. */
-/* The stack is of the full descending type. */
-/* You may want to use this variable to locate the call stack and static
-   variables in different memory regions. Below is shown the default value */
-/* _stack_start = ORIGIN(RAM) + LENGTH(RAM); */
-
-/* You can use this symbol to customize the location of the .text section */
-/* If omitted the .text section will be placed right after the .vector_table
-   section */
-/* This can be useful if you want to move the firmware to some address other
-   than the default one (= 0x00000000 in thumb mode, 0x00000008 in non-thumb mode) */
-/* ENTRY_POINT = 0x08000000; */
-"#;
-        fs::write(output_dir.join("memory.x"), memory_x_content)?;
-
-        // Create probe-rs config
-        let embed_toml_content = r#"[default.probe]
-protocol = "Swd"
-
-[default.flashing]
-enabled = true
-
-[default.reset]
-enabled = true
-
-[default.general]
-chip = "STM32F401RETx"
-
-[default.rtt]
-enabled = true
-up_mode = "NoBlockSkip"
-"#;
-        fs::write(output_dir.join("Embed.toml"), embed_toml_content)?;
-
-        Ok(())
-    }
-
-    fn generate_workspace(&self, config: &ProjectConfig, output_dir: &Path) -> Result<()> {
-        // Create workspace structure
-        fs::create_dir_all(output_dir.join("crates/core/src"))?;
-        fs::create_dir_all(output_dir.join("crates/api/src"))?;
-        fs::create_dir_all(output_dir.join("crates/cli/src"))?;
-
-        // Create core crate
-        fs::write(
-            output_dir.join("crates/core/src/lib.rs"),
-            "//! Core library\n\npub fn hello() {\n    println!(\"Hello from core!\");\n}\n",
-        )?;
-        fs::write(output_dir.join("crates/core/src/error.rs"), "//! Error types for the core library\n\nuse std::fmt;\n\n#[derive(Debug)]\npub enum CoreError {\n    Generic(String),\n}\n\nimpl fmt::Display for CoreError {\n    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {\n        match self {\n            CoreError::Generic(msg) => write!(f, \"Core error: {}\", msg),\n        }\n    }\n}\n\nimpl std::error::Error for CoreError {}\n")?;
-        fs::write(output_dir.join("crates/core/src/lib.rs"), "//! Core library\n\npub mod error;\n\npub use error::CoreError;\n\npub fn hello() {\n    println!(\"Hello from core!\");\n}\n")?;
-        fs::write(output_dir.join("crates/core/src/models.rs"), "//! Data models\n\n#[derive(Debug, Clone)]\npub struct User {\n    pub id: u64,\n    pub name: String,\n    pub email: String,\n}\n\nimpl User {\n    pub fn new(id: u64, name: String, email: String) -> Self {\n        Self { id, name, email }\n    }\n}\n")?;
-        fs::write(output_dir.join("crates/core/src/utils.rs"), "//! Utility functions\n\npub fn format_name(first: &str, last: &str) -> String {\n    format!(\"{} {}\", first, last)\n}\n\npub fn validate_email(email: &str) -> bool {\n    email.contains('@') && email.contains('.')\n}\n")?;
-
-        let core_cargo_toml = format!(
-            r#"[package]
-name = "{}-core"
-version = "0.1.0"
-edition = "2021"
-authors = ["{}"]
-
-[dependencies]
-serde = {{ version = "1.0", features = ["derive"] }}
-anyhow = "1.0"
-"#,
-            config.name, config.author
-        );
-        fs::write(output_dir.join("crates/core/Cargo.toml"), core_cargo_toml)?;
-
-        // Create API crate
-        fs::write(output_dir.join("crates/api/src/lib.rs"), "//! API library\n\nuse anyhow::Result;\n\npub fn start_server() -> Result<()> {\n    println!(\"Starting API server...\");\n    Ok(())\n}\n")?;
-        fs::write(output_dir.join("crates/api/src/state.rs"), "//! Application state management\n\nuse std::sync::Arc;\nuse tokio::sync::RwLock;\n\n#[derive(Clone)]\npub struct AppState {\n    pub counter: Arc<RwLock<u64>>,\n}\n\nimpl AppState {\n    pub fn new() -> Self {\n        Self {\n            counter: Arc::new(RwLock::new(0)),\n        }\n    }\n}\n\nimpl Default for AppState {\n    fn default() -> Self {\n        Self::new()\n    }\n}\n")?;
-
-        let api_cargo_toml = format!(
-            r#"[package]
-name = "{}-api"
-version = "0.1.0"
-edition = "2021"
-authors = ["{}"]
-
-[dependencies]
-{}-core = {{ path = "../core" }}
-tokio = {{ version = "1", features = ["full"] }}
-anyhow = "1.0"
-serde = {{ version = "1.0", features = ["derive"] }}
-
-[lib]
-name = "{}_api"
-"#,
-            config.name,
-            config.author,
-            config.name,
-            config.name.replace('-', "_")
-        );
-        fs::write(output_dir.join("crates/api/Cargo.toml"), api_cargo_toml)?;
-
-        // Create CLI crate
-        fs::write(output_dir.join("crates/cli/src/main.rs"), &format!("use {}_core::hello;\nuse anyhow::Result;\n\nfn main() -> Result<()> {{\n    println!(\"Welcome to {}!\");\n    hello();\n    Ok(())\n}}\n", config.name.replace('-', "_"), config.name))?;
-
-        let cli_cargo_toml = format!(
-            r#"[package]
-name = "{}-cli"
-version = "0.1.0"
-edition = "2021"
-authors = ["{}"]
-
-[[bin]]
-name = "{}"
-path = "src/main.rs"
-
-[dependencies]
-{}-core = {{ path = "../core" }}
-clap = {{ version = "4", features = ["derive"] }}
-anyhow = "1.0"
-"#,
-            config.name, config.author, config.name, config.name
-        );
-        fs::write(output_dir.join("crates/cli/Cargo.toml"), cli_cargo_toml)?;
-
-        Ok(())
-    }
-
-    fn generate_cargo_toml(&self, config: &ProjectConfig, output_dir: &Path) -> Result<()> {
-        // Skip generating Cargo.toml for esp32 projects
-        if let Some(target) = &config.target {
-            if target == "esp32" {
-                return Ok(());
+    let mut features: Vec<&String> = feature_context.features.keys().collect();
+    features.sort();
+    for feature in features {
+        if let Some(dependencies) = feature_context.optional_dependencies.get(feature) {
+            let mut names: Vec<&String> = dependencies.keys().collect();
+            names.sort();
+            for name in names {
+                manifest.add_optional_dependency(feature, name, &dependencies[name])?;
             }
         }
-        let mut content = String::new();
-
-        // Generate workspace Cargo.toml for workspace projects
-        if config.project_type == "workspace" {
-            // Workspace root is a virtual manifest: no [package] section,
-            // since the root has no src/ targets of its own.
-            content.push_str("[workspace]\n");
-            content.push_str("resolver = \"2\"\n");
-            content.push_str("members = [\n");
-            content.push_str("  \"crates/core\",\n");
-            content.push_str("  \"crates/api\",\n");
-            content.push_str("  \"crates/cli\",\n");
-            content.push_str("]\n\n");
-
-            content.push_str("[workspace.package]\n");
-            content.push_str(&format!(r#"version = "0.1.0""#));
-            content.push('\n');
-            content.push_str(&format!(r#"authors = ["{}"]"#, config.author));
-            content.push('\n');
-            content.push_str(r#"edition = "2021""#);
-            content.push('\n');
-            if let Some(desc) = &config.description {
-                content.push_str(&format!(r#"description = "{}""#, desc));
-                content.push('\n');
-            }
-            content.push_str("\n");
-
-            content.push_str("[workspace.dependencies]\n");
-            content.push_str("tokio = { version = \"1\", features = [\"full\"] }\n");
-            content.push_str("serde = { version = \"1\", features = [\"derive\"] }\n");
-            content.push_str("anyhow = \"1\"\n");
-            content.push_str("clap = { version = \"4\", features = [\"derive\"] }\n");
-
-            fs::write(output_dir.join("Cargo.toml"), content)?;
-            return Ok(());
-        }
-
-        // Generate regular package Cargo.toml for other project types
-        content.push_str("[package]\n");
-        content.push_str(&format!(r#"name = "{}""#, config.name));
-        content.push('\n');
-        content.push_str(r#"version = "0.1.0""#);
-        content.push('\n');
-        content.push_str(&format!(r#"authors = ["{}"]"#, config.author));
-        content.push('\n');
-        content.push_str(r#"edition = "2021""#);
-        content.push('\n');
-
-        if let Some(desc) = &config.description {
-            content.push_str(&format!(r#"description = "{}""#, desc));
-            content.push('\n');
-        }
-
-        content.push_str("\n[dependencies]\n");
-
-        // Add project-type specific dependencies
-        match config.project_type.as_str() {
-            "api-server" => {
-                content.push_str("axum = \"0.7\"\n");
-                content.push_str("tokio = { version = \"1\", features = [\"full\"] }\n");
-                content.push_str("serde = { version = \"1\", features = [\"derive\"] }\n");
-                content.push_str("tower = \"0.4\"\n");
-            }
-            "cli-tool" => {
-                content.push_str("clap = { version = \"4\", features = [\"derive\"] }\n");
-                content.push_str("anyhow = \"1\"\n");
-                content.push_str("env_logger = \"0.10\"\n");
-
-                content.push_str("\n[[bin]]\n");
-                content.push_str(&format!(r#"name = "{}""#, config.name));
-                content.push('\n');
-                content.push_str("path = \"src/main.rs\"\n");
-            }
-            "library" => {
-                content.push_str("\n[lib]\n");
-                content.push_str(&format!(r#"name = "{}""#, config.name.replace('-', "_")));
-                content.push('\n');
-            }
-            "wasm-app" => {
-                content.push_str("wasm-bindgen = \"0.2\"\n");
-                content.push_str("web-sys = \"0.3\"\n");
-                content.push_str("js-sys = \"0.3\"\n");
+        let members: Vec<&str> = feature_context.features[feature]
+            .iter()
+            .map(String::as_str)
+            .collect();
+        manifest.add_feature(feature, &members)?;
+    }
 
-                content.push_str("\n[lib]\n");
-                content.push_str(r#"crate-type = ["cdylib"]"#);
-                content.push('\n');
-            }
-            "game-engine" => {
-                content.push_str("bevy = \"0.12\"\n");
+    file.contents = manifest.to_string().into_bytes();
+    Ok(())
+}
 
-                content.push_str("\n[target.'cfg(target_arch = \"wasm32\")'.dependencies]\n");
-                content.push_str("wasm-bindgen = \"0.2\"\n");
-                content.push_str("web-sys = \"0.3\"\n");
-                content.push_str("console_error_panic_hook = \"0.1\"\n");
+/// Write the plan through the journal, honouring the conflict resolutions
+fn apply_plan(
+    plan: &GenerationPlan,
+    dir: &Path,
+    conflicts: &[Conflict],
+    resolutions: &[Resolution],
+    journal: &mut InitJournal,
+    summary: &mut InitSummary,
+) -> Result<()> {
+    for directory in plan.directories() {
+        journal.create_dir_all(Path::new(directory))?;
+    }
 
-                content.push_str("\n[profile.dev]\n");
-                content.push_str("opt-level = 1\n");
-                content.push_str("\n[profile.dev.package.\"*\"]\n");
-                content.push_str("opt-level = 3\n");
-            }
-            "embedded" => {
-                content.push_str("cortex-m = \"0.7\"\n");
-                content.push_str("cortex-m-rt = \"0.7\"\n");
-                content.push_str("panic-halt = \"0.2\"\n");
-
-                content.push_str("\n[profile.dev]\n");
-                content.push_str("opt-level = 1\n");
-                content.push_str("\n[profile.release]\n");
-                content.push_str("lto = \"fat\"\n");
-                content.push_str("opt-level = 3\n");
+    for file in plan.files() {
+        let relative = Path::new(&file.path);
+        let conflict = conflicts.iter().position(|c| c.path == file.path);
+        match conflict {
+            Some(index) => {
+                let conflict = &conflicts[index];
+                let resolution = resolutions[index];
+                match conflict.contents(resolution) {
+                    Some(contents) => {
+                        journal.write_contents(relative, contents, file.mode)?;
+                        if resolution == Resolution::Merge {
+                            summary.merged.push(file.path.clone());
+                        } else {
+                            summary.replaced.push(file.path.clone());
+                        }
+                    }
+                    None => summary.kept.push(file.path.clone()),
+                }
             }
-            "workspace" => {
-                // Workspace projects have different structure - skip dependencies here
+            // Already there with the same contents
+            None if dir.join(relative).exists() => summary.kept.push(file.path.clone()),
+            None => {
+                journal.write_contents(relative, &file.contents, file.mode)?;
+                summary.created.push(file.path.clone());
             }
-            _ => {}
         }
-
-        fs::write(output_dir.join("Cargo.toml"), content)?;
-        Ok(())
     }
-}
 
-impl Default for Generator {
-    fn default() -> Self {
-        Self::new()
-    }
+    Ok(())
 }
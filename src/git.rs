@@ -0,0 +1,366 @@
+//! Reading the local git configuration
+//!
+//! The author of a new project defaults to the git identity of the person
+//! running forge. It is read straight from the config files git itself uses,
+//! so no `git` process is spawned. Sources are applied from lowest to highest
+//! precedence: the XDG config (`$XDG_CONFIG_HOME/git/config`), `~/.gitconfig`,
+//! the config of the repository forge runs in, and finally the
+//! `GIT_AUTHOR_NAME` and `GIT_AUTHOR_EMAIL` environment variables.
+//!
+//! With `--vcs git`, forge also initializes a repository in the new project
+//! by running the `git` command.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// How deep `[include]` directives are followed
+const MAX_INCLUDE_DEPTH: usize = 10;
+
+/// Version control system to set up in a new project
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Vcs {
+    Git,
+    #[default]
+    None,
+}
+
+/// How the repository of a new project is set up
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VcsOptions {
+    pub vcs: Vcs,
+    /// Name of the first branch
+    pub branch: String,
+    /// Commit the generated files
+    pub initial_commit: bool,
+}
+
+impl Default for VcsOptions {
+    fn default() -> Self {
+        Self {
+            vcs: Vcs::None,
+            branch: "main".to_string(),
+            initial_commit: false,
+        }
+    }
+}
+
+/// Initialize a git repository in `dir` and stage everything in it
+///
+/// `.gitignore` is staged first, so ignored files such as `target/` are
+/// never added. With `initial_commit`, the files are committed as `author`,
+/// falling back to the user's git identity for anything it leaves out.
+pub fn init_repository(dir: &Path, options: &VcsOptions, author: Option<&str>) -> Result<()> {
+    let branch = format!("refs/heads/{}", options.branch);
+    git(dir, &["check-ref-format", &branch], &[])
+        .with_context(|| format!("Invalid branch name '{}'", options.branch))?;
+
+    git(dir, &["init", "--quiet"], &[])?;
+    git(dir, &["symbolic-ref", "HEAD", &branch], &[])?;
+    if dir.join(".gitignore").is_file() {
+        git(dir, &["add", ".gitignore"], &[])?;
+    }
+    git(dir, &["add", "--all"], &[])?;
+
+    if options.initial_commit {
+        let configured = GitIdentity::detect(dir);
+        let committer = GitIdentity::detect_committer(dir);
+        let author = author.map(GitIdentity::parse).unwrap_or_default();
+        let mut env = Vec::new();
+        for (variable, value, fallback, committer) in [
+            ("NAME", &author.name, &configured.name, &committer.name),
+            ("EMAIL", &author.email, &configured.email, &committer.email),
+        ] {
+            if let Some(value) = value.as_ref().or(fallback.as_ref()) {
+                env.push((format!("GIT_AUTHOR_{}", variable), value.clone()));
+                // Let the commit go through where git has no committer identity
+                if committer.is_none() {
+                    env.push((format!("GIT_COMMITTER_{}", variable), value.clone()));
+                }
+            }
+        }
+        git(dir, &["commit", "--quiet", "-m", "Initial commit"], &env)?;
+    }
+    Ok(())
+}
+
+/// Run `git` in `dir`, turning a failure into an error with its output
+fn git(dir: &Path, args: &[&str], env: &[(String, String)]) -> Result<()> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .envs(env.iter().map(|(key, value)| (key, value)))
+        .output()
+        .map_err(|e| anyhow!("Failed to run git, is it installed? {}", e))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Values read from git config files, keyed by `section.key` or `section.subsection.key`
+///
+/// Section and key names are lowercased, as git treats them case-insensitively.
+#[derive(Debug, Clone, Default)]
+pub struct GitConfig {
+    values: HashMap<String, String>,
+}
+
+impl GitConfig {
+    /// The config git would use in `dir`, without the environment
+    pub fn detect(dir: &Path) -> Self {
+        let home = dirs::home_dir();
+        let xdg_config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute());
+        Self::from_files(&config_files(
+            dir,
+            home.as_deref(),
+            xdg_config_home.as_deref(),
+        ))
+    }
+
+    /// Read config files, later files taking precedence
+    ///
+    /// Files that do not exist are skipped.
+    pub fn from_files(files: &[PathBuf]) -> Self {
+        let mut config = Self::default();
+        for file in files {
+            config.read_file(file, 0);
+        }
+        config
+    }
+
+    /// A value such as `user.name`, if set to something non-empty
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values
+            .get(&key.to_lowercase())
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    }
+
+    fn read_file(&mut self, path: &Path, depth: usize) {
+        let Ok(contents) = fs::read_to_string(path) else {
+            return;
+        };
+
+        let mut section = String::new();
+        for line in contents.lines() {
+            let line = line.trim();
+            if let Some(header) = line.strip_prefix('[') {
+                let Some(end) = header.find(']') else {
+                    continue;
+                };
+                section = section_name(&header[..end]);
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = format!("{}.{}", section, key.trim().to_lowercase());
+            let value = parse_value(value);
+
+            // Included files are read in place, so later lines still override them
+            if key == "include.path" {
+                if depth < MAX_INCLUDE_DEPTH {
+                    if let Some(included) = include_path(path, &value) {
+                        self.read_file(&included, depth + 1);
+                    }
+                }
+                continue;
+            }
+            self.values.insert(key, value);
+        }
+    }
+}
+
+/// The `user.name` and `user.email` git would commit with
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitIdentity {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+impl GitIdentity {
+    /// The identity for a project created in `dir`, from the user's config files and environment
+    pub fn detect(dir: &Path) -> Self {
+        Self::from_config(&GitConfig::detect(dir)).with_env("AUTHOR")
+    }
+
+    /// The identity git would record as the committer in `dir`
+    ///
+    /// Like [`GitIdentity::detect`], but overridden by `GIT_COMMITTER_NAME`
+    /// and `GIT_COMMITTER_EMAIL` instead.
+    pub fn detect_committer(dir: &Path) -> Self {
+        Self::from_config(&GitConfig::detect(dir)).with_env("COMMITTER")
+    }
+
+    /// Read `user.name` and `user.email` from config files, later files taking precedence
+    pub fn from_files(files: &[PathBuf]) -> Self {
+        Self::from_config(&GitConfig::from_files(files))
+    }
+
+    fn from_config(config: &GitConfig) -> Self {
+        Self {
+            name: config.get("user.name").map(String::from),
+            email: config.get("user.email").map(String::from),
+        }
+    }
+
+    /// Override with `GIT_<role>_NAME` and `GIT_<role>_EMAIL` where they are set
+    fn with_env(mut self, role: &str) -> Self {
+        if let Some(name) = non_empty_var(&format!("GIT_{}_NAME", role)) {
+            self.name = Some(name);
+        }
+        if let Some(email) = non_empty_var(&format!("GIT_{}_EMAIL", role)) {
+            self.email = Some(email);
+        }
+        self
+    }
+
+    /// Split an author written as `Name <email>`
+    pub fn parse(author: &str) -> Self {
+        let author = author.trim();
+        let (name, email) = match author.split_once('<') {
+            Some((name, rest)) => (name, rest.strip_suffix('>').map(str::trim)),
+            None => (author, None),
+        };
+        let name = name.trim();
+        Self {
+            name: (!name.is_empty()).then(|| name.to_string()),
+            email: email.filter(|e| !e.is_empty()).map(String::from),
+        }
+    }
+
+    /// The author as written to `Cargo.toml`: `Name <email>`, or just the name
+    ///
+    /// There is no author without a name.
+    pub fn author(&self) -> Option<String> {
+        let name = self.name.as_deref()?;
+        Some(match self.email.as_deref() {
+            Some(email) => format!("{} <{}>", name, email),
+            None => name.to_string(),
+        })
+    }
+}
+
+/// The config files git reads for `dir`, lowest precedence first
+///
+/// `home` and `xdg_config_home` are usually `$HOME` and `$XDG_CONFIG_HOME`;
+/// without the latter, `$HOME/.config` is used.
+pub fn config_files(
+    dir: &Path,
+    home: Option<&Path>,
+    xdg_config_home: Option<&Path>,
+) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    match (xdg_config_home, home) {
+        (Some(xdg), _) => files.push(xdg.join("git").join("config")),
+        (None, Some(home)) => files.push(home.join(".config").join("git").join("config")),
+        (None, None) => {}
+    }
+    if let Some(home) = home {
+        files.push(home.join(".gitconfig"));
+    }
+    if let Some(git_dir) = find_git_dir(dir) {
+        files.push(git_dir.join("config"));
+    }
+    files
+}
+
+/// The git directory of the repository containing `dir`, if any
+///
+/// Follows `.git` files, as used by worktrees and submodules, to the
+/// directory holding the shared config.
+pub fn find_git_dir(dir: &Path) -> Option<PathBuf> {
+    for ancestor in dir.ancestors() {
+        let dot_git = ancestor.join(".git");
+        if dot_git.is_dir() {
+            return Some(dot_git);
+        }
+        if dot_git.is_file() {
+            let contents = fs::read_to_string(&dot_git).ok()?;
+            let git_dir = ancestor.join(contents.trim().strip_prefix("gitdir:")?.trim());
+            // Worktrees keep their config in the main repository
+            return Some(match fs::read_to_string(git_dir.join("commondir")) {
+                Ok(common) => git_dir.join(common.trim()),
+                Err(_) => git_dir,
+            });
+        }
+    }
+    None
+}
+
+/// Normalize `user`, `User` and `user "sub"` headers; subsections are kept apart
+///
+/// `[user "sub"]` becomes `user.sub`, with the quotes and their escapes removed.
+fn section_name(header: &str) -> String {
+    let header = header.trim();
+    let Some((section, subsection)) = header.split_once(|c: char| c.is_whitespace()) else {
+        return header.to_lowercase();
+    };
+    let subsection = subsection.trim();
+    let subsection = match subsection
+        .strip_prefix('"')
+        .and_then(|quoted| quoted.strip_suffix('"'))
+    {
+        Some(quoted) => {
+            let mut unescaped = String::with_capacity(quoted.len());
+            let mut chars = quoted.chars();
+            while let Some(c) = chars.next() {
+                unescaped.extend(if c == '\\' { chars.next() } else { Some(c) });
+            }
+            unescaped
+        }
+        None => subsection.to_string(),
+    };
+    format!("{}.{}", section.to_lowercase(), subsection)
+}
+
+/// Unquote a value and drop any trailing comment
+fn parse_value(raw: &str) -> String {
+    let mut value = String::new();
+    let mut quoted = false;
+    let mut chars = raw.trim().chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => quoted = !quoted,
+            '\\' => match chars.next() {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some(other) => value.push(other),
+                None => {}
+            },
+            '#' | ';' if !quoted => break,
+            c => value.push(c),
+        }
+    }
+    value.trim_end().to_string()
+}
+
+/// Resolve an `include.path` against the including file, expanding `~/`
+fn include_path(including: &Path, path: &str) -> Option<PathBuf> {
+    if let Some(rest) = path.strip_prefix("~/") {
+        return dirs::home_dir().map(|home| home.join(rest));
+    }
+    let path = Path::new(path);
+    if path.is_absolute() {
+        Some(path.to_path_buf())
+    } else {
+        including.parent().map(|parent| parent.join(path))
+    }
+}
+
+fn non_empty_var(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+}
@@ -3,14 +3,25 @@ pub use crate::config::Config;
 pub use crate::features::{Plugin, PluginManager, ProjectContext};
 pub use crate::forge::Forge;
 pub use crate::generator::{Generator, ProjectConfig};
-pub use crate::project_types::ProjectType;
+pub use crate::plan::GenerationPlan;
+pub use crate::project_types::{Framework, ProjectType};
 pub use crate::templates::TemplateEngine;
+pub use crate::toolchain::Edition;
 
 // Module declarations
+pub mod cargo_toml;
 pub mod config;
+pub mod conflict;
 pub mod external_generators;
 pub mod features;
 pub mod forge;
 pub mod generator;
+pub mod git;
+pub mod license;
+pub mod plan;
+pub mod project_name;
 pub mod project_types;
 pub mod templates;
+pub mod toolchain;
+pub mod transaction;
+pub mod verify;
@@ -0,0 +1,315 @@
+//! License expressions and the license files generated for them
+//!
+//! `--license` takes an SPDX expression such as `MIT` or `MIT OR Apache-2.0`,
+//! or `proprietary`. Licenses with a bundled text under `templates/licenses/`
+//! get a `LICENSE` file, or one `LICENSE-<NAME>` file each when the
+//! expression names several of them, the way most Rust crates are laid out.
+
+use anyhow::{anyhow, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// The value for a project that is not released under an open source license
+pub const PROPRIETARY: &str = "proprietary";
+
+/// SPDX license identifiers accepted in expressions
+const KNOWN_LICENSES: &[&str] = &[
+    "0BSD",
+    "AGPL-3.0",
+    "AGPL-3.0-only",
+    "AGPL-3.0-or-later",
+    "Apache-2.0",
+    "Artistic-2.0",
+    "BSD-2-Clause",
+    "BSD-3-Clause",
+    "BSL-1.0",
+    "CC-BY-4.0",
+    "CC0-1.0",
+    "EPL-2.0",
+    "EUPL-1.2",
+    "GPL-2.0",
+    "GPL-2.0-only",
+    "GPL-2.0-or-later",
+    "GPL-3.0",
+    "GPL-3.0-only",
+    "GPL-3.0-or-later",
+    "ISC",
+    "LGPL-2.1",
+    "LGPL-2.1-only",
+    "LGPL-2.1-or-later",
+    "LGPL-3.0",
+    "LGPL-3.0-only",
+    "LGPL-3.0-or-later",
+    "MIT",
+    "MIT-0",
+    "MPL-2.0",
+    "Unicode-3.0",
+    "Unicode-DFS-2016",
+    "Unlicense",
+    "WTFPL",
+    "Zlib",
+];
+
+/// SPDX exception identifiers accepted after `WITH`
+const KNOWN_EXCEPTIONS: &[&str] = &[
+    "Classpath-exception-2.0",
+    "GCC-exception-3.1",
+    "LLVM-exception",
+];
+
+/// Licenses with a bundled text: identifier, template and `LICENSE-<NAME>` suffix
+const BUNDLED: &[(&str, &str, &str)] = &[
+    ("MIT", "licenses/MIT.tera", "MIT"),
+    ("Apache-2.0", "licenses/Apache-2.0.tera", "APACHE"),
+    ("BSD-3-Clause", "licenses/BSD-3-Clause.tera", "BSD"),
+    ("MPL-2.0", "licenses/MPL-2.0.tera", "MPL"),
+    ("GPL-3.0", "licenses/GPL-3.0.tera", "GPL"),
+    ("GPL-3.0-only", "licenses/GPL-3.0.tera", "GPL"),
+    ("GPL-3.0-or-later", "licenses/GPL-3.0.tera", "GPL"),
+];
+
+const PROPRIETARY_TEMPLATE: &str = "licenses/proprietary.tera";
+
+/// A validated license expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct License {
+    expression: String,
+    /// License identifiers in the order they appear, without duplicates
+    ids: Vec<String>,
+}
+
+/// A license file to write for a [`License`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LicenseFile {
+    /// Path relative to the project root, such as `LICENSE-MIT`
+    pub path: String,
+    /// The template holding the license text
+    pub template: String,
+}
+
+impl License {
+    /// Parse and validate an SPDX expression, or `proprietary`
+    ///
+    /// Identifiers are matched case-insensitively and written back in their
+    /// SPDX spelling; the `AND`, `OR` and `WITH` operators must be uppercase.
+    pub fn parse(input: &str) -> Result<Self> {
+        let input = input.trim();
+        if input.eq_ignore_ascii_case(PROPRIETARY) {
+            return Ok(Self {
+                expression: PROPRIETARY.to_string(),
+                ids: Vec::new(),
+            });
+        }
+
+        let mut parser = Parser {
+            tokens: tokenize(input),
+            position: 0,
+            output: Vec::new(),
+            ids: Vec::new(),
+        };
+        parser
+            .expression()
+            .and_then(|()| match parser.tokens.get(parser.position) {
+                None => Ok(()),
+                Some(token) if ["and", "or", "with"].contains(&token.to_lowercase().as_str()) => {
+                    Err(anyhow!("operators must be uppercase, found '{}'", token))
+                }
+                Some(token) => Err(anyhow!("unexpected '{}'", token)),
+            })
+            .map_err(|e| anyhow!("Invalid license expression '{}': {}", input, e))?;
+
+        Ok(Self {
+            expression: parser.render(),
+            ids: parser.ids,
+        })
+    }
+
+    /// The expression in canonical form, as written to `Cargo.toml`
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    pub fn is_proprietary(&self) -> bool {
+        self.expression == PROPRIETARY
+    }
+
+    /// The license files to generate, one per license with a bundled text
+    pub fn files(&self) -> Vec<LicenseFile> {
+        if self.is_proprietary() {
+            return vec![LicenseFile {
+                path: "LICENSE".to_string(),
+                template: PROPRIETARY_TEMPLATE.to_string(),
+            }];
+        }
+
+        let mut bundled: Vec<(&str, &str)> = Vec::new();
+        for id in &self.ids {
+            if let Some((_, template, suffix)) = BUNDLED.iter().find(|(known, _, _)| known == id) {
+                if !bundled.iter().any(|(t, _)| t == template) {
+                    bundled.push((template, suffix));
+                }
+            }
+        }
+
+        let single = bundled.len() == 1;
+        bundled
+            .into_iter()
+            .map(|(template, suffix)| LicenseFile {
+                path: if single {
+                    "LICENSE".to_string()
+                } else {
+                    format!("LICENSE-{}", suffix)
+                },
+                template: template.to_string(),
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for License {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expression)
+    }
+}
+
+impl FromStr for License {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Split an expression into identifiers, operators and parentheses
+fn tokenize(input: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    for c in input.chars() {
+        if c.is_whitespace() || c == '(' || c == ')' {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            if !c.is_whitespace() {
+                tokens.push(c.to_string());
+            }
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+struct Parser {
+    tokens: Vec<String>,
+    position: usize,
+    output: Vec<String>,
+    ids: Vec<String>,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<String> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.position).map(String::as_str)
+    }
+
+    /// `term ((AND | OR) term)*`
+    fn expression(&mut self) -> Result<()> {
+        self.term()?;
+        while let Some(operator @ ("AND" | "OR")) = self.peek() {
+            self.output.push(operator.to_string());
+            self.position += 1;
+            self.term()?;
+        }
+        Ok(())
+    }
+
+    /// `( expression )` or `license [WITH exception]`
+    fn term(&mut self) -> Result<()> {
+        match self.next().as_deref() {
+            Some("(") => {
+                self.output.push("(".to_string());
+                self.expression()?;
+                if self.next().as_deref() != Some(")") {
+                    return Err(anyhow!("missing ')'"));
+                }
+                self.output.push(")".to_string());
+            }
+            Some(token) => {
+                let id = canonical_license(token)?;
+                self.output.push(id.clone());
+                let id = id.trim_end_matches('+').to_string();
+                if !self.ids.contains(&id) {
+                    self.ids.push(id);
+                }
+                if self.peek() == Some("WITH") {
+                    self.position += 1;
+                    let exception = self
+                        .next()
+                        .ok_or_else(|| anyhow!("expected an exception after WITH"))?;
+                    self.output.push("WITH".to_string());
+                    self.output.push(canonical_exception(&exception)?);
+                }
+            }
+            None => return Err(anyhow!("expected a license identifier")),
+        }
+        Ok(())
+    }
+
+    /// Join the tokens back together, without spaces inside parentheses
+    fn render(&self) -> String {
+        let mut expression = String::new();
+        for token in &self.output {
+            if !expression.is_empty() && !expression.ends_with('(') && token != ")" {
+                expression.push(' ');
+            }
+            expression.push_str(token);
+        }
+        expression
+    }
+}
+
+/// The SPDX spelling of a license identifier, with any `+` suffix kept
+fn canonical_license(token: &str) -> Result<String> {
+    let (id, plus) = match token.strip_suffix('+') {
+        Some(id) => (id, "+"),
+        None => (token, ""),
+    };
+    if let Some(reference) = id.strip_prefix("LicenseRef-") {
+        let valid = !reference.is_empty()
+            && reference
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+        return if valid {
+            Ok(token.to_string())
+        } else {
+            Err(anyhow!("invalid license reference '{}'", token))
+        };
+    }
+    if ["AND", "OR", "WITH", ")"].contains(&id) {
+        return Err(anyhow!("expected a license identifier, found '{}'", token));
+    }
+    if ["and", "or", "with"].contains(&id.to_lowercase().as_str()) {
+        return Err(anyhow!("operators must be uppercase, found '{}'", token));
+    }
+    KNOWN_LICENSES
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(id))
+        .map(|known| format!("{}{}", known, plus))
+        .ok_or_else(|| anyhow!("unknown SPDX license identifier '{}'", token))
+}
+
+fn canonical_exception(token: &str) -> Result<String> {
+    KNOWN_EXCEPTIONS
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(token))
+        .map(|known| known.to_string())
+        .ok_or_else(|| anyhow!("unknown SPDX exception '{}'", token))
+}
@@ -1,13 +1,14 @@
 mod cli;
 
 use anyhow::Result;
+use cargo_forge::forge::{DryRunOptions, OutputFormat};
 use cargo_forge::Forge;
 use clap::{CommandFactory, Parser};
 use clap_complete::{generate, Generator};
 use colored::*;
 use std::io;
 
-use crate::cli::{Cli, Commands};
+use crate::cli::{Cli, Commands, TemplateCommands};
 
 fn print_completions<G: Generator>(gen: G, cmd: &mut clap::Command) {
     generate(gen, cmd, cmd.get_name().to_string(), &mut io::stdout());
@@ -35,6 +36,15 @@ fn display_logo() {
     println!("{}", logo.bright_cyan());
 }
 
+fn print_dry_run_banner() {
+    println!(
+        "{}",
+        "🔍 DRY RUN MODE - No files will be created"
+            .bright_yellow()
+            .bold()
+    );
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
@@ -44,25 +54,51 @@ fn main() -> Result<()> {
             project_type,
             author,
             description,
-            license: _,
+            license,
             non_interactive,
             from_config,
             dry_run,
+            format,
+            include_contents,
+            output_dir,
+            framework,
+            edition,
+            rust_version,
+            vcs,
+            initial_commit,
+            verify,
+            verify_online,
+            optional_features,
+            vars,
         }) => {
+            let json = format == OutputFormat::Json;
+
             // Display logo unless in non-interactive mode
-            if !non_interactive {
+            if !non_interactive && !json {
                 display_logo();
             }
 
-            let forge = Forge::new(".");
+            let forge = Forge::new(".")
+                .with_variables(vars.into_iter().collect())
+                .with_optional_features(optional_features)
+                .with_framework(framework)
+                .with_edition(edition)
+                .with_rust_version(rust_version)
+                .with_license(license)
+                .with_vcs(vcs)
+                .with_initial_commit(initial_commit)
+                .with_verify(verify)
+                .with_verify_online(verify_online)
+                .with_dry_run_options(DryRunOptions {
+                    format,
+                    include_contents,
+                    output_dir,
+                });
 
             if dry_run {
-                println!(
-                    "{}",
-                    "🔍 DRY RUN MODE - No files will be created"
-                        .bright_yellow()
-                        .bold()
-                );
+                if !json {
+                    print_dry_run_banner();
+                }
                 forge.run_dry_run(
                     name,
                     project_type,
@@ -85,24 +121,52 @@ fn main() -> Result<()> {
         Some(Commands::Init {
             project_type,
             author: _,
-            license: _,
+            license,
             non_interactive,
             from_config,
             dry_run,
+            format,
+            include_contents,
+            output_dir,
+            on_conflict,
+            framework,
+            edition,
+            rust_version,
+            vcs,
+            initial_commit,
+            verify,
+            verify_online,
+            optional_features,
+            vars,
         }) => {
-            if !non_interactive {
+            let json = format == OutputFormat::Json;
+
+            if !non_interactive && !json {
                 display_logo();
             }
 
-            let forge = Forge::new(".");
+            let forge = Forge::new(".")
+                .with_variables(vars.into_iter().collect())
+                .with_conflict_strategy(on_conflict)
+                .with_optional_features(optional_features)
+                .with_framework(framework)
+                .with_edition(edition)
+                .with_rust_version(rust_version)
+                .with_license(license)
+                .with_vcs(vcs)
+                .with_initial_commit(initial_commit)
+                .with_verify(verify)
+                .with_verify_online(verify_online)
+                .with_dry_run_options(DryRunOptions {
+                    format,
+                    include_contents,
+                    output_dir,
+                });
 
             if dry_run {
-                println!(
-                    "{}",
-                    "🔍 DRY RUN MODE - No files will be created"
-                        .bright_yellow()
-                        .bold()
-                );
+                if !json {
+                    print_dry_run_banner();
+                }
                 forge.run_init_dry_run(project_type, non_interactive, from_config)?;
             } else if non_interactive {
                 forge.run_init_non_interactive(project_type, from_config)?;
@@ -112,6 +176,12 @@ fn main() -> Result<()> {
                 forge.run_init(project_type)?;
             }
         }
+        Some(Commands::Template {
+            command: TemplateCommands::Check { project_type, vars },
+        }) => {
+            let forge = Forge::new(".").with_variables(vars.into_iter().collect());
+            forge.run_template_check(project_type)?;
+        }
         Some(Commands::Completions { shell }) => {
             let mut cmd = Cli::command();
             print_completions(shell, &mut cmd);
@@ -0,0 +1,219 @@
+//! The list of files a generation run will write
+//!
+//! [`Generator::plan`](crate::Generator::plan) renders templates and runs
+//! feature plugins into a [`GenerationPlan`] without touching the disk. The
+//! same plan is then either executed or shown as a preview, so a dry run
+//! lists exactly the files a real run creates.
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Where a planned file comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileSource {
+    /// Rendered or copied from this template
+    Template(String),
+    /// Written by the named feature plugin
+    Plugin(String),
+    /// Built by the generator itself, such as `.gitignore`
+    Generated,
+}
+
+impl FileSource {
+    /// `template`, `plugin` or `built-in`
+    pub fn origin(&self) -> &'static str {
+        match self {
+            FileSource::Template(_) => "template",
+            FileSource::Plugin(_) => "plugin",
+            FileSource::Generated => "built-in",
+        }
+    }
+
+    /// The template or plugin name, if any
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            FileSource::Template(name) | FileSource::Plugin(name) => Some(name),
+            FileSource::Generated => None,
+        }
+    }
+}
+
+/// One file in a [`GenerationPlan`]
+#[derive(Debug, Clone)]
+pub struct PlannedFile {
+    /// Path relative to the project root, with `/` separators
+    pub path: String,
+    pub source: FileSource,
+    pub contents: Vec<u8>,
+    /// Unix permissions to set, or `None` for the default
+    pub mode: Option<u32>,
+}
+
+/// One line of [`GenerationPlan::tree`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeLine {
+    /// Box-drawing prefix such as `│   ├── `
+    pub prefix: String,
+    pub name: String,
+    pub is_dir: bool,
+}
+
+/// Every directory and file a generation run will create
+#[derive(Debug, Clone, Default)]
+pub struct GenerationPlan {
+    directories: Vec<String>,
+    files: Vec<PlannedFile>,
+}
+
+impl GenerationPlan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file, replacing any earlier file planned at the same path
+    pub fn add_file(&mut self, file: PlannedFile) {
+        match self.files.iter_mut().find(|f| f.path == file.path) {
+            Some(existing) => *existing = file,
+            None => self.files.push(file),
+        }
+    }
+
+    /// Add a directory that is created even if no file ends up in it
+    pub fn add_directory(&mut self, path: &str) {
+        let path = path.trim_end_matches('/');
+        if !path.is_empty() && !self.directories.iter().any(|d| d == path) {
+            self.directories.push(path.to_string());
+        }
+    }
+
+    pub fn files(&self) -> &[PlannedFile] {
+        &self.files
+    }
+
+    pub fn file(&self, path: &str) -> Option<&PlannedFile> {
+        self.files.iter().find(|f| f.path == path)
+    }
+
+    pub fn file_mut(&mut self, path: &str) -> Option<&mut PlannedFile> {
+        self.files.iter_mut().find(|f| f.path == path)
+    }
+
+    pub fn directories(&self) -> &[String] {
+        &self.directories
+    }
+
+    /// Write the plan into `root`, which must already exist
+    pub fn execute(&self, root: &Path) -> Result<()> {
+        for dir in &self.directories {
+            fs::create_dir_all(root.join(dir))
+                .with_context(|| format!("Failed to create directory {}", dir))?;
+        }
+
+        for file in &self.files {
+            let file_path = root.join(&file.path);
+            if let Some(parent) = file_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&file_path, &file.contents)
+                .with_context(|| format!("Failed to write {}", file.path))?;
+
+            #[cfg(unix)]
+            if let Some(mode) = file.mode {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&file_path, fs::Permissions::from_mode(mode))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Total size of all planned files in bytes
+    pub fn total_size(&self) -> usize {
+        self.files.iter().map(|f| f.contents.len()).sum()
+    }
+
+    /// Describe the plan as JSON, optionally with each file's contents
+    ///
+    /// Contents are included as text; files that are not valid UTF-8 get
+    /// `"binary": true` instead.
+    pub fn to_json(&self, include_contents: bool) -> Value {
+        let files: Vec<Value> = self
+            .files
+            .iter()
+            .map(|file| {
+                let mut entry = json!({
+                    "path": file.path,
+                    "size": file.contents.len(),
+                    "origin": file.source.origin(),
+                    "source": file.source.name(),
+                    "mode": file.mode.map(|mode| format!("{:o}", mode)),
+                });
+                if include_contents {
+                    match std::str::from_utf8(&file.contents) {
+                        Ok(text) => entry["contents"] = json!(text),
+                        Err(_) => entry["binary"] = json!(true),
+                    }
+                }
+                entry
+            })
+            .collect();
+
+        json!({
+            "directories": self.directories,
+            "files": files,
+        })
+    }
+
+    /// The planned directories and files as an indented tree, sorted by name
+    pub fn tree(&self) -> Vec<TreeLine> {
+        let mut root = TreeNode::default();
+        for dir in &self.directories {
+            root.insert(dir, true);
+        }
+        for file in &self.files {
+            root.insert(&file.path, false);
+        }
+
+        let mut lines = Vec::new();
+        root.walk("", &mut lines);
+        lines
+    }
+}
+
+#[derive(Default)]
+struct TreeNode {
+    is_dir: bool,
+    children: BTreeMap<String, TreeNode>,
+}
+
+impl TreeNode {
+    fn insert(&mut self, path: &str, is_dir: bool) {
+        let mut node = self;
+        let mut parts = path.split('/').filter(|part| !part.is_empty()).peekable();
+        while let Some(part) = parts.next() {
+            node = node.children.entry(part.to_string()).or_default();
+            if parts.peek().is_some() || is_dir {
+                node.is_dir = true;
+            }
+        }
+    }
+
+    fn walk(&self, indent: &str, lines: &mut Vec<TreeLine>) {
+        let count = self.children.len();
+        for (index, (name, child)) in self.children.iter().enumerate() {
+            let last = index + 1 == count;
+            lines.push(TreeLine {
+                prefix: format!("{}{}", indent, if last { "└── " } else { "├── " }),
+                name: name.clone(),
+                is_dir: child.is_dir,
+            });
+            child.walk(
+                &format!("{}{}", indent, if last { "    " } else { "│   " }),
+                lines,
+            );
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Project name validation
+//!
+//! Every way of naming a project, from `new <NAME>` to the directory `init`
+//! runs in, goes through [`validate`]. The rules follow what Cargo and
+//! crates.io accept, plus names that would make the generated project
+//! awkward to build: Rust keywords, standard library crates, file names
+//! Windows reserves, and crate names that clash with a dependency. Errors
+//! carry a suggested fix where one can be derived from the input.
+
+use crate::templates::filters;
+use std::fmt;
+
+/// Longest name accepted, as on crates.io
+pub const MAX_LENGTH: usize = 64;
+
+/// Names Cargo or the generated layout would trip over
+const RESERVED_NAMES: &[&str] = &[
+    "test", "main", "build", "cargo", "rust", "src", "target", "bin", "lib",
+];
+
+const STD_CRATES: &[&str] = &["std", "core", "alloc", "proc_macro"];
+
+/// Strict and reserved keywords across editions, including `gen` from 2024
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Device names Windows will not create files or directories for
+const WINDOWS_RESERVED: &[&str] = &[
+    "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8",
+    "com9", "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+/// Why a project name was rejected, and a name that would be accepted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameError {
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+impl NameError {
+    fn new(name: &str, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            suggestion: suggest(name),
+        }
+    }
+}
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " (try '{}')", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for NameError {}
+
+/// Check a project name against every rule
+pub fn validate(name: &str) -> Result<(), NameError> {
+    match problem(name) {
+        Some(message) => Err(NameError::new(name, message)),
+        None => Ok(()),
+    }
+}
+
+/// Reject a name whose crate identifier is also one of `dependencies`
+///
+/// `use tokio::...` in a crate named `tokio` refers to the crate itself, so
+/// the dependency could not be used.
+pub fn check_dependencies<'a>(
+    name: &str,
+    dependencies: impl IntoIterator<Item = &'a str>,
+) -> Result<(), NameError> {
+    let ident = filters::crate_ident(name);
+    for dependency in dependencies {
+        if dependency.replace('-', "_") == ident {
+            return Err(NameError {
+                message: format!(
+                    "Project name '{}' clashes with the '{}' dependency",
+                    name, dependency
+                ),
+                suggestion: Some(format!("{}-app", name)),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// A valid name close to `name`, such as `my-app` for `My App`
+///
+/// Characters a name cannot contain, non-ASCII letters included, become `-`.
+/// Returns `None` when `name` is already valid, nothing usable is left, or
+/// the fix would cut the name short.
+pub fn suggest(name: &str) -> Option<String> {
+    let name = name.trim();
+    // A trailing `é` would become a `-` and be trimmed, losing the end of the name
+    if name
+        .trim_end_matches(|c: char| c.is_ascii() && !c.is_ascii_alphanumeric())
+        .ends_with(|c: char| !c.is_ascii())
+    {
+        return None;
+    }
+
+    let mut fixed = String::new();
+    for c in name.chars() {
+        let c = if c.is_ascii_alphanumeric() || c == '_' {
+            c.to_ascii_lowercase()
+        } else {
+            '-'
+        };
+        // Collapse runs of separators into the first one
+        let separator = c == '-' || c == '_';
+        if separator && (fixed.is_empty() || fixed.ends_with(['-', '_'])) {
+            continue;
+        }
+        fixed.push(c);
+    }
+    let mut fixed = fixed
+        .trim_start_matches(|c: char| c.is_ascii_digit() || c == '-' || c == '_')
+        .to_string();
+    while fixed.ends_with(['-', '_']) {
+        fixed.pop();
+    }
+    if fixed.is_empty() || fixed.len() > MAX_LENGTH {
+        return None;
+    }
+    if problem(&fixed).is_some() {
+        fixed = format!("{}-app", fixed);
+    }
+    (fixed != name && problem(&fixed).is_none()).then_some(fixed)
+}
+
+/// The first rule `name` breaks
+fn problem(name: &str) -> Option<String> {
+    if name.is_empty() {
+        return Some("Project name cannot be empty".to_string());
+    }
+    if name.len() > MAX_LENGTH {
+        return Some(format!(
+            "Project name is too long (max {} characters)",
+            MAX_LENGTH
+        ));
+    }
+    if RESERVED_NAMES.contains(&name) {
+        return Some(format!("'{}' is a reserved name", name));
+    }
+    if name.contains(char::is_whitespace) {
+        return Some("Project name cannot contain spaces".to_string());
+    }
+    if name.contains('/') || name.contains('\\') {
+        return Some("Project name cannot contain slashes".to_string());
+    }
+    if name != name.to_lowercase() {
+        return Some("Project name must be lowercase".to_string());
+    }
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        return Some("Project name cannot start with a number".to_string());
+    }
+    if name.starts_with('-') || name.starts_with('_') {
+        return Some("Project name cannot start with '-' or '_'".to_string());
+    }
+    if name.ends_with('-') || name.ends_with('_') {
+        return Some("Project name cannot end with '-' or '_'".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Some("Project name can only contain letters, numbers, '-', and '_'".to_string());
+    }
+    if name.contains("--") || name.contains("__") {
+        return Some("Project name cannot contain consecutive dashes or underscores".to_string());
+    }
+
+    let ident = filters::crate_ident(name);
+    if KEYWORDS.contains(&ident.as_str()) {
+        return Some(format!("'{}' is a Rust keyword", name));
+    }
+    if STD_CRATES.contains(&ident.as_str()) {
+        return Some(format!(
+            "'{}' is the name of a standard library crate",
+            name
+        ));
+    }
+    if WINDOWS_RESERVED.contains(&name) {
+        return Some(format!("'{}' is a reserved file name on Windows", name));
+    }
+    None
+}
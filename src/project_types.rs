@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
 
@@ -45,9 +45,20 @@ impl FromStr for ProjectType {
 }
 
 impl ProjectType {
+    /// Every project type, in the order they are offered
+    pub const ALL: [ProjectType; 7] = [
+        ProjectType::ApiServer,
+        ProjectType::CliTool,
+        ProjectType::Library,
+        ProjectType::WasmApp,
+        ProjectType::GameEngine,
+        ProjectType::Embedded,
+        ProjectType::Workspace,
+    ];
+
     pub fn default_features(&self) -> Vec<&'static str> {
         match self {
-            ProjectType::ApiServer => vec!["axum", "tokio", "serde", "tower"],
+            ProjectType::ApiServer => vec!["tokio", "serde"],
             ProjectType::CliTool => vec!["clap", "anyhow", "env_logger"],
             ProjectType::Library => vec![],
             ProjectType::WasmApp => vec!["wasm-bindgen", "web-sys", "js-sys"],
@@ -57,6 +68,95 @@ impl ProjectType {
         }
     }
 
+    /// Features offered when creating a project: `(name, description, selected by default)`
+    ///
+    /// Embedded projects list the Cortex-M features; ESP32 projects are
+    /// configured by esp-generate instead.
+    pub fn available_features(&self) -> Vec<(&'static str, &'static str, bool)> {
+        match self {
+            ProjectType::ApiServer => vec![
+                ("tokio", "Async runtime", true),
+                ("serde", "Serialization", true),
+                ("sqlx", "SQL toolkit", false),
+                ("jwt", "JWT authentication", false),
+                ("cors", "CORS support", false),
+                ("tracing", "Structured logging", false),
+            ],
+            ProjectType::CliTool => vec![
+                ("clap", "CLI argument parsing", true),
+                ("anyhow", "Error handling", true),
+                ("env_logger", "Logging", true),
+                ("tokio", "Async runtime", false),
+                ("serde", "Serialization", false),
+                ("indicatif", "Progress bars", false),
+                ("colored", "Colored output", false),
+                (
+                    "packaging",
+                    "deb, rpm, Homebrew, AUR and binstall packaging",
+                    false,
+                ),
+            ],
+            ProjectType::Library => vec![
+                ("serde", "Serialization", false),
+                ("thiserror", "Error types", false),
+                ("async-trait", "Async traits", false),
+                ("criterion", "Benchmarking", false),
+            ],
+            ProjectType::WasmApp => vec![
+                ("wasm-bindgen", "JS bindings", true),
+                ("web-sys", "Web APIs", true),
+                ("js-sys", "JS APIs", true),
+                ("wee_alloc", "Small allocator", false),
+                ("console_error_panic_hook", "Better panic messages", false),
+            ],
+            ProjectType::GameEngine => vec![
+                ("bevy", "Game engine framework", true),
+                ("audio", "Audio support", false),
+                ("networking", "Multiplayer networking", false),
+                ("physics", "Physics simulation", false),
+                ("ui", "UI framework", false),
+            ],
+            ProjectType::Embedded => vec![
+                ("cortex-m", "ARM Cortex-M support", true),
+                ("cortex-m-rt", "Runtime support", true),
+                ("panic-halt", "Halt on panic", true),
+                ("panic-rtt", "RTT panic messages", false),
+                ("rtt", "Real-time transfer debugging", false),
+                ("semihosting", "Semihosting debug output", false),
+                ("stm32f4", "STM32F4 HAL", false),
+                ("stm32f1", "STM32F1 HAL", false),
+                ("rp2040", "Raspberry Pi Pico support", false),
+            ],
+            ProjectType::Workspace => vec![
+                ("tokio", "Async runtime", true),
+                ("serde", "Serialization", true),
+                ("anyhow", "Error handling", true),
+                ("database", "Database support", false),
+                ("web", "Web framework", false),
+                ("clap", "CLI support", false),
+                ("testing", "Advanced testing", false),
+                (
+                    "packaging",
+                    "deb, rpm, Homebrew, AUR and binstall packaging",
+                    false,
+                ),
+            ],
+        }
+    }
+
+    /// Directory under `templates/` holding this project type's templates
+    pub fn template_dir(&self) -> &'static str {
+        match self {
+            ProjectType::ApiServer => "api_server",
+            ProjectType::CliTool => "cli_tool",
+            ProjectType::Library => "library",
+            ProjectType::WasmApp => "wasm_app",
+            ProjectType::GameEngine => "game_engine",
+            ProjectType::Embedded => "embedded",
+            ProjectType::Workspace => "workspace",
+        }
+    }
+
     pub fn requires_external_generator(&self, target: Option<&str>) -> bool {
         match self {
             ProjectType::Embedded => target == Some("esp32"),
@@ -64,3 +164,75 @@ impl ProjectType {
         }
     }
 }
+
+/// Web framework an API server is built on
+///
+/// The framework decides the dependencies, the `main`, router, handler and
+/// error templates, and how middleware and the integration tests are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Framework {
+    #[default]
+    Axum,
+    ActixWeb,
+    Poem,
+    Rocket,
+}
+
+impl Framework {
+    /// Every framework, in the order they are offered
+    pub const ALL: [Framework; 4] = [
+        Framework::Axum,
+        Framework::ActixWeb,
+        Framework::Poem,
+        Framework::Rocket,
+    ];
+
+    /// Name as written in prose, such as `actix-web` or `Axum`
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Framework::Axum => "Axum",
+            Framework::ActixWeb => "actix-web",
+            Framework::Poem => "Poem",
+            Framework::Rocket => "Rocket",
+        }
+    }
+
+    /// One-line description shown when choosing a framework
+    pub fn description(&self) -> &'static str {
+        match self {
+            Framework::Axum => "Extractors and Tower middleware, from the Tokio team",
+            Framework::ActixWeb => "Mature and fast, with its own middleware ecosystem",
+            Framework::Poem => "Endpoint and middleware traits, with OpenAPI support",
+            Framework::Rocket => "Macro-driven routes, fairings and request guards",
+        }
+    }
+}
+
+impl fmt::Display for Framework {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Framework::Axum => write!(f, "axum"),
+            Framework::ActixWeb => write!(f, "actix-web"),
+            Framework::Poem => write!(f, "poem"),
+            Framework::Rocket => write!(f, "rocket"),
+        }
+    }
+}
+
+impl FromStr for Framework {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "axum" => Ok(Framework::Axum),
+            "actix-web" => Ok(Framework::ActixWeb),
+            "poem" => Ok(Framework::Poem),
+            "rocket" => Ok(Framework::Rocket),
+            _ => Err(anyhow::anyhow!(
+                "Invalid framework: {} (expected axum, actix-web, poem or rocket)",
+                s
+            )),
+        }
+    }
+}
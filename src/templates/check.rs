@@ -0,0 +1,482 @@
+use super::manifest::{resolve_variables, TemplateManifest};
+use super::TemplateEngine;
+use crate::forge::ProjectContext;
+use crate::git::VcsOptions;
+use crate::{Framework, ProjectType};
+use anyhow::Result;
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tera::Context;
+
+/// A problem found in one template
+#[derive(Debug, Clone)]
+pub struct CheckFailure {
+    /// Template name, such as `api_server/Cargo.toml.tera`
+    pub template: String,
+    pub project_type: ProjectType,
+    /// Web framework of the API server being rendered
+    pub framework: Option<Framework>,
+    /// Enabled features when the problem showed up
+    pub features: Vec<String>,
+    /// Line in the rendered file, or in the template for condition problems
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for CheckFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.template)?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+        }
+        let features = if self.features.is_empty() {
+            "no features".to_string()
+        } else {
+            format!("features: {}", self.features.join(", "))
+        };
+        match self.framework {
+            Some(framework) => write!(
+                f,
+                " [{} ({}), {}]: {}",
+                self.project_type, framework, features, self.message
+            ),
+            None => write!(
+                f,
+                " [{}, {}]: {}",
+                self.project_type, features, self.message
+            ),
+        }
+    }
+}
+
+/// Result of checking the templates
+#[derive(Debug, Default)]
+pub struct CheckReport {
+    /// Number of (project type, framework, feature set) combinations rendered
+    pub combinations: usize,
+    /// Number of rendered files parsed as TOML, Rust or YAML
+    pub files_checked: usize,
+    /// Each problem once, with the first feature set that showed it
+    pub failures: Vec<CheckFailure>,
+}
+
+impl CheckReport {
+    pub fn is_clean(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Renders every template across a matrix of feature sets and checks the output
+///
+/// Rendered `.toml` files are parsed with `toml`, `.rs` files with `syn` and
+/// `.yml`/`.yaml` files with a YAML parser. Template conditions are also
+/// checked for variables that are never set, such as `{% if database %}`
+/// where `has_feature(feature="database")` was meant.
+pub struct TemplateChecker {
+    template_dirs: Vec<PathBuf>,
+    project_types: Vec<ProjectType>,
+    variables: HashMap<String, String>,
+}
+
+impl TemplateChecker {
+    pub fn new() -> Self {
+        Self {
+            template_dirs: Vec::new(),
+            project_types: ProjectType::ALL.to_vec(),
+            variables: HashMap::new(),
+        }
+    }
+
+    /// Check these template directories layered over the bundled templates
+    pub fn with_template_dirs(mut self, dirs: Vec<PathBuf>) -> Self {
+        self.template_dirs = dirs;
+        self
+    }
+
+    /// Only check these project types
+    pub fn with_project_types(mut self, project_types: Vec<ProjectType>) -> Self {
+        self.project_types = project_types;
+        self
+    }
+
+    /// Values for template variables that have no default
+    pub fn with_variables(mut self, variables: HashMap<String, String>) -> Self {
+        self.variables = variables;
+        self
+    }
+
+    pub fn run(&self) -> Result<CheckReport> {
+        let mut report = CheckReport::default();
+        let mut seen = HashSet::new();
+
+        for &project_type in &self.project_types {
+            let manifests = TemplateManifest::discover(&self.template_dirs, project_type)?;
+            let variables = resolve_variables(&manifests, &self.variables, |_, _| Ok(None))?;
+            let base = TemplateEngine::new()?.with_template_dirs(&self.template_dirs)?;
+            let known = known_variables(project_type, &variables);
+            let mut linted = HashSet::new();
+
+            // API servers are rendered once per framework
+            let frameworks: Vec<Option<Framework>> = match project_type {
+                ProjectType::ApiServer => Framework::ALL.iter().copied().map(Some).collect(),
+                _ => vec![None],
+            };
+            let matrix = self.feature_matrix(&base, project_type)?;
+            for (framework, features) in frameworks
+                .iter()
+                .flat_map(|framework| matrix.iter().map(move |features| (*framework, features)))
+            {
+                report.combinations += 1;
+                let context = check_context(project_type, framework, features, &variables)?;
+                let engine = TemplateEngine::with_features(features.clone())?
+                    .with_template_dirs(&self.template_dirs)?;
+
+                let rendered =
+                    engine.try_render_directory(project_type.template_dir(), &context)?;
+                let partials = engine
+                    .list_templates()
+                    .into_iter()
+                    .filter(|name| name.starts_with("partials/"));
+
+                let mut failures = Vec::new();
+                for name in rendered
+                    .iter()
+                    .map(|(name, _)| name.clone())
+                    .chain(partials)
+                {
+                    if !linted.insert(name.clone()) {
+                        continue;
+                    }
+                    if let Some(source) = engine.template_source(&name) {
+                        for (line, message) in undefined_condition_variables(source, &known) {
+                            failures.push((name.clone(), Some(line), message));
+                        }
+                    }
+                }
+
+                for (name, file) in rendered {
+                    match file {
+                        Ok(file) => {
+                            let Ok(contents) = std::str::from_utf8(&file.contents) else {
+                                continue;
+                            };
+                            if let Some(result) = check_output(&file.path, contents) {
+                                report.files_checked += 1;
+                                if let Err((line, message)) = result {
+                                    let message = format!("{}: {}", file.path, message);
+                                    failures.push((name, line, message));
+                                }
+                            }
+                        }
+                        Err(err) => failures.push((name, None, format!("{:#}", err))),
+                    }
+                }
+
+                for (template, line, message) in failures {
+                    if seen.insert((template.clone(), line, message.clone())) {
+                        report.failures.push(CheckFailure {
+                            template,
+                            project_type,
+                            framework,
+                            features: features.clone(),
+                            line,
+                            message,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Feature sets to render a project type with
+    ///
+    /// The candidate features are those offered for the project type plus
+    /// any feature the templates test for. The matrix holds no features, the
+    /// defaults, every feature alone and added to the defaults, and all of
+    /// them together, in that order.
+    fn feature_matrix(
+        &self,
+        engine: &TemplateEngine,
+        project_type: ProjectType,
+    ) -> Result<Vec<Vec<String>>> {
+        let offered = project_type.available_features();
+        let defaults: BTreeSet<String> = offered
+            .iter()
+            .filter(|(_, _, default)| *default)
+            .map(|(name, _, _)| name.to_string())
+            .collect();
+
+        let mut candidates: BTreeSet<String> = offered
+            .iter()
+            .map(|(name, _, _)| name.to_string())
+            .collect();
+        let prefix = format!("{}/", project_type.template_dir());
+        for name in engine.list_templates() {
+            if name.starts_with(&prefix)
+                || name.starts_with("features/")
+                || name.starts_with("partials/")
+            {
+                if let Some(source) = engine.template_source(&name) {
+                    candidates.extend(referenced_features(source));
+                }
+            }
+        }
+        let values = serde_json::json!({ "project_type": project_type.to_string() });
+        for rule in engine.file_rules(&values) {
+            if let Some(condition) = rule.condition()? {
+                candidates.extend(condition.features().into_iter().map(String::from));
+            }
+        }
+
+        // Smallest sets first, so failures name the fewest features that show them
+        let mut sets = vec![BTreeSet::new(), defaults.clone()];
+        sets.extend(
+            candidates
+                .iter()
+                .map(|feature| BTreeSet::from([feature.clone()])),
+        );
+        for feature in &candidates {
+            let mut with_defaults = defaults.clone();
+            with_defaults.insert(feature.clone());
+            sets.push(with_defaults);
+        }
+        sets.push(candidates);
+
+        let mut matrix: Vec<Vec<String>> = Vec::new();
+        for set in sets {
+            let set: Vec<String> = set.into_iter().collect();
+            if !matrix.contains(&set) {
+                matrix.push(set);
+            }
+        }
+        Ok(matrix)
+    }
+}
+
+impl Default for TemplateChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The template context used for checking, with every optional value set
+fn check_context(
+    project_type: ProjectType,
+    framework: Option<Framework>,
+    features: &[String],
+    variables: &HashMap<String, serde_json::Value>,
+) -> Result<Context> {
+    let project = ProjectContext {
+        name: "forge-check".to_string(),
+        project_type,
+        features: features.to_vec(),
+        author: Some("Forge Check".to_string()),
+        description: Some("Template check".to_string()),
+        license: Some("MIT".to_string()),
+        edition: "2021".to_string(),
+        rust_version: Some("1.75".to_string()),
+        vcs: VcsOptions::default(),
+        target: None,
+        esp32_chip: None,
+        framework,
+        variables: variables.clone(),
+    };
+    let mut context = Context::from_serialize(project.build_template_context())?;
+    // Set by the generator; only these project types support optional features
+    context.insert(
+        "optional_features",
+        &matches!(project_type, ProjectType::Library | ProjectType::CliTool),
+    );
+    Ok(context)
+}
+
+/// Every variable the generator can put in the context for a project type
+fn known_variables(
+    project_type: ProjectType,
+    variables: &HashMap<String, serde_json::Value>,
+) -> HashSet<String> {
+    let project = ProjectContext {
+        name: String::new(),
+        project_type,
+        // A database feature, so `database_backend` is included
+        features: vec!["database".to_string()],
+        author: Some(String::new()),
+        description: Some(String::new()),
+        // A valid expression, so the variables derived from it are included
+        license: Some("MIT".to_string()),
+        edition: String::new(),
+        rust_version: Some(String::new()),
+        vcs: VcsOptions::default(),
+        target: Some(String::new()),
+        esp32_chip: Some(String::new()),
+        framework: None,
+        variables: variables.clone(),
+    };
+    let mut known: HashSet<String> = project.build_template_context().into_keys().collect();
+    known.insert("optional_features".to_string());
+    known
+}
+
+/// Parse a rendered file by its extension
+///
+/// Returns `None` for file types that are not checked, otherwise the line and
+/// message of the first syntax error.
+pub(crate) fn check_output(
+    path: &str,
+    contents: &str,
+) -> Option<Result<(), (Option<usize>, String)>> {
+    let extension = path.rsplit_once('.').map(|(_, ext)| ext)?;
+    let result = match extension {
+        "toml" => toml::from_str::<toml::Value>(contents)
+            .map(|_| ())
+            .map_err(|err| {
+                let line = err
+                    .span()
+                    .map(|span| contents[..span.start].matches('\n').count() + 1);
+                (line, err.message().to_string())
+            }),
+        "rs" => syn::parse_file(contents).map(|_| ()).map_err(|err| {
+            let line = err.span().start().line;
+            ((line > 0).then_some(line), err.to_string())
+        }),
+        "yml" | "yaml" => yaml_rust2::YamlLoader::load_from_str(contents)
+            .map(|_| ())
+            .map_err(|err| (Some(err.marker().line()), err.info().to_string())),
+        _ => return None,
+    };
+    Some(result)
+}
+
+fn regex(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("valid regex"))
+}
+
+/// Feature names a template tests for
+fn referenced_features(source: &str) -> Vec<String> {
+    static CALL: OnceLock<Regex> = OnceLock::new();
+    static CHECK: OnceLock<Regex> = OnceLock::new();
+    static STRING: OnceLock<Regex> = OnceLock::new();
+    static IN_FEATURES: OnceLock<Regex> = OnceLock::new();
+    static COMPARE: OnceLock<Regex> = OnceLock::new();
+
+    let mut features = Vec::new();
+    for caps in
+        regex(&CALL, r#"has_feature\(\s*feature\s*=\s*["']([^"']+)["']"#).captures_iter(source)
+    {
+        features.push(caps[1].to_string());
+    }
+    for caps in regex(&CHECK, r"check\s*=\s*\[([^\]]*)\]").captures_iter(source) {
+        for item in regex(&STRING, r#"["']([^"']+)["']"#).captures_iter(&caps[1]) {
+            features.push(item[1].to_string());
+        }
+    }
+    for caps in regex(
+        &IN_FEATURES,
+        r#"["']([^"']+)["']\s+(?:not\s+)?in\s+features\b"#,
+    )
+    .captures_iter(source)
+    {
+        features.push(caps[1].to_string());
+    }
+    for caps in regex(&COMPARE, r#"\bfeature\s*==\s*["']([^"']+)["']"#).captures_iter(source) {
+        features.push(caps[1].to_string());
+    }
+    features
+}
+
+/// Variables in `{% if %}` and `{% elif %}` conditions that are never set
+///
+/// Tera treats an unknown variable in a condition as false, so a typo or a
+/// feature name used as a variable silently hides that branch. Returns the
+/// template line and a message for each one. `has_<feature>` flags, loop and
+/// `set` variables, macro arguments and names guarded by `is defined` or
+/// `| default` are allowed.
+fn undefined_condition_variables(source: &str, known: &HashSet<String>) -> Vec<(usize, String)> {
+    static CONDITION: OnceLock<Regex> = OnceLock::new();
+    static LOCAL: OnceLock<Regex> = OnceLock::new();
+    static MACRO: OnceLock<Regex> = OnceLock::new();
+    static LITERAL: OnceLock<Regex> = OnceLock::new();
+    static IDENT: OnceLock<Regex> = OnceLock::new();
+    static GUARDED: OnceLock<Regex> = OnceLock::new();
+
+    let mut locals: HashSet<&str> = HashSet::from(["loop", "features"]);
+    for caps in regex(
+        &LOCAL,
+        r"\{%-?\s*(?:for\s+(\w+)(?:\s*,\s*(\w+))?\s+in|set(?:_global)?\s+(\w+)\s*=)",
+    )
+    .captures_iter(source)
+    {
+        locals.extend(caps.iter().skip(1).flatten().map(|m| m.as_str()));
+    }
+    for caps in regex(&MACRO, r"\{%-?\s*macro\s+\w+\s*\(([^)]*)\)").captures_iter(source) {
+        for param in caps.get(1).map_or("", |m| m.as_str()).split(',') {
+            if let Some(name) = param.split('=').next().map(str::trim) {
+                locals.insert(name);
+            }
+        }
+    }
+
+    let mut problems = Vec::new();
+    for caps in regex(&CONDITION, r"(?s)\{%-?\s*(?:if|elif)\s+(.*?)-?%\}").captures_iter(source) {
+        let expr = caps.get(1).expect("condition group");
+        let line = source[..expr.start()].matches('\n').count() + 1;
+        // Blank out string literals so their contents are not read as names
+        let expr = regex(&LITERAL, r#""[^"]*"|'[^']*'|`[^`]*`"#)
+            .replace_all(expr.as_str(), |m: &regex::Captures| " ".repeat(m[0].len()));
+
+        for ident in regex(&IDENT, r"[A-Za-z_][A-Za-z0-9_]*").find_iter(&expr) {
+            let name = ident.as_str();
+            let before = expr[..ident.start()].trim_end();
+            let after = &expr[ident.end()..];
+            let after_trimmed = after.trim_start();
+
+            let is_keyword = matches!(
+                name,
+                "and" | "or" | "not" | "in" | "is" | "true" | "false" | "True" | "False"
+            );
+            let is_member =
+                before.ends_with('.') || before.ends_with('|') || before.ends_with("::");
+            let mut previous = before.rsplit(char::is_whitespace);
+            let is_test = match previous.next() {
+                Some("is") => true,
+                Some("not") => previous.find(|word| !word.is_empty()) == Some("is"),
+                _ => false,
+            };
+            let is_call = after_trimmed.starts_with('(') || after_trimmed.starts_with("::");
+            let is_kwarg = after_trimmed.starts_with('=') && !after_trimmed.starts_with("==");
+            let is_guarded = regex(
+                &GUARDED,
+                r"^\s*(?:is\s+(?:not\s+)?(?:un)?defined\b|\|\s*default\b)",
+            )
+            .is_match(after);
+
+            if is_keyword
+                || is_member
+                || is_test
+                || is_call
+                || is_kwarg
+                || is_guarded
+                || name.starts_with("has_")
+                || known.contains(name)
+                || locals.contains(name)
+            {
+                continue;
+            }
+
+            problems.push((
+                line,
+                format!(
+                    "condition uses `{}`, which is never set; \
+                     use has_feature(feature=\"{}\") to test for a feature",
+                    name, name
+                ),
+            ));
+        }
+    }
+    problems
+}
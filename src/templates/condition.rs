@@ -0,0 +1,280 @@
+use anyhow::{anyhow, bail, Result};
+use std::collections::HashSet;
+
+/// A parsed `include_if` expression from a template manifest
+///
+/// The syntax is a small subset of Tera expressions:
+///
+/// ```text
+/// has_feature('auth') and not has_any_feature('sqlite', 'mysql')
+/// project_type == 'api-server' or (has_all_features('web', 'database'))
+/// ```
+///
+/// Bare identifiers are looked up in the template context and are true when
+/// the value is truthy. `==` and `!=` compare a context value with a string.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Literal(bool),
+    Variable(String),
+    Compare {
+        name: String,
+        value: String,
+        equal: bool,
+    },
+    HasFeature(String),
+    HasAnyFeature(Vec<String>),
+    HasAllFeatures(Vec<String>),
+    Not(Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    LParen,
+    RParen,
+    Comma,
+    Eq,
+    NotEq,
+}
+
+impl Condition {
+    /// Parse an expression
+    pub fn parse(input: &str) -> Result<Self> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let condition = parser.parse_or()?;
+        if parser.pos != parser.tokens.len() {
+            bail!("Unexpected trailing input in condition '{}'", input);
+        }
+        Ok(condition)
+    }
+
+    /// Every feature name the expression checks for
+    pub fn features(&self) -> Vec<&str> {
+        match self {
+            Condition::Literal(_) | Condition::Variable(_) | Condition::Compare { .. } => {
+                Vec::new()
+            }
+            Condition::HasFeature(feature) => vec![feature.as_str()],
+            Condition::HasAnyFeature(check) | Condition::HasAllFeatures(check) => {
+                check.iter().map(String::as_str).collect()
+            }
+            Condition::Not(inner) => inner.features(),
+            Condition::And(left, right) | Condition::Or(left, right) => {
+                let mut features = left.features();
+                features.extend(right.features());
+                features
+            }
+        }
+    }
+
+    /// Evaluate against the enabled features and the template context
+    pub fn evaluate(&self, features: &HashSet<String>, context: &serde_json::Value) -> bool {
+        match self {
+            Condition::Literal(value) => *value,
+            Condition::Variable(name) => is_truthy(context.get(name)),
+            Condition::Compare { name, value, equal } => {
+                let matches = context.get(name).and_then(|v| v.as_str()) == Some(value.as_str());
+                matches == *equal
+            }
+            Condition::HasFeature(feature) => features.contains(feature),
+            Condition::HasAnyFeature(check) => check.iter().any(|f| features.contains(f)),
+            Condition::HasAllFeatures(check) => check.iter().all(|f| features.contains(f)),
+            Condition::Not(inner) => !inner.evaluate(features, context),
+            Condition::And(left, right) => {
+                left.evaluate(features, context) && right.evaluate(features, context)
+            }
+            Condition::Or(left, right) => {
+                left.evaluate(features, context) || right.evaluate(features, context)
+            }
+        }
+    }
+}
+
+fn is_truthy(value: Option<&serde_json::Value>) -> bool {
+    match value {
+        None | Some(serde_json::Value::Null) => false,
+        Some(serde_json::Value::Bool(b)) => *b,
+        Some(serde_json::Value::String(s)) => !s.is_empty(),
+        Some(serde_json::Value::Array(a)) => !a.is_empty(),
+        Some(serde_json::Value::Number(n)) => n.as_f64() != Some(0.0),
+        Some(serde_json::Value::Object(o)) => !o.is_empty(),
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            ',' => {
+                chars.next();
+                tokens.push(Token::Comma);
+            }
+            '=' | '!' => {
+                chars.next();
+                if chars.next() != Some('=') {
+                    bail!("Expected '{}=' in condition '{}'", c, input);
+                }
+                tokens.push(if c == '=' { Token::Eq } else { Token::NotEq });
+            }
+            '\'' | '"' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some(end) if end == c => break,
+                        Some(ch) => value.push(ch),
+                        None => bail!("Unterminated string in condition '{}'", input),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut ident = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_alphanumeric() || ch == '_' {
+                        ident.push(ch);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            _ => bail!("Unexpected character '{}' in condition '{}'", c, input),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(ident)) if ident == keyword)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            other => Err(anyhow!("Expected {:?} but found {:?}", expected, other)),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Condition> {
+        let mut left = self.parse_and()?;
+        while self.peek_keyword("or") {
+            self.pos += 1;
+            let right = self.parse_and()?;
+            left = Condition::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Condition> {
+        let mut left = self.parse_not()?;
+        while self.peek_keyword("and") {
+            self.pos += 1;
+            let right = self.parse_not()?;
+            left = Condition::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<Condition> {
+        if self.peek_keyword("not") {
+            self.pos += 1;
+            return Ok(Condition::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Condition> {
+        match self.next() {
+            Some(Token::LParen) => {
+                let inner = self.parse_or()?;
+                self.expect(Token::RParen)?;
+                Ok(inner)
+            }
+            Some(Token::Ident(ident)) => match ident.as_str() {
+                "true" => Ok(Condition::Literal(true)),
+                "false" => Ok(Condition::Literal(false)),
+                _ if self.peek() == Some(&Token::LParen) => self.parse_call(ident),
+                _ => match self.peek() {
+                    Some(Token::Eq) | Some(Token::NotEq) => {
+                        let equal = self.next() == Some(Token::Eq);
+                        match self.next() {
+                            Some(Token::Str(value)) => Ok(Condition::Compare {
+                                name: ident,
+                                value,
+                                equal,
+                            }),
+                            other => Err(anyhow!("Expected a string but found {:?}", other)),
+                        }
+                    }
+                    _ => Ok(Condition::Variable(ident)),
+                },
+            },
+            other => Err(anyhow!("Unexpected token {:?} in condition", other)),
+        }
+    }
+
+    fn parse_call(&mut self, name: String) -> Result<Condition> {
+        self.expect(Token::LParen)?;
+        let mut args = Vec::new();
+        while self.peek() != Some(&Token::RParen) {
+            match self.next() {
+                Some(Token::Str(arg)) => args.push(arg),
+                other => bail!(
+                    "Expected a string argument to {} but found {:?}",
+                    name,
+                    other
+                ),
+            }
+            if self.peek() == Some(&Token::Comma) {
+                self.pos += 1;
+            }
+        }
+        self.expect(Token::RParen)?;
+
+        match name.as_str() {
+            "has_feature" => match <[String; 1]>::try_from(args) {
+                Ok([feature]) => Ok(Condition::HasFeature(feature)),
+                Err(_) => bail!("has_feature takes exactly one feature"),
+            },
+            "has_any_feature" => Ok(Condition::HasAnyFeature(args)),
+            "has_all_features" => Ok(Condition::HasAllFeatures(args)),
+            _ => bail!("Unknown function '{}' in condition", name),
+        }
+    }
+}
@@ -1,145 +1,111 @@
+use super::TemplateEngine;
 use anyhow::Result;
-use std::collections::HashSet;
-use tera::{Context, Tera};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tera::{Context, Tera, Value};
 
 /// Conditional template renderer that supports feature-based conditional rendering
+///
+/// This is a thin wrapper over [`TemplateEngine`] for rendering templates
+/// added at runtime. The embedded templates, filters and feature functions
+/// are all available, so added templates can `{% include %}` the shared
+/// partials and `{% import %}` the shared macros.
 pub struct ConditionalRenderer {
-    tera: Tera,
-    features: HashSet<String>,
+    engine: TemplateEngine,
 }
 
 impl ConditionalRenderer {
     pub fn new(features: Vec<String>) -> Result<Self> {
-        let mut tera = Tera::default();
-
-        // Register custom functions for feature checking
-        tera.register_function("has_feature", has_feature_function);
-        tera.register_function("has_any_feature", has_any_feature_function);
-        tera.register_function("has_all_features", has_all_features_function);
-
         Ok(Self {
-            tera,
-            features: features.into_iter().collect(),
+            engine: TemplateEngine::with_features(features)?,
         })
     }
 
     /// Add a template with a given name
     pub fn add_template(&mut self, name: &str, content: &str) -> Result<()> {
-        self.tera.add_raw_template(name, content)?;
-        Ok(())
+        self.engine.add_template(name, content)
     }
 
     /// Render a template with feature-aware context
-    pub fn render(&self, template_name: &str, mut context: Context) -> Result<String> {
-        // Add features to the context
-        context.insert("features", &self.features);
-
-        // Add feature checking helpers
-        for feature in &self.features {
-            context.insert(&format!("has_{}", feature), &true);
-        }
-
-        let rendered = self.tera.render(template_name, &context)?;
-        Ok(rendered)
+    pub fn render(&self, template_name: &str, context: Context) -> Result<String> {
+        self.engine.render(template_name, &context)
     }
 
     /// Check if a specific feature is enabled
     pub fn has_feature(&self, feature: &str) -> bool {
-        self.features.contains(feature)
+        self.engine.has_feature(feature)
     }
 
     /// Get all enabled features
     pub fn get_features(&self) -> Vec<String> {
-        self.features.iter().cloned().collect()
+        self.engine.get_features()
     }
 }
 
-/// Tera function to check if a feature is enabled
-fn has_feature_function(
-    args: &HashMap<String, serde_json::Value>,
-) -> tera::Result<serde_json::Value> {
-    let feature = args
-        .get("feature")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| tera::Error::msg("has_feature requires a 'feature' parameter"))?;
-
-    let features = args
-        .get("features")
-        .and_then(|v| v.as_array())
-        .ok_or_else(|| tera::Error::msg("has_feature requires 'features' in context"))?;
-
-    let feature_strings: Vec<String> = features
-        .iter()
-        .filter_map(|v| v.as_str().map(|s| s.to_string()))
-        .collect();
-
-    Ok(serde_json::Value::Bool(
-        feature_strings.contains(&feature.to_string()),
-    ))
+/// Register `has_feature`, `has_any_feature` and `has_all_features` on a Tera instance
+///
+/// The functions check against `features` unless the call passes its own
+/// list, so both `has_feature(feature="auth")` and the older
+/// `has_feature(feature="auth", features=features)` work.
+pub(crate) fn register_feature_functions(tera: &mut Tera, features: &HashSet<String>) {
+    let enabled = Arc::new(features.clone());
+
+    let features = Arc::clone(&enabled);
+    tera.register_function("has_feature", move |args: &HashMap<String, Value>| {
+        let feature = args
+            .get("feature")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| tera::Error::msg("has_feature requires a 'feature' parameter"))?;
+        Ok(Value::Bool(
+            enabled_features(args, &features).contains(feature),
+        ))
+    });
+
+    let features = Arc::clone(&enabled);
+    tera.register_function("has_any_feature", move |args: &HashMap<String, Value>| {
+        let check = check_features(args, "has_any_feature")?;
+        let enabled = enabled_features(args, &features);
+        Ok(Value::Bool(check.iter().any(|f| enabled.contains(*f))))
+    });
+
+    let features = enabled;
+    tera.register_function("has_all_features", move |args: &HashMap<String, Value>| {
+        let check = check_features(args, "has_all_features")?;
+        let enabled = enabled_features(args, &features);
+        Ok(Value::Bool(check.iter().all(|f| enabled.contains(*f))))
+    });
 }
 
-/// Tera function to check if any of the specified features are enabled
-fn has_any_feature_function(
-    args: &HashMap<String, serde_json::Value>,
-) -> tera::Result<serde_json::Value> {
-    let check_features = args
-        .get("check")
-        .and_then(|v| v.as_array())
-        .ok_or_else(|| tera::Error::msg("has_any_feature requires a 'check' array parameter"))?;
-
-    let features = args
-        .get("features")
-        .and_then(|v| v.as_array())
-        .ok_or_else(|| tera::Error::msg("has_any_feature requires 'features' in context"))?;
-
-    let feature_strings: HashSet<String> = features
-        .iter()
-        .filter_map(|v| v.as_str().map(|s| s.to_string()))
-        .collect();
-
-    for check_feature in check_features {
-        if let Some(feature_str) = check_feature.as_str() {
-            if feature_strings.contains(feature_str) {
-                return Ok(serde_json::Value::Bool(true));
-            }
-        }
+/// The feature set a call checks against: its `features` argument, or the engine's features
+fn enabled_features<'a>(
+    args: &HashMap<String, Value>,
+    default: &'a HashSet<String>,
+) -> Cow<'a, HashSet<String>> {
+    match args.get("features").and_then(|v| v.as_array()) {
+        Some(list) => Cow::Owned(
+            list.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect(),
+        ),
+        None => Cow::Borrowed(default),
     }
-
-    Ok(serde_json::Value::Bool(false))
 }
 
-/// Tera function to check if all specified features are enabled
-fn has_all_features_function(
-    args: &HashMap<String, serde_json::Value>,
-) -> tera::Result<serde_json::Value> {
-    let check_features = args
+/// The `check` array parameter of `has_any_feature` and `has_all_features`
+fn check_features<'a>(
+    args: &'a HashMap<String, Value>,
+    function: &str,
+) -> tera::Result<Vec<&'a str>> {
+    let check = args
         .get("check")
         .and_then(|v| v.as_array())
-        .ok_or_else(|| tera::Error::msg("has_all_features requires a 'check' array parameter"))?;
-
-    let features = args
-        .get("features")
-        .and_then(|v| v.as_array())
-        .ok_or_else(|| tera::Error::msg("has_all_features requires 'features' in context"))?;
-
-    let feature_strings: HashSet<String> = features
-        .iter()
-        .filter_map(|v| v.as_str().map(|s| s.to_string()))
-        .collect();
-
-    for check_feature in check_features {
-        if let Some(feature_str) = check_feature.as_str() {
-            if !feature_strings.contains(feature_str) {
-                return Ok(serde_json::Value::Bool(false));
-            }
-        }
-    }
-
-    Ok(serde_json::Value::Bool(true))
+        .ok_or_else(|| {
+            tera::Error::msg(format!("{} requires a 'check' array parameter", function))
+        })?;
+    Ok(check.iter().filter_map(|v| v.as_str()).collect())
 }
 
-use std::collections::HashMap;
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use tera::{Tera, Value};
+
+/// Register the case-conversion and Rust helper filters on a Tera instance
+///
+/// Available filters: `snake_case`, `pascal_case`, `kebab_case`,
+/// `shouty_snake`, `crate_ident`, `escape_toml_string` and `escape_ruby_string`.
+pub fn register(tera: &mut Tera) {
+    tera.register_filter("snake_case", string_filter("snake_case", snake_case));
+    tera.register_filter("pascal_case", string_filter("pascal_case", pascal_case));
+    tera.register_filter("kebab_case", string_filter("kebab_case", kebab_case));
+    tera.register_filter("shouty_snake", string_filter("shouty_snake", shouty_snake));
+    tera.register_filter("crate_ident", string_filter("crate_ident", crate_ident));
+    tera.register_filter(
+        "escape_toml_string",
+        string_filter("escape_toml_string", escape_toml_string),
+    );
+    tera.register_filter(
+        "escape_ruby_string",
+        string_filter("escape_ruby_string", escape_ruby_string),
+    );
+}
+
+/// Wrap a string conversion as a Tera filter
+fn string_filter(
+    name: &'static str,
+    convert: fn(&str) -> String,
+) -> impl Fn(&Value, &HashMap<String, Value>) -> tera::Result<Value> + Send + Sync {
+    move |value, _args| {
+        let input = value
+            .as_str()
+            .ok_or_else(|| tera::Error::msg(format!("Filter `{}` expects a string", name)))?;
+        Ok(Value::String(convert(input)))
+    }
+}
+
+/// Split a name into lowercase words
+///
+/// Words are separated by `-`, `_`, whitespace and other punctuation, and at
+/// camelCase boundaries, so `myHTTPServer` splits into `my`, `http`, `server`.
+fn words(input: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let chars: Vec<char> = input.chars().collect();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        if c.is_uppercase() && !current.is_empty() {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase() || prev.is_numeric() || (prev.is_uppercase() && next_is_lower) {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+
+        current.extend(c.to_lowercase());
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// `my-project` → `my_project`
+pub fn snake_case(input: &str) -> String {
+    words(input).join("_")
+}
+
+/// `my-project` → `MyProject`
+pub fn pascal_case(input: &str) -> String {
+    words(input)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// `my_project` → `my-project`
+pub fn kebab_case(input: &str) -> String {
+    words(input).join("-")
+}
+
+/// `my-project` → `MY_PROJECT`
+pub fn shouty_snake(input: &str) -> String {
+    snake_case(input).to_uppercase()
+}
+
+/// The identifier Cargo uses for a package in `use` paths
+///
+/// Like Cargo, this keeps the case and replaces `-` with `_`. Any other
+/// character that cannot appear in an identifier also becomes `_`, and a
+/// leading digit gets a `_` prefix.
+pub fn crate_ident(input: &str) -> String {
+    let mut ident: String = input
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    ident
+}
+
+/// Escape a value for use inside a double-quoted TOML string
+pub fn escape_toml_string(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escape a value for use inside a double-quoted Ruby string
+///
+/// On top of what [`escape_toml_string`] escapes, `#` becomes `\#` so `#{...}`
+/// is not interpolated.
+pub fn escape_ruby_string(input: &str) -> String {
+    escape_toml_string(input).replace('#', "\\#")
+}
@@ -0,0 +1,351 @@
+use super::condition::Condition;
+use crate::ProjectType;
+use anyhow::{anyhow, bail, Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name of the manifest at the root of a template directory
+pub const MANIFEST_FILE: &str = "forge-template.toml";
+
+/// Manifest describing a custom template directory and the variables it uses
+///
+/// ```toml
+/// [template]
+/// name = "house-api"
+/// description = "Our team's API server layout"
+/// project_type = "api-server"
+/// prompts = ["service_name", "tier"]
+///
+/// [variables.service_name]
+/// description = "Service name used in logs"
+/// validation = "^[a-z][a-z0-9-]*$"
+///
+/// [variables.tier]
+/// choices = ["internal", "public"]
+/// default = "internal"
+///
+/// [[files]]
+/// path = "api_server/deploy"
+/// include_if = "tier == 'public'"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct TemplateManifest {
+    pub template: TemplateInfo,
+    #[serde(default)]
+    pub variables: BTreeMap<String, TemplateVariable>,
+    #[serde(default)]
+    pub files: Vec<FileRule>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TemplateInfo {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Project type the templates target; unset applies to every type
+    #[serde(default)]
+    pub project_type: Option<String>,
+    /// Variables asked interactively, in order
+    #[serde(default)]
+    pub prompts: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VariableType {
+    #[default]
+    String,
+    Bool,
+    Integer,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TemplateVariable {
+    #[serde(rename = "type", default)]
+    pub var_type: VariableType,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub default: Option<toml::Value>,
+    #[serde(default)]
+    pub choices: Vec<String>,
+    /// Regular expression every value must match
+    #[serde(default)]
+    pub validation: Option<String>,
+}
+
+/// Rule controlling where, and whether, a template file or directory is generated
+#[derive(Debug, Clone, Deserialize)]
+pub struct FileRule {
+    /// Template file or directory, relative to the template root
+    pub path: String,
+    /// Condition that must hold for the files to be generated
+    #[serde(default)]
+    pub include_if: Option<String>,
+    /// Output file or directory in the project; may contain Tera tags
+    #[serde(default)]
+    pub output: Option<String>,
+    /// Render matching non-`.tera` files through Tera instead of copying them
+    #[serde(default)]
+    pub render: bool,
+}
+
+impl FileRule {
+    /// The part of `template_name` below this rule's path, if it matches
+    ///
+    /// An exact match on a file returns an empty string.
+    pub fn remainder<'a>(&self, template_name: &'a str) -> Option<&'a str> {
+        let path = self.path.trim_end_matches('/');
+        if template_name == path {
+            return Some("");
+        }
+        template_name
+            .strip_prefix(path)
+            .and_then(|rest| rest.strip_prefix('/'))
+    }
+
+    /// Output path for a template matched by this rule, before rendering
+    pub fn output_path(&self, remainder: &str) -> Option<String> {
+        let output = self.output.as_deref()?.trim_end_matches('/');
+        if remainder.is_empty() {
+            Some(output.to_string())
+        } else {
+            Some(format!("{}/{}", output, remainder))
+        }
+    }
+
+    /// The parsed `include_if` condition, if any
+    pub fn condition(&self) -> Result<Option<Condition>> {
+        self.include_if.as_deref().map(Condition::parse).transpose()
+    }
+}
+
+impl TemplateManifest {
+    /// Load and validate a manifest file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read template manifest: {}", path.display()))?;
+        Self::parse(&content)
+            .with_context(|| format!("Invalid template manifest: {}", path.display()))
+    }
+
+    /// Parse and validate manifest contents
+    pub fn parse(content: &str) -> Result<Self> {
+        let manifest: TemplateManifest = toml::from_str(content)?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// Load the manifests of every template directory that targets `project_type`
+    ///
+    /// Directories without a manifest are skipped.
+    pub fn discover(dirs: &[PathBuf], project_type: ProjectType) -> Result<Vec<Self>> {
+        let mut manifests = Vec::new();
+        for dir in dirs {
+            let path = dir.join(MANIFEST_FILE);
+            if !path.is_file() {
+                continue;
+            }
+            let manifest = Self::load(&path)?;
+            if manifest.applies_to(project_type) {
+                manifests.push(manifest);
+            }
+        }
+        Ok(manifests)
+    }
+
+    /// Whether this template directory targets the given project type
+    pub fn applies_to(&self, project_type: ProjectType) -> bool {
+        match &self.template.project_type {
+            Some(target) => target.parse::<ProjectType>().ok() == Some(project_type),
+            None => true,
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        if let Some(project_type) = &self.template.project_type {
+            project_type.parse::<ProjectType>()?;
+        }
+
+        for name in &self.template.prompts {
+            if !self.variables.contains_key(name) {
+                bail!("Prompt '{}' does not match a declared variable", name);
+            }
+        }
+
+        for (name, variable) in &self.variables {
+            if let Some(pattern) = &variable.validation {
+                Regex::new(pattern).with_context(|| {
+                    format!("Invalid validation pattern for variable '{}'", name)
+                })?;
+            }
+            if variable.var_type == VariableType::Bool && !variable.choices.is_empty() {
+                bail!("Variable '{}' is a bool and cannot declare choices", name);
+            }
+            variable.default_value(name)?;
+        }
+
+        for rule in &self.files {
+            rule.condition()
+                .with_context(|| format!("Invalid include_if for '{}'", rule.path))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TemplateVariable {
+    /// Text shown when prompting for this variable
+    pub fn prompt_text(&self, name: &str) -> String {
+        self.description.clone().unwrap_or_else(|| name.to_string())
+    }
+
+    /// Convert a raw value into the variable's type, checking choices and validation
+    pub fn parse_value(&self, name: &str, raw: &str) -> Result<serde_json::Value> {
+        if !self.choices.is_empty() && !self.choices.iter().any(|c| c == raw) {
+            bail!(
+                "Invalid value '{}' for variable '{}' (expected one of: {})",
+                raw,
+                name,
+                self.choices.join(", ")
+            );
+        }
+
+        if let Some(pattern) = &self.validation {
+            let regex = Regex::new(pattern)?;
+            if !regex.is_match(raw) {
+                bail!(
+                    "Invalid value '{}' for variable '{}' (must match {})",
+                    raw,
+                    name,
+                    pattern
+                );
+            }
+        }
+
+        match self.var_type {
+            VariableType::String => Ok(serde_json::json!(raw)),
+            VariableType::Bool => match raw {
+                "true" | "yes" | "1" => Ok(serde_json::json!(true)),
+                "false" | "no" | "0" => Ok(serde_json::json!(false)),
+                _ => Err(anyhow!(
+                    "Invalid value '{}' for variable '{}' (expected true or false)",
+                    raw,
+                    name
+                )),
+            },
+            VariableType::Integer => {
+                raw.parse::<i64>()
+                    .map(|n| serde_json::json!(n))
+                    .map_err(|_| {
+                        anyhow!(
+                            "Invalid value '{}' for variable '{}' (expected an integer)",
+                            raw,
+                            name
+                        )
+                    })
+            }
+        }
+    }
+
+    /// The declared default, checked against the variable's type and rules
+    pub fn default_value(&self, name: &str) -> Result<Option<serde_json::Value>> {
+        let raw = match &self.default {
+            None => return Ok(None),
+            Some(toml::Value::String(s)) => s.clone(),
+            Some(toml::Value::Boolean(b)) => b.to_string(),
+            Some(toml::Value::Integer(n)) => n.to_string(),
+            Some(other) => bail!(
+                "Unsupported default for variable '{}': {}",
+                name,
+                other.type_str()
+            ),
+        };
+        self.parse_value(name, &raw).map(Some)
+    }
+}
+
+/// Resolve template variables from manifests and `--var` assignments
+///
+/// Values passed explicitly win. Declared variables that were not passed are
+/// handed to `ask` when they are listed in `prompts` or have no default; `ask`
+/// returns `None` to fall back to the default. Assignments that no manifest
+/// declares are passed through as strings.
+pub fn resolve_variables<F>(
+    manifests: &[TemplateManifest],
+    assignments: &HashMap<String, String>,
+    mut ask: F,
+) -> Result<HashMap<String, serde_json::Value>>
+where
+    F: FnMut(&str, &TemplateVariable) -> Result<Option<String>>,
+{
+    let mut declared: BTreeMap<&str, &TemplateVariable> = BTreeMap::new();
+    let mut prompts: Vec<&str> = Vec::new();
+    for manifest in manifests {
+        for (name, variable) in &manifest.variables {
+            declared.insert(name, variable);
+        }
+        for name in &manifest.template.prompts {
+            if !prompts.contains(&name.as_str()) {
+                prompts.push(name);
+            }
+        }
+    }
+
+    let mut values = HashMap::new();
+    for (name, raw) in assignments {
+        let value = match declared.get(name.as_str()) {
+            Some(variable) => variable.parse_value(name, raw)?,
+            None => serde_json::json!(raw),
+        };
+        values.insert(name.clone(), value);
+    }
+
+    // Prompted variables first, in manifest order, then any remaining ones
+    let remaining = declared.keys().filter(|name| !prompts.contains(name));
+    let order: Vec<&str> = prompts.iter().copied().chain(remaining.copied()).collect();
+
+    for name in order {
+        if values.contains_key(name) {
+            continue;
+        }
+        let variable = declared[name];
+        let default = variable.default_value(name)?;
+
+        let answer = if prompts.contains(&name) || default.is_none() {
+            ask(name, variable)?
+        } else {
+            None
+        };
+
+        let value = match answer {
+            Some(raw) => variable.parse_value(name, &raw)?,
+            None => default.ok_or_else(|| {
+                anyhow!(
+                    "Missing value for template variable '{}' (pass --var {}=<value>)",
+                    name,
+                    name
+                )
+            })?,
+        };
+        values.insert(name.to_string(), value);
+    }
+
+    Ok(values)
+}
+
+/// Parse a `key=value` assignment as given to `--var`
+pub fn parse_assignment(input: &str) -> Result<(String, String)> {
+    let (key, value) = input
+        .split_once('=')
+        .ok_or_else(|| anyhow!("Invalid variable '{}' (expected key=value)", input))?;
+    let key = key.trim();
+    if key.is_empty() {
+        bail!("Invalid variable '{}' (missing key)", input);
+    }
+    Ok((key.to_string(), value.to_string()))
+}
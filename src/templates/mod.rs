@@ -1,16 +1,50 @@
+pub mod check;
+pub mod condition;
 pub mod conditional;
+pub mod filters;
+pub mod manifest;
 
-use anyhow::Result;
+use crate::ProjectType;
+use anyhow::{bail, Context as _, Result};
 use include_dir::{include_dir, Dir};
-use std::collections::HashSet;
+use manifest::{FileRule, TemplateManifest, MANIFEST_FILE};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use tera::{Context, Tera};
 
 // Embed all templates at compile time
 static TEMPLATES_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/templates");
 
+// Parsed copy of the embedded templates, shared by every engine
+static EMBEDDED_TERA: OnceLock<Tera> = OnceLock::new();
+
 pub struct TemplateEngine {
     tera: Tera,
     features: HashSet<String>,
+    manifests: Vec<TemplateManifest>,
+    assets: BTreeMap<String, Asset>,
+    /// Source of templates loaded from disk or added at runtime
+    sources: HashMap<String, String>,
+}
+
+/// A file produced from the template tree
+#[derive(Debug, Clone)]
+pub struct RenderedFile {
+    /// Path relative to the project root
+    pub path: String,
+    pub contents: Vec<u8>,
+    /// Unix permissions of the source file, when it was copied verbatim
+    pub mode: Option<u32>,
+}
+
+/// A non-`.tera` file copied into projects byte-for-byte
+#[derive(Debug, Clone)]
+struct Asset {
+    contents: Cow<'static, [u8]>,
+    mode: Option<u32>,
 }
 
 impl TemplateEngine {
@@ -19,18 +53,98 @@ impl TemplateEngine {
     }
 
     pub fn with_features(features: Vec<String>) -> Result<Self> {
-        let mut tera = Tera::default();
+        let features: HashSet<String> = features.into_iter().collect();
+        let tera = Self::build_tera(&features, &HashSet::new())?;
 
-        // Load all embedded templates
-        Self::load_embedded_templates(&mut tera)?;
+        let mut manifests = Vec::new();
+        if let Some(contents) = TEMPLATES_DIR
+            .get_file(MANIFEST_FILE)
+            .and_then(|file| file.contents_utf8())
+        {
+            manifests.push(
+                TemplateManifest::parse(contents).context("Invalid embedded template manifest")?,
+            );
+        }
+
+        let mut assets = BTreeMap::new();
+        Self::collect_embedded_assets(&TEMPLATES_DIR, &mut assets);
 
         Ok(Self {
             tera,
-            features: features.into_iter().collect(),
+            features,
+            manifests,
+            assets,
+            sources: HashMap::new(),
         })
     }
 
+    /// Layer user template directories on top of the embedded templates
+    ///
+    /// Each directory mirrors the layout of the bundled `templates/` tree. A
+    /// file with the same relative path as an embedded template replaces it,
+    /// and directories later in the list override earlier ones.
+    pub fn with_template_dirs(mut self, dirs: &[PathBuf]) -> Result<Self> {
+        let mut templates = Vec::new();
+        let mut shadowed = HashSet::new();
+        for dir in dirs {
+            if !dir.is_dir() {
+                anyhow::bail!("Template directory not found: {}", dir.display());
+            }
+            self.load_filesystem_templates(dir, "", &mut templates, &mut shadowed)
+                .with_context(|| format!("Failed to load templates from {}", dir.display()))?;
+
+            let manifest_path = dir.join(MANIFEST_FILE);
+            if manifest_path.is_file() {
+                self.manifests.push(TemplateManifest::load(&manifest_path)?);
+            }
+        }
+
+        // Templates replaced by an asset are left out of a rebuilt engine
+        shadowed.retain(|name| self.has_template(name));
+        if !shadowed.is_empty() {
+            let mut tera = Self::build_tera(&self.features, &shadowed)?;
+            tera.add_raw_templates(
+                self.sources
+                    .iter()
+                    .filter(|(name, _)| !shadowed.contains(*name)),
+            )?;
+            self.tera = tera;
+            self.sources.retain(|name, _| !shadowed.contains(name));
+        }
+
+        if !templates.is_empty() {
+            self.tera
+                .add_raw_templates(templates.clone())
+                .context("Failed to load custom templates")?;
+            self.sources.extend(templates);
+        }
+        Ok(self)
+    }
+
+    /// Add a template at runtime, replacing any template with the same name
+    ///
+    /// The template can include or import any loaded template, such as the
+    /// shared `partials/` and `macros/`.
+    pub fn add_template(&mut self, name: &str, content: &str) -> Result<()> {
+        self.tera
+            .add_raw_template(name, content)
+            .with_context(|| format!("Failed to parse template '{}'", name))?;
+        self.sources.insert(name.to_string(), content.to_string());
+        Ok(())
+    }
+
     pub fn render(&self, template_name: &str, context: &Context) -> Result<String> {
+        let context = self.feature_context(context);
+        let rendered = self.tera.render(template_name, &context)?;
+        Ok(rendered)
+    }
+
+    pub fn render_with_context(&self, template_name: &str, context: &Context) -> Result<String> {
+        self.render(template_name, context)
+    }
+
+    /// Add the enabled features to a template context
+    fn feature_context(&self, context: &Context) -> Context {
         let mut context = context.clone();
 
         // Add features to context for conditional rendering
@@ -41,101 +155,301 @@ impl TemplateEngine {
 
         // Add individual feature flags
         for feature in &self.features {
-            context.insert(&format!("has_{}", feature), &true);
+            context.insert(format!("has_{}", feature), &true);
         }
 
-        let rendered = self.tera.render(template_name, &context)?;
-        Ok(rendered)
+        context
     }
 
-    pub fn render_with_context(&self, template_name: &str, context: &Context) -> Result<String> {
-        self.render(template_name, context)
+    /// Render every template under `dir`, returning output paths relative to it
+    ///
+    /// The `.tera` extension is stripped from each path, so
+    /// `api_server/src/main.rs.tera` renders to `src/main.rs`, and Tera tags in
+    /// the path are rendered too. Other files are copied byte-for-byte unless a
+    /// rule sets `render = true`. `[[files]]` rules from template manifests can
+    /// skip files with `include_if` or pull templates from elsewhere in the tree
+    /// with `output`; when several rules match, the most specific path wins.
+    pub fn render_directory(&self, dir: &str, context: &Context) -> Result<Vec<RenderedFile>> {
+        self.try_render_directory(dir, context)?
+            .into_iter()
+            .map(|(_, file)| file)
+            .collect()
     }
 
-    /// Load all embedded templates recursively
-    fn load_embedded_templates(tera: &mut Tera) -> Result<()> {
-        Self::load_directory_templates(tera, &TEMPLATES_DIR, "")?;
-        Ok(())
-    }
+    /// Like [`render_directory`](Self::render_directory), but keeps going past failures
+    ///
+    /// Each entry pairs the template name with its rendered file or the
+    /// error from rendering it. Only invalid `[[files]]` rules fail the call.
+    pub fn try_render_directory(
+        &self,
+        dir: &str,
+        context: &Context,
+    ) -> Result<Vec<(String, Result<RenderedFile>)>> {
+        let context = self.feature_context(context);
+        let values = context.clone().into_json();
+        let rules = self.file_rules(&values);
+        let prefix = format!("{}/", dir.trim_end_matches('/'));
 
-    /// Recursively load templates from an embedded directory
-    fn load_directory_templates(tera: &mut Tera, dir: &Dir<'_>, prefix: &str) -> Result<()> {
-        // Process all files in the current directory
-        for file in dir.files() {
-            if let Some(file_name) = file.path().file_name() {
-                if let Some(file_name_str) = file_name.to_str() {
-                    if file_name_str.ends_with(".tera") {
-                        // Create template name with directory prefix
-                        let template_name = if prefix.is_empty() {
-                            file_name_str.to_string()
-                        } else {
-                            format!("{}/{}", prefix, file_name_str)
-                        };
-
-                        // Get file contents as string
-                        if let Some(contents) = file.contents_utf8() {
-                            tera.add_raw_template(&template_name, contents)?;
-                        }
+        let mut names: Vec<&str> = self
+            .tera
+            .get_template_names()
+            .chain(self.assets.keys().map(String::as_str))
+            .collect();
+        names.sort();
+
+        let mut rendered = Vec::new();
+        for name in names {
+            let rule = rules
+                .iter()
+                .filter_map(|rule| rule.remainder(name).map(|rest| (*rule, rest)))
+                .max_by_key(|(rule, _)| rule.path.trim_end_matches('/').len());
+
+            let output = match rule.and_then(|(rule, rest)| rule.output_path(rest)) {
+                Some(output) => output,
+                None => match name.strip_prefix(&prefix) {
+                    Some(rest) => rest.to_string(),
+                    None => continue,
+                },
+            };
+
+            if let Some((rule, _)) = rule {
+                if let Some(condition) = rule.condition()? {
+                    if !condition.evaluate(&self.features, &values) {
+                        continue;
                     }
                 }
             }
+
+            let file = self.render_file(name, rule.map(|(rule, _)| rule), &output, &context);
+            rendered.push((name.to_string(), file));
         }
 
-        // Recursively process subdirectories
-        for subdir in dir.dirs() {
-            if let Some(dir_name) = subdir.path().file_name() {
-                if let Some(dir_name_str) = dir_name.to_str() {
-                    let new_prefix = if prefix.is_empty() {
-                        dir_name_str.to_string()
-                    } else {
-                        format!("{}/{}", prefix, dir_name_str)
-                    };
-                    Self::load_directory_templates(tera, subdir, &new_prefix)?;
+        Ok(rendered)
+    }
+
+    /// Render one template or asset to its output path
+    fn render_file(
+        &self,
+        name: &str,
+        rule: Option<&FileRule>,
+        output: &str,
+        context: &Context,
+    ) -> Result<RenderedFile> {
+        let path = render_path(&self.tera, output.trim_end_matches(".tera"), context)
+            .with_context(|| format!("Failed to render path of template '{}'", name))?;
+
+        let file = match self.assets.get(name) {
+            Some(asset) if rule.is_some_and(|rule| rule.render) => {
+                let source = std::str::from_utf8(&asset.contents)
+                    .with_context(|| format!("Cannot render non-UTF-8 file '{}'", name))?;
+                let mut tera = self.tera.clone();
+                tera.add_raw_template(name, source)?;
+                let content = tera
+                    .render(name, context)
+                    .with_context(|| format!("Failed to render template '{}'", name))?;
+                RenderedFile {
+                    path,
+                    contents: content.into_bytes(),
+                    mode: asset.mode,
                 }
             }
-        }
+            Some(asset) => RenderedFile {
+                path,
+                contents: asset.contents.to_vec(),
+                mode: asset.mode,
+            },
+            None => {
+                let content = self
+                    .tera
+                    .render(name, context)
+                    .with_context(|| format!("Failed to render template '{}'", name))?;
+                RenderedFile {
+                    path,
+                    contents: content.into_bytes(),
+                    mode: None,
+                }
+            }
+        };
+        Ok(file)
+    }
 
-        Ok(())
+    /// File rules from every manifest that applies to the context's project type
+    fn file_rules(&self, values: &serde_json::Value) -> Vec<&FileRule> {
+        let project_type = values
+            .get("project_type")
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.parse::<ProjectType>().ok());
+
+        self.manifests
+            .iter()
+            .filter(|manifest| match project_type {
+                Some(project_type) => manifest.applies_to(project_type),
+                None => manifest.template.project_type.is_none(),
+            })
+            .flat_map(|manifest| manifest.files.iter())
+            .collect()
     }
 
-    /// Get a list of all available templates
-    pub fn list_templates(&self) -> Vec<String> {
+    /// A Tera instance with the embedded templates, filters and feature functions
+    fn build_tera(features: &HashSet<String>, skip: &HashSet<String>) -> Result<Tera> {
+        let mut tera = Tera::default();
+
+        // Load all embedded templates
+        Self::load_embedded_templates(&mut tera, skip)?;
+
+        filters::register(&mut tera);
+        conditional::register_feature_functions(&mut tera, features);
+        Ok(tera)
+    }
+
+    /// Load all embedded templates recursively, except those in `skip`
+    ///
+    /// The embedded set never changes, so it is parsed once per process and
+    /// copied into each new engine. Skipping templates parses them afresh.
+    fn load_embedded_templates(tera: &mut Tera, skip: &HashSet<String>) -> Result<()> {
         let mut templates = Vec::new();
-        Self::collect_template_names(&TEMPLATES_DIR, "", &mut templates);
-        templates.sort();
-        templates
+        if !skip.is_empty() {
+            Self::load_directory_templates(&mut templates, &TEMPLATES_DIR);
+            templates.retain(|(name, _)| !skip.contains(name));
+            tera.add_raw_templates(templates)?;
+            return Ok(());
+        }
+
+        if let Some(parsed) = EMBEDDED_TERA.get() {
+            tera.extend(parsed)?;
+            return Ok(());
+        }
+
+        Self::load_directory_templates(&mut templates, &TEMPLATES_DIR);
+        let mut parsed = Tera::default();
+        parsed.add_raw_templates(templates)?;
+        tera.extend(&parsed)?;
+        let _ = EMBEDDED_TERA.set(parsed);
+        Ok(())
     }
 
-    fn collect_template_names(dir: &Dir<'_>, prefix: &str, templates: &mut Vec<String>) {
+    /// Recursively collect templates from an embedded directory
+    ///
+    /// Templates are added in one batch afterwards, so a template can import
+    /// macros from a file that sorts after it.
+    fn load_directory_templates(templates: &mut Vec<(String, &'static str)>, dir: &Dir<'static>) {
         for file in dir.files() {
-            if let Some(file_name) = file.path().file_name() {
-                if let Some(file_name_str) = file_name.to_str() {
-                    if file_name_str.ends_with(".tera") {
-                        let template_name = if prefix.is_empty() {
-                            file_name_str.to_string()
-                        } else {
-                            format!("{}/{}", prefix, file_name_str)
-                        };
-                        templates.push(template_name);
-                    }
+            let Some(name) = file.path().to_str() else {
+                continue;
+            };
+            if name.ends_with(".tera") {
+                if let Some(contents) = file.contents_utf8() {
+                    templates.push((name.to_string(), contents));
                 }
             }
         }
 
         for subdir in dir.dirs() {
-            if let Some(dir_name) = subdir.path().file_name() {
-                if let Some(dir_name_str) = dir_name.to_str() {
-                    let new_prefix = if prefix.is_empty() {
-                        dir_name_str.to_string()
-                    } else {
-                        format!("{}/{}", prefix, dir_name_str)
-                    };
-                    Self::collect_template_names(subdir, &new_prefix, templates);
-                }
+            Self::load_directory_templates(templates, subdir);
+        }
+    }
+
+    /// Collect every embedded file that is not a template
+    fn collect_embedded_assets(dir: &Dir<'static>, assets: &mut BTreeMap<String, Asset>) {
+        for file in dir.files() {
+            let Some(name) = file.path().to_str() else {
+                continue;
+            };
+            if name.ends_with(".tera") || name == MANIFEST_FILE {
+                continue;
             }
+            assets.insert(
+                name.to_string(),
+                Asset {
+                    contents: Cow::Borrowed(file.contents()),
+                    mode: None,
+                },
+            );
+        }
+
+        for subdir in dir.dirs() {
+            Self::collect_embedded_assets(subdir, assets);
         }
     }
 
+    /// Recursively load templates and assets from a directory on disk
+    ///
+    /// A file replaces an earlier one with the same output path, so an asset
+    /// can override a template and the other way round. Templates are pushed
+    /// onto `templates` for the caller to add in one batch, and templates an
+    /// asset replaces are collected in `shadowed`.
+    fn load_filesystem_templates(
+        &mut self,
+        dir: &Path,
+        prefix: &str,
+        templates: &mut Vec<(String, String)>,
+        shadowed: &mut HashSet<String>,
+    ) -> Result<()> {
+        let mut entries = fs::read_dir(dir)?.collect::<std::io::Result<Vec<_>>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            let path = entry.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let template_name = if prefix.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+
+            if path.is_dir() {
+                self.load_filesystem_templates(&path, &template_name, templates, shadowed)?;
+            } else if let Some(asset_name) = template_name.strip_suffix(".tera") {
+                let contents = fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read template {}", path.display()))?;
+                self.assets.remove(asset_name);
+                templates.push((template_name, contents));
+            } else if template_name != MANIFEST_FILE {
+                let contents = fs::read(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                let replaced = format!("{}.tera", template_name);
+                templates.retain(|(name, _)| *name != replaced);
+                shadowed.insert(replaced);
+                self.assets.insert(
+                    template_name,
+                    Asset {
+                        contents: Cow::Owned(contents),
+                        mode: file_mode(&path)?,
+                    },
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The source text of a loaded template
+    pub fn template_source(&self, name: &str) -> Option<&str> {
+        if !self.has_template(name) {
+            return None;
+        }
+        match self.sources.get(name) {
+            Some(source) => Some(source),
+            None => TEMPLATES_DIR
+                .get_file(name)
+                .and_then(|file| file.contents_utf8()),
+        }
+    }
+
+    fn has_template(&self, name: &str) -> bool {
+        self.tera.get_template_names().any(|loaded| loaded == name)
+    }
+
+    /// Get a list of all available templates, including user overrides
+    pub fn list_templates(&self) -> Vec<String> {
+        let mut templates: Vec<String> =
+            self.tera.get_template_names().map(str::to_string).collect();
+        templates.sort();
+        templates
+    }
+
     /// Get templates for specific features
     pub fn get_feature_templates(&self, features: &[String]) -> Vec<String> {
         let mut templates = Vec::new();
@@ -166,6 +480,7 @@ impl TemplateEngine {
     /// Add a feature
     pub fn add_feature(&mut self, feature: String) {
         self.features.insert(feature);
+        conditional::register_feature_functions(&mut self.tera, &self.features);
     }
 
     /// Get all enabled features
@@ -173,3 +488,36 @@ impl TemplateEngine {
         self.features.iter().cloned().collect()
     }
 }
+
+/// Unix permissions of a file, preserved when it is copied
+#[cfg(unix)]
+fn file_mode(path: &Path) -> Result<Option<u32>> {
+    use std::os::unix::fs::PermissionsExt;
+    Ok(Some(fs::metadata(path)?.permissions().mode() & 0o7777))
+}
+
+#[cfg(not(unix))]
+fn file_mode(_path: &Path) -> Result<Option<u32>> {
+    Ok(None)
+}
+
+/// Render Tera tags in an output path and check it stays inside the project
+///
+/// Paths are rendered with the engine's own filters and feature functions.
+fn render_path(tera: &Tera, path: &str, context: &Context) -> Result<String> {
+    let rendered = if path.contains("{{") || path.contains("{%") {
+        tera.clone().render_str(path, context)?
+    } else {
+        path.to_string()
+    };
+
+    if rendered.starts_with('/')
+        || rendered
+            .split('/')
+            .any(|segment| segment.is_empty() || segment == "." || segment == "..")
+    {
+        bail!("Invalid output path '{}'", rendered);
+    }
+
+    Ok(rendered)
+}
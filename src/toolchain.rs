@@ -0,0 +1,112 @@
+//! Rust edition and minimum supported Rust version of generated projects
+
+use anyhow::{anyhow, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// Rust edition written to every generated manifest
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum Edition {
+    #[value(name = "2018")]
+    E2018,
+    #[default]
+    #[value(name = "2021")]
+    E2021,
+    #[value(name = "2024")]
+    E2024,
+}
+
+impl Edition {
+    pub const ALL: [Edition; 3] = [Edition::E2024, Edition::E2021, Edition::E2018];
+
+    /// The dependency resolver this edition uses by default, for workspace roots
+    pub fn resolver(&self) -> &'static str {
+        match self {
+            Edition::E2018 => "1",
+            Edition::E2021 => "2",
+            Edition::E2024 => "3",
+        }
+    }
+
+    /// The first Rust release that supports this edition
+    pub fn min_rust_version(&self) -> &'static str {
+        match self {
+            Edition::E2018 => "1.31",
+            Edition::E2021 => "1.56",
+            Edition::E2024 => "1.85",
+        }
+    }
+}
+
+impl fmt::Display for Edition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Edition::E2018 => write!(f, "2018"),
+            Edition::E2021 => write!(f, "2021"),
+            Edition::E2024 => write!(f, "2024"),
+        }
+    }
+}
+
+impl FromStr for Edition {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "2018" => Ok(Edition::E2018),
+            "2021" => Ok(Edition::E2021),
+            "2024" => Ok(Edition::E2024),
+            _ => Err(anyhow!(
+                "Unsupported edition: {} (expected 2018, 2021 or 2024)",
+                s
+            )),
+        }
+    }
+}
+
+/// Check a `rust-version` such as `1.75` or `1.75.0` against the edition it builds
+pub fn validate_rust_version(version: &str, edition: Edition) -> Result<()> {
+    let parsed = parse_version(version).ok_or_else(|| {
+        anyhow!(
+            "Invalid Rust version '{}': expected MAJOR.MINOR or MAJOR.MINOR.PATCH, like 1.75",
+            version
+        )
+    })?;
+    let required = parse_version(edition.min_rust_version()).expect("edition minimums parse");
+    if parsed < required {
+        return Err(anyhow!(
+            "Rust {} does not support edition {}; it needs Rust {} or later",
+            version,
+            edition,
+            edition.min_rust_version()
+        ));
+    }
+    Ok(())
+}
+
+/// Contents of a `rust-toolchain.toml` pinning `version`, with any extra compilation targets
+pub fn toolchain_file(version: &str, targets: &[&str]) -> String {
+    let mut contents = format!(
+        "[toolchain]\nchannel = \"{}\"\ncomponents = [\"rustfmt\", \"clippy\"]\n",
+        version
+    );
+    if !targets.is_empty() {
+        let targets: Vec<String> = targets.iter().map(|t| format!("\"{}\"", t)).collect();
+        contents.push_str(&format!("targets = [{}]\n", targets.join(", ")));
+    }
+    contents
+}
+
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = match parts.next() {
+        Some(patch) => patch.parse().ok()?,
+        None => 0,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
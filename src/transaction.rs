@@ -0,0 +1,271 @@
+//! Staged, all-or-nothing writes for project generation
+//!
+//! New projects are generated into a [`StagingDir`] next to the destination
+//! and renamed into place once every step succeeded. `init` cannot rename over
+//! an existing directory, so it writes the planned files one at a time
+//! through an [`InitJournal`] that can undo them.
+
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// A scratch directory that is removed on drop unless committed
+pub struct StagingDir {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl StagingDir {
+    /// Create a hidden scratch directory inside `parent`
+    ///
+    /// The name starts with `.{label}.forge-` so leftovers from a killed
+    /// process are easy to recognise.
+    pub fn new_in(parent: &Path, label: &str) -> Result<Self> {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+
+        for attempt in 0u32..100 {
+            let path = parent.join(format!(
+                ".{}.forge-{}-{}",
+                label,
+                std::process::id(),
+                attempt
+            ));
+            match fs::create_dir(&path) {
+                Ok(()) => return Ok(Self { path, keep: false }),
+                Err(err) if err.kind() == ErrorKind::AlreadyExists => continue,
+                Err(err) => {
+                    return Err(err).with_context(|| {
+                        format!("Failed to create staging directory in {}", parent.display())
+                    })
+                }
+            }
+        }
+        Err(anyhow!(
+            "Could not find a free staging directory name in {}",
+            parent.display()
+        ))
+    }
+
+    /// Create a scratch directory next to `target`, on the same filesystem
+    pub fn next_to(target: &Path) -> Result<Self> {
+        let parent = match target.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let label = target
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("project");
+        Self::new_in(parent, label)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Move the staged directory to `target`
+    ///
+    /// `target` must not exist or be an empty directory.
+    pub fn commit(mut self, target: &Path) -> Result<()> {
+        if target.is_dir() {
+            fs::remove_dir(target)
+                .with_context(|| format!("Directory '{}' is not empty", target.display()))?;
+        }
+        fs::rename(&self.path, target).with_context(|| {
+            format!("Failed to move generated project into {}", target.display())
+        })?;
+        self.keep = true;
+        Ok(())
+    }
+}
+
+impl Drop for StagingDir {
+    fn drop(&mut self) {
+        if !self.keep {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+}
+
+/// One change made to the target directory
+#[derive(Debug)]
+enum JournalEntry {
+    CreatedDir(PathBuf),
+    CreatedFile(PathBuf),
+    /// An existing file was replaced; the original was moved to `backup`
+    ReplacedFile {
+        path: PathBuf,
+        backup: PathBuf,
+    },
+}
+
+/// Record of the changes made while applying files to an existing directory
+///
+/// Every file written through the journal is recorded, and files it replaces
+/// are moved aside first. [`rollback`](Self::rollback) undoes the changes in
+/// reverse order; dropping the journal without calling
+/// [`commit`](Self::commit) rolls back too.
+pub struct InitJournal {
+    root: PathBuf,
+    backups: StagingDir,
+    entries: Vec<JournalEntry>,
+    finished: bool,
+}
+
+impl InitJournal {
+    /// Start a journal for changes under `root`
+    ///
+    /// Replaced files are kept in a hidden directory inside `root` until the
+    /// journal is committed or rolled back.
+    pub fn new(root: &Path) -> Result<Self> {
+        Ok(Self {
+            root: root.to_path_buf(),
+            backups: StagingDir::new_in(root, "backup")?,
+            entries: Vec::new(),
+            finished: false,
+        })
+    }
+
+    /// Copy `source` to `relative` under the root, creating parent directories
+    ///
+    /// An existing file at that path is backed up and replaced.
+    pub fn write_file(&mut self, relative: &Path, source: &Path) -> Result<()> {
+        self.write_with(relative, |target| fs::copy(source, target).map(drop))
+    }
+
+    /// Write `contents` to `relative` under the root, like [`write_file`](Self::write_file)
+    pub fn write_contents(
+        &mut self,
+        relative: &Path,
+        contents: &[u8],
+        mode: Option<u32>,
+    ) -> Result<()> {
+        self.write_with(relative, |target| {
+            fs::write(target, contents)?;
+
+            #[cfg(unix)]
+            if let Some(mode) = mode {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(target, fs::Permissions::from_mode(mode))?;
+            }
+            #[cfg(not(unix))]
+            let _ = mode;
+            Ok(())
+        })
+    }
+
+    /// Prepare `relative`, write it, and record a new file once the write succeeded
+    ///
+    /// A new file left half-written by a failed write is removed again.
+    fn write_with(
+        &mut self,
+        relative: &Path,
+        write: impl FnOnce(&Path) -> std::io::Result<()>,
+    ) -> Result<()> {
+        let created = !self.root.join(relative).exists();
+        let target = self.prepare(relative)?;
+        if let Err(err) = write(&target) {
+            if created {
+                let _ = fs::remove_file(&target);
+            }
+            return Err(err).with_context(|| format!("Failed to write {}", target.display()));
+        }
+
+        if created {
+            self.entries.push(JournalEntry::CreatedFile(target));
+        }
+        Ok(())
+    }
+
+    /// Create the parents of `relative` and move any existing file there aside
+    fn prepare(&mut self, relative: &Path) -> Result<PathBuf> {
+        let target = self.root.join(relative);
+        if let Some(parent) = relative.parent() {
+            self.create_dir_all(parent)?;
+        }
+
+        if target.is_file() {
+            let backup = self.backups.path().join(relative);
+            if let Some(parent) = backup.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(&target, &backup)
+                .with_context(|| format!("Failed to back up {}", target.display()))?;
+            self.entries.push(JournalEntry::ReplacedFile {
+                path: target.clone(),
+                backup,
+            });
+        }
+        Ok(target)
+    }
+
+    /// Create a directory and any missing parents under the root
+    pub fn create_dir_all(&mut self, relative: &Path) -> Result<()> {
+        let mut current = self.root.clone();
+        for component in relative.components() {
+            current.push(component);
+            if current.is_dir() {
+                continue;
+            }
+            fs::create_dir(&current)
+                .with_context(|| format!("Failed to create directory {}", current.display()))?;
+            self.entries.push(JournalEntry::CreatedDir(current.clone()));
+        }
+        Ok(())
+    }
+
+    /// Paths of the files written so far, relative to the root
+    pub fn written_files(&self) -> Vec<PathBuf> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match entry {
+                JournalEntry::CreatedFile(path) | JournalEntry::ReplacedFile { path, .. } => {
+                    path.strip_prefix(&self.root).ok().map(Path::to_path_buf)
+                }
+                JournalEntry::CreatedDir(_) => None,
+            })
+            .collect()
+    }
+
+    /// Keep the changes and discard the backups
+    pub fn commit(mut self) {
+        self.finished = true;
+    }
+
+    /// Undo every recorded change, newest first
+    pub fn rollback(mut self) -> Result<()> {
+        self.finished = true;
+        self.undo()
+    }
+
+    fn undo(&mut self) -> Result<()> {
+        let mut first_error = None;
+        while let Some(entry) = self.entries.pop() {
+            let result = match &entry {
+                JournalEntry::CreatedFile(path) => fs::remove_file(path),
+                JournalEntry::CreatedDir(path) => fs::remove_dir(path),
+                JournalEntry::ReplacedFile { path, backup } => {
+                    let _ = fs::remove_file(path);
+                    fs::rename(backup, path)
+                }
+            };
+            if let Err(err) = result {
+                first_error.get_or_insert_with(|| anyhow!("Failed to undo {:?}: {}", entry, err));
+            }
+        }
+        match first_error {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for InitJournal {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.undo();
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! Checking that a generated project really works
+//!
+//! `--verify` runs after generation. Every TOML and YAML file is parsed,
+//! `cargo metadata --no-deps --offline` validates the manifests, and
+//! `cargo check --offline` builds the project when its dependencies can come
+//! from a vendor directory or the local registry cache. Nothing touches the
+//! network: a check that would need crates that are not cached is skipped
+//! instead of failing. Only with [`Verifier::with_online`] are dependencies
+//! that cannot be resolved offline looked up in the registry index, so a
+//! misspelled crate or an impossible version fails too.
+
+use crate::templates::check::check_output;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directories never walked when parsing files
+const SKIPPED_DIRS: &[&str] = &["target", ".git", "node_modules", "vendor"];
+
+/// `cargo` output that means the environment, not the project, stopped a check
+const ENVIRONMENT_ERRORS: &[(&str, &str)] = &[
+    (
+        "--offline was specified",
+        "dependencies are not in the local cache",
+    ),
+    (
+        "can't find crate for `core`",
+        "the compilation target is not installed",
+    ),
+    (
+        "can't find crate for `std`",
+        "the compilation target is not installed",
+    ),
+];
+
+/// `cargo` output of a dependency that could not be resolved
+///
+/// Offline, this is also what a dependency that was never downloaded gives.
+const RESOLUTION_ERRORS: &[&str] = &["failed to select a version", "no matching package named"];
+
+/// Outcome of one verification step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifyStatus {
+    Passed,
+    /// Failed with the tool's error output
+    Failed(String),
+    /// Not run, with the reason
+    Skipped(String),
+}
+
+/// One row of a [`VerifyReport`]
+#[derive(Debug, Clone)]
+pub struct VerifyCheck {
+    /// Such as `cargo metadata` or `Parse TOML (3 files)`
+    pub name: String,
+    pub status: VerifyStatus,
+}
+
+/// Results of verifying a generated project
+#[derive(Debug, Default)]
+pub struct VerifyReport {
+    pub checks: Vec<VerifyCheck>,
+}
+
+impl VerifyReport {
+    pub fn passed(&self) -> bool {
+        !self
+            .checks
+            .iter()
+            .any(|check| matches!(check.status, VerifyStatus::Failed(_)))
+    }
+
+    /// The check named `name`, if it ran
+    pub fn check(&self, name: &str) -> Option<&VerifyCheck> {
+        self.checks
+            .iter()
+            .find(|check| check.name.starts_with(name))
+    }
+
+    fn push(&mut self, name: impl Into<String>, status: VerifyStatus) {
+        self.checks.push(VerifyCheck {
+            name: name.into(),
+            status,
+        });
+    }
+}
+
+/// Runs the verification steps on a project directory
+pub struct Verifier {
+    cargo: PathBuf,
+    cargo_check: bool,
+    online: bool,
+}
+
+impl Verifier {
+    pub fn new() -> Self {
+        // `CARGO` is set when running under cargo, so the same toolchain is used
+        let cargo = std::env::var_os("CARGO")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("cargo"));
+        Self {
+            cargo,
+            cargo_check: true,
+            online: false,
+        }
+    }
+
+    /// Whether to run `cargo check` when a dependency cache is available
+    pub fn with_cargo_check(mut self, cargo_check: bool) -> Self {
+        self.cargo_check = cargo_check;
+        self
+    }
+
+    /// Whether to look dependencies that cannot be resolved offline up in the registry index
+    pub fn with_online(mut self, online: bool) -> Self {
+        self.online = online;
+        self
+    }
+
+    pub fn run(&self, dir: &Path) -> VerifyReport {
+        let mut report = VerifyReport::default();
+
+        let mut files = Vec::new();
+        collect_files(dir, dir, &mut files);
+        for (label, extensions) in [("TOML", &["toml"][..]), ("YAML", &["yml", "yaml"][..])] {
+            let matching: Vec<&(String, PathBuf)> = files
+                .iter()
+                .filter(|(relative, _)| {
+                    extensions
+                        .iter()
+                        .any(|ext| relative.ends_with(&format!(".{}", ext)))
+                })
+                .collect();
+            if matching.is_empty() {
+                continue;
+            }
+            let errors: Vec<String> = matching
+                .iter()
+                .filter_map(|(relative, path)| parse_error(relative, path))
+                .collect();
+            let status = if errors.is_empty() {
+                VerifyStatus::Passed
+            } else {
+                VerifyStatus::Failed(errors.join("\n"))
+            };
+            let count = match matching.len() {
+                1 => "1 file".to_string(),
+                n => format!("{} files", n),
+            };
+            report.push(format!("Parse {} ({})", label, count), status);
+        }
+
+        let metadata = self.cargo(
+            dir,
+            &[
+                "metadata",
+                "--no-deps",
+                "--offline",
+                "--format-version",
+                "1",
+            ],
+        );
+        let manifests_valid = metadata == VerifyStatus::Passed;
+        report.push("cargo metadata", metadata);
+
+        let check = if !self.cargo_check {
+            VerifyStatus::Skipped("disabled".to_string())
+        } else if !manifests_valid {
+            VerifyStatus::Skipped("cargo metadata failed".to_string())
+        } else if !has_dependency_cache(dir) {
+            VerifyStatus::Skipped("no vendored sources or registry cache".to_string())
+        } else {
+            self.cargo(dir, &["check", "--offline", "--quiet"])
+        };
+        report.push("cargo check", check);
+
+        report
+    }
+
+    fn cargo(&self, dir: &Path, args: &[&str]) -> VerifyStatus {
+        let output = match Command::new(&self.cargo)
+            .args(args)
+            .current_dir(dir)
+            .output()
+        {
+            Ok(output) => output,
+            Err(e) => return VerifyStatus::Skipped(format!("cargo could not be run: {}", e)),
+        };
+        if output.status.success() {
+            return VerifyStatus::Passed;
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if RESOLUTION_ERRORS
+            .iter()
+            .any(|pattern| stderr.contains(pattern))
+        {
+            return if self.online {
+                self.resolve_online(dir)
+            } else {
+                VerifyStatus::Skipped("dependencies could not be resolved offline".to_string())
+            };
+        }
+        match ENVIRONMENT_ERRORS
+            .iter()
+            .find(|(pattern, _)| stderr.contains(pattern))
+        {
+            Some((_, reason)) => VerifyStatus::Skipped(reason.to_string()),
+            None => VerifyStatus::Failed(stderr),
+        }
+    }
+
+    /// Tell a dependency missing from the local cache from one that does not exist
+    ///
+    /// `cargo update --dry-run` resolves against the registry index without
+    /// downloading crates or writing a lockfile. Only when the index cannot be
+    /// reached is the failure put down to the environment.
+    fn resolve_online(&self, dir: &Path) -> VerifyStatus {
+        let not_cached =
+            || VerifyStatus::Skipped("dependencies are not in the local cache".to_string());
+        let output = match Command::new(&self.cargo)
+            .args(["update", "--dry-run", "--quiet"])
+            .current_dir(dir)
+            .output()
+        {
+            Ok(output) => output,
+            Err(_) => return not_cached(),
+        };
+        if output.status.success() {
+            return not_cached();
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if RESOLUTION_ERRORS
+            .iter()
+            .any(|pattern| stderr.contains(pattern))
+        {
+            VerifyStatus::Failed(stderr)
+        } else {
+            VerifyStatus::Skipped("the registry index is unavailable".to_string())
+        }
+    }
+}
+
+impl Default for Verifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Every file under `dir` as (path relative to `root`, full path)
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<(String, PathBuf)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut entries: Vec<_> = entries.filter_map(Result::ok).collect();
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            let name = entry.file_name();
+            if !SKIPPED_DIRS.iter().any(|skipped| name == *skipped) {
+                collect_files(root, &path, files);
+            }
+        } else if file_type.is_file() {
+            let relative = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            files.push((relative, path));
+        }
+    }
+}
+
+/// `path:line: message` for a file that does not parse
+fn parse_error(relative: &str, path: &Path) -> Option<String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => return Some(format!("{}: {}", relative, e)),
+    };
+    match check_output(relative, &contents)? {
+        Ok(()) => None,
+        Err((Some(line), message)) => Some(format!("{}:{}: {}", relative, line, message)),
+        Err((None, message)) => Some(format!("{}: {}", relative, message)),
+    }
+}
+
+/// Whether `cargo check --offline` could find dependencies without the network
+fn has_dependency_cache(dir: &Path) -> bool {
+    if dir.join("vendor").is_dir() {
+        return true;
+    }
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".cargo")));
+    cargo_home.is_some_and(|home| {
+        fs::read_dir(home.join("registry").join("cache"))
+            .map(|mut entries| entries.next().is_some())
+            .unwrap_or(false)
+    })
+}
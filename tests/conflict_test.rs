@@ -0,0 +1,325 @@
+use cargo_forge::conflict::{
+    merge_cargo_toml, merge_gitignore, merge_readme, Conflict, ConflictStrategy, Resolution,
+};
+use cargo_forge::{Generator, ProjectConfig};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn library_config(name: &str) -> ProjectConfig {
+    ProjectConfig {
+        name: name.to_string(),
+        project_type: "library".to_string(),
+        author: "Test Author".to_string(),
+        description: None,
+        features: vec![],
+        target: None,
+        esp32_chip: None,
+    }
+}
+
+/// A freshly cloned repository with a README, LICENSE, .gitignore and manifest
+fn cloned_repo(root: &Path) {
+    fs::write(
+        root.join("README.md"),
+        "# existing\n\nOur own intro.\n\n## License\n\nMIT\n",
+    )
+    .unwrap();
+    fs::write(root.join("LICENSE"), "MIT License\n").unwrap();
+    fs::write(root.join(".gitignore"), "/target\n.env\n").unwrap();
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"existing\" # keep me\nversion = \"2.0.0\"\n\n[dependencies]\nanyhow = \"1.0.50\"\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_merge_gitignore_appends_missing_patterns() {
+    let merged = merge_gitignore(
+        "/target\n.env",
+        "/target\n**/*.rs.bk\nCargo.lock\n**/*.rs.bk\n",
+    );
+    assert_eq!(
+        merged,
+        "/target\n.env\n\n# Added by cargo-forge\n**/*.rs.bk\nCargo.lock\n"
+    );
+
+    // Nothing to add leaves the file alone
+    assert_eq!(merge_gitignore("/target\n", "/target\n"), "/target\n");
+}
+
+#[test]
+fn test_merge_readme_adds_missing_sections() {
+    let generated = "# generated\n\nIntro.\n\n## Usage\n\n```bash\n## not a heading\n```\n\n## License\n\nMIT or Apache-2.0\n";
+    let merged = merge_readme("# mine\n\n### license\n\nMIT\n", generated);
+    assert_eq!(
+        merged,
+        "# mine\n\n### license\n\nMIT\n\n## Usage\n\n```bash\n## not a heading\n```\n"
+    );
+}
+
+#[test]
+fn test_merge_cargo_toml_adds_missing_dependencies() {
+    let existing = "[package]\nname = \"mine\" # keep me\n\n[dependencies]\nserde = \"1.0.100\"\n";
+    let generated = "[package]\nname = \"generated\"\n\n[dependencies]\nserde = { version = \"1.0\", features = [\"derive\"] }\nanyhow = \"1.0\"\n\n[dev-dependencies]\ntempfile = \"3\"\n";
+
+    let merged = merge_cargo_toml(existing, generated).unwrap();
+    assert!(merged.starts_with("[package]\nname = \"mine\" # keep me\n"));
+
+    let parsed: toml::Value = toml::from_str(&merged).unwrap();
+    assert_eq!(parsed["dependencies"]["serde"].as_str(), Some("1.0.100"));
+    assert_eq!(parsed["dependencies"]["anyhow"].as_str(), Some("1.0"));
+    assert_eq!(parsed["dev-dependencies"]["tempfile"].as_str(), Some("3"));
+
+    assert!(merge_cargo_toml("not [valid", generated).is_none());
+}
+
+#[test]
+fn test_merge_cargo_toml_adds_features_targets_and_metadata() {
+    let existing = r#"[package]
+name = "mine"
+
+[features]
+default = []
+
+[[example]]
+name = "demo"
+path = "examples/mine.rs"
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2.100"
+"#;
+    let generated = r#"[package]
+name = "generated"
+
+[dependencies]
+serde = { version = "1.0", optional = true }
+
+[features]
+default = ["serde"]
+serde = ["dep:serde"]
+
+[[example]]
+name = "demo"
+path = "examples/demo.rs"
+
+[[example]]
+name = "other"
+
+[[bin]]
+name = "tool"
+path = "src/main.rs"
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2"
+nix = "0.29"
+
+[target.'cfg(windows)'.dev-dependencies]
+windows-sys = "0.59"
+
+[package.metadata.docs.rs]
+all-features = true
+"#;
+
+    let merged = merge_cargo_toml(existing, generated).unwrap();
+    assert!(merged.starts_with("[package]\nname = \"mine\"\n\n[features]\ndefault = []\n"));
+
+    let parsed: toml::Value = toml::from_str(&merged).unwrap();
+    let features = &parsed["features"];
+    assert_eq!(features["default"].as_array().unwrap().len(), 0);
+    assert_eq!(features["serde"][0].as_str(), Some("dep:serde"));
+
+    let examples = parsed["example"].as_array().unwrap();
+    assert_eq!(examples.len(), 2);
+    assert_eq!(examples[0]["path"].as_str(), Some("examples/mine.rs"));
+    assert_eq!(examples[1]["name"].as_str(), Some("other"));
+    assert_eq!(parsed["bin"][0]["name"].as_str(), Some("tool"));
+
+    let unix = &parsed["target"]["cfg(unix)"]["dependencies"];
+    assert_eq!(unix["libc"].as_str(), Some("0.2.100"));
+    assert_eq!(unix["nix"].as_str(), Some("0.29"));
+    let windows = &parsed["target"]["cfg(windows)"]["dev-dependencies"];
+    assert_eq!(windows["windows-sys"].as_str(), Some("0.59"));
+
+    let docs_rs = &parsed["package"]["metadata"]["docs"]["rs"];
+    assert_eq!(docs_rs["all-features"].as_bool(), Some(true));
+}
+
+#[test]
+fn test_init_merge_feature_gated_project() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    cloned_repo(dir);
+
+    let mut config = library_config("existing");
+    config.features = vec!["serde".to_string()];
+    let summary = Generator::new()
+        .with_conflict_strategy(ConflictStrategy::Merge)
+        .generate_in_place(&config, dir)
+        .unwrap();
+    assert!(summary.merged.contains(&"Cargo.toml".to_string()));
+
+    let manifest: toml::Value =
+        toml::from_str(&fs::read_to_string(dir.join("Cargo.toml")).unwrap()).unwrap();
+    assert_eq!(manifest["package"]["version"].as_str(), Some("2.0.0"));
+    assert_eq!(manifest["dependencies"]["anyhow"].as_str(), Some("1.0.50"));
+    assert_eq!(
+        manifest["dependencies"]["serde"]["optional"].as_bool(),
+        Some(true)
+    );
+    assert_eq!(manifest["features"]["serde"][0].as_str(), Some("dep:serde"));
+    assert_eq!(manifest["bench"][0]["name"].as_str(), Some("benchmarks"));
+    assert!(manifest["package"]["metadata"]["docs"]["rs"].is_table());
+
+    // Cargo accepts the merged manifest, features and all
+    let output = Command::new("cargo")
+        .args([
+            "metadata",
+            "--no-deps",
+            "--offline",
+            "--format-version",
+            "1",
+        ])
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_conflict_merge_only_for_known_files() {
+    let license = Conflict::new("LICENSE", b"MIT".to_vec(), b"Apache".to_vec());
+    assert!(license.merged.is_none());
+    let gitignore = Conflict::new(".gitignore", b"/target\n".to_vec(), b"*.bin\n".to_vec());
+    assert!(gitignore.merged.is_some());
+
+    let resolutions = ConflictStrategy::Merge
+        .resolve(&[license.clone(), gitignore.clone()])
+        .unwrap();
+    assert_eq!(resolutions, vec![Resolution::Keep, Resolution::Merge]);
+
+    let diff = license.diff(Resolution::Replace);
+    assert!(diff.contains("-MIT"));
+    assert!(diff.contains("+Apache"));
+    assert!(license.diff(Resolution::Keep).is_empty());
+
+    let err = ConflictStrategy::Error
+        .resolve(&[license, gitignore])
+        .unwrap_err();
+    assert!(err.to_string().contains("LICENSE, .gitignore"));
+    assert!(ConflictStrategy::Error.resolve(&[]).unwrap().is_empty());
+}
+
+#[test]
+fn test_init_merge_into_cloned_repo() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    cloned_repo(dir);
+
+    let summary = Generator::new()
+        .with_conflict_strategy(ConflictStrategy::Merge)
+        .generate_in_place(&library_config("existing"), dir)
+        .unwrap();
+
+    assert!(summary.merged.contains(&".gitignore".to_string()));
+    assert!(summary.merged.contains(&"README.md".to_string()));
+    assert!(summary.merged.contains(&"Cargo.toml".to_string()));
+    assert!(summary.created.contains(&"src/lib.rs".to_string()));
+
+    assert_eq!(
+        fs::read_to_string(dir.join("LICENSE")).unwrap(),
+        "MIT License\n"
+    );
+    let gitignore = fs::read_to_string(dir.join(".gitignore")).unwrap();
+    assert!(gitignore.starts_with("/target\n.env\n"));
+    assert!(gitignore.contains("Cargo.lock"));
+    let readme = fs::read_to_string(dir.join("README.md")).unwrap();
+    assert!(readme.starts_with("# existing\n\nOur own intro."));
+    assert_eq!(readme.matches("## License").count(), 1);
+    let manifest: toml::Value =
+        toml::from_str(&fs::read_to_string(dir.join("Cargo.toml")).unwrap()).unwrap();
+    assert_eq!(manifest["package"]["version"].as_str(), Some("2.0.0"));
+    assert_eq!(manifest["dependencies"]["anyhow"].as_str(), Some("1.0.50"));
+}
+
+#[test]
+fn test_init_skip_and_overwrite() {
+    let temp_dir = TempDir::new().unwrap();
+    let skip_dir = temp_dir.path().join("skip");
+    let overwrite_dir = temp_dir.path().join("overwrite");
+    for dir in [&skip_dir, &overwrite_dir] {
+        fs::create_dir_all(dir).unwrap();
+        cloned_repo(dir);
+    }
+
+    let summary = Generator::new()
+        .with_conflict_strategy(ConflictStrategy::Skip)
+        .generate_in_place(&library_config("existing"), &skip_dir)
+        .unwrap();
+    assert!(summary.kept.contains(&"Cargo.toml".to_string()));
+    assert!(summary.merged.is_empty() && summary.replaced.is_empty());
+    assert!(fs::read_to_string(skip_dir.join("Cargo.toml"))
+        .unwrap()
+        .contains("2.0.0"));
+    assert!(skip_dir.join("src/lib.rs").exists());
+
+    let summary = Generator::new()
+        .with_conflict_strategy(ConflictStrategy::Overwrite)
+        .generate_in_place(&library_config("existing"), &overwrite_dir)
+        .unwrap();
+    assert!(summary.replaced.contains(&"Cargo.toml".to_string()));
+    assert!(!fs::read_to_string(overwrite_dir.join("Cargo.toml"))
+        .unwrap()
+        .contains("2.0.0"));
+}
+
+#[test]
+fn test_init_with_custom_resolver() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    cloned_repo(dir);
+
+    let mut seen = Vec::new();
+    let summary = Generator::new()
+        .generate_in_place_with(&library_config("existing"), dir, |conflicts| {
+            seen = conflicts.iter().map(|c| c.path.clone()).collect();
+            Ok(conflicts
+                .iter()
+                .map(|c| {
+                    if c.path == "README.md" {
+                        Resolution::Replace
+                    } else {
+                        Resolution::Keep
+                    }
+                })
+                .collect())
+        })
+        .unwrap();
+
+    seen.sort();
+    assert_eq!(seen, vec![".gitignore", "Cargo.toml", "README.md"]);
+    assert_eq!(summary.replaced, vec!["README.md"]);
+    assert!(!fs::read_to_string(dir.join("README.md"))
+        .unwrap()
+        .contains("Our own intro"));
+}
+
+#[test]
+fn test_init_prompt_without_terminal_changes_nothing() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    cloned_repo(dir);
+
+    let result = Generator::new()
+        .with_conflict_strategy(ConflictStrategy::Prompt)
+        .generate_in_place(&library_config("existing"), dir);
+
+    assert!(result.is_err());
+    assert!(!dir.join("src").exists());
+}
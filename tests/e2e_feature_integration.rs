@@ -1,4 +1,4 @@
-use cargo_forge::{Generator, ProjectConfig};
+use cargo_forge::{Framework, Generator, ProjectConfig};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -599,3 +599,179 @@ fn test_dependency_management_patterns() {
         }
     }
 }
+
+#[test]
+fn test_api_server_auth_and_database_compile_with_each_framework() {
+    // Login and registration are wired into every framework's router
+    let temp_dir = create_test_dir();
+
+    for framework in Framework::ALL {
+        let project_name = format!("auth-db-{}", framework);
+        let project_dir = temp_dir.path().join(&project_name);
+        let mut config = create_test_config(&project_name, "api-server");
+        config.features = vec!["auth".to_string(), "database".to_string()];
+
+        Generator::new()
+            .with_framework(Some(framework))
+            .generate(&config, &project_dir)
+            .unwrap_or_else(|_| panic!("Failed to generate {} project", framework));
+
+        verify_file_contains(
+            &project_dir.join("src/lib.rs"),
+            &["pub mod accounts;", "pub mod auth;", "pub mod database;"],
+        )
+        .unwrap_or_else(|e| panic!("{}: {}", framework, e));
+        verify_file_contains(
+            &project_dir.join("src/routes.rs"),
+            &["handlers::register", "handlers::login"],
+        )
+        .unwrap_or_else(|e| panic!("{}: {}", framework, e));
+
+        match run_cargo_check(&project_dir) {
+            Ok(_) => println!("✓ {} with auth and database compiles", framework),
+            Err(e) => panic!("✗ {} with auth and database failed: {}", framework, e),
+        }
+    }
+}
+
+#[test]
+fn test_api_server_database_backends_build_and_pass_tests_with_each_framework() {
+    // The pool, queries and sqlx features follow the database that was chosen
+    let temp_dir = create_test_dir();
+    let target_dir = temp_dir.path().join("target");
+
+    let mut projects: Vec<(Framework, &str, &str)> = Framework::ALL
+        .into_iter()
+        .map(|framework| (framework, "sqlite", "test"))
+        .collect();
+    // The generated tests need no running server, so MySQL is only built
+    projects.push((Framework::Axum, "mysql", "check"));
+
+    for (framework, backend, command) in projects {
+        let project_name = format!("{}-{}", backend, framework);
+        let project_dir = temp_dir.path().join(&project_name);
+        let mut config = create_test_config(&project_name, "api-server");
+        config.features = vec!["auth".to_string(), backend.to_string()];
+
+        Generator::new()
+            .with_framework(Some(framework))
+            .generate(&config, &project_dir)
+            .unwrap_or_else(|_| panic!("Failed to generate {} project", project_name));
+
+        verify_file_contains(
+            &project_dir.join("src/lib.rs"),
+            &["pub mod accounts;", "pub mod database;"],
+        )
+        .unwrap_or_else(|e| panic!("{}: {}", project_name, e));
+        verify_file_contains(
+            &project_dir.join("Cargo.toml"),
+            &[&format!("\"{}\"", backend)],
+        )
+        .unwrap_or_else(|e| panic!("{}: {}", project_name, e));
+        assert!(
+            project_dir
+                .join("migrations/002_add_password_hash.sql")
+                .exists(),
+            "{}: the password hash migration is missing",
+            project_name
+        );
+
+        let output = Command::new("cargo")
+            .args([command, "--all-targets"])
+            .env("CARGO_TARGET_DIR", &target_dir)
+            .current_dir(&project_dir)
+            .output()
+            .expect("Failed to execute cargo");
+        assert!(
+            output.status.success(),
+            "✗ cargo {} of {} failed: {}",
+            command,
+            project_name,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
+
+#[test]
+fn test_optional_database_feature_builds_with_and_without_it() {
+    // The database module is only compiled when its Cargo feature is on
+    let temp_dir = create_test_dir();
+    let target_dir = temp_dir.path().join("target");
+
+    for project_type in ["library", "cli-tool"] {
+        let project_name = format!("optional-db-{}", project_type);
+        let project_dir = temp_dir.path().join(&project_name);
+        let mut config = create_test_config(&project_name, project_type);
+        config.features = vec!["database".to_string()];
+
+        Generator::new()
+            .with_optional_features(true)
+            .generate(&config, &project_dir)
+            .unwrap_or_else(|_| panic!("Failed to generate {} project", project_type));
+
+        let root = if project_type == "library" {
+            "src/lib.rs"
+        } else {
+            "src/main.rs"
+        };
+        verify_file_contains(
+            &project_dir.join(root),
+            &["#[cfg(feature = \"database\")]", "mod database;"],
+        )
+        .unwrap_or_else(|e| panic!("{}: {}", project_type, e));
+
+        for features in [&["--no-default-features"][..], &["--features", "database"]] {
+            let output = Command::new("cargo")
+                .args(["check", "--all-targets"])
+                .args(features)
+                .env("CARGO_TARGET_DIR", &target_dir)
+                .current_dir(&project_dir)
+                .output()
+                .expect("Failed to execute cargo check");
+            assert!(
+                output.status.success(),
+                "✗ {} with {:?} failed: {}",
+                project_type,
+                features,
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_workspace_with_web_database_clap_and_packaging_builds() {
+    // Every feature that adds code to the workspace's member crates at once
+    let temp_dir = create_test_dir();
+    let project_dir = temp_dir.path().join("full-workspace");
+    let mut config = create_test_config("full-workspace", "workspace");
+    config.features = ["packaging", "clap", "database", "web"]
+        .map(String::from)
+        .to_vec();
+
+    Generator::new()
+        .generate(&config, &project_dir)
+        .expect("Failed to generate workspace");
+
+    for module in ["handlers", "middleware", "routes"] {
+        assert!(
+            project_dir
+                .join(format!("crates/api/src/{}.rs", module))
+                .exists(),
+            "crates/api declares {} without writing it",
+            module
+        );
+    }
+
+    let output = Command::new("cargo")
+        .args(["test", "--workspace", "--all-targets"])
+        .env("CARGO_TARGET_DIR", temp_dir.path().join("target"))
+        .current_dir(&project_dir)
+        .output()
+        .expect("Failed to execute cargo test");
+    assert!(
+        output.status.success(),
+        "✗ workspace with web, database, clap and packaging failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
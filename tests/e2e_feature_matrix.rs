@@ -82,7 +82,7 @@ impl FeatureMatrixTestSuite {
                 ],
             },
             MatrixTestConfig {
-                name: "very-long-project-name-that-tests-the-limits-of-reasonable-naming-conventions-and-filesystem-compatibility".to_string(),
+                name: "very-long-project-name-that-tests-the-64-character-crates-io-cap".to_string(),
                 project_type: "library".to_string(),
                 author: "Test <test@example.com>".to_string(),
                 description: Some("Very long project name".to_string()),
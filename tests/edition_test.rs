@@ -0,0 +1,183 @@
+use cargo_forge::plan::{FileSource, GenerationPlan};
+use cargo_forge::toolchain::validate_rust_version;
+use cargo_forge::{Edition, Generator, ProjectConfig};
+use std::process::Command;
+use tempfile::TempDir;
+use toml::Value;
+
+fn config(project_type: &str, features: &[&str]) -> ProjectConfig {
+    ProjectConfig {
+        name: "edition-test".to_string(),
+        project_type: project_type.to_string(),
+        author: "Test Author".to_string(),
+        description: None,
+        features: features.iter().map(|f| f.to_string()).collect(),
+        target: None,
+        esp32_chip: None,
+    }
+}
+
+fn contents(plan: &GenerationPlan, path: &str) -> String {
+    String::from_utf8_lossy(&plan.file(path).unwrap().contents).into_owned()
+}
+
+fn manifest(plan: &GenerationPlan, path: &str) -> Value {
+    toml::from_str(&contents(plan, path)).unwrap()
+}
+
+#[test]
+fn test_edition_parsing_and_resolver() {
+    assert_eq!("2024".parse::<Edition>().unwrap(), Edition::E2024);
+    assert_eq!(Edition::default(), Edition::E2021);
+    assert!("2015".parse::<Edition>().is_err());
+
+    assert_eq!(Edition::E2018.resolver(), "1");
+    assert_eq!(Edition::E2021.resolver(), "2");
+    assert_eq!(Edition::E2024.resolver(), "3");
+}
+
+#[test]
+fn test_rust_version_validation() {
+    assert!(validate_rust_version("1.75", Edition::E2021).is_ok());
+    assert!(validate_rust_version("1.85.1", Edition::E2024).is_ok());
+    assert!(validate_rust_version("1.80", Edition::E2024)
+        .unwrap_err()
+        .to_string()
+        .contains("1.85"));
+    for invalid in ["", "1", "one.two", "1.75.0.1", "v1.75"] {
+        assert!(
+            validate_rust_version(invalid, Edition::E2021).is_err(),
+            "{}",
+            invalid
+        );
+    }
+}
+
+#[test]
+fn test_default_edition_without_msrv() {
+    let plan = Generator::new().plan(&config("cli-tool", &[])).unwrap();
+    let package = &manifest(&plan, "Cargo.toml")["package"];
+    assert_eq!(package["edition"].as_str(), Some("2021"));
+    assert!(package.get("rust-version").is_none());
+    assert!(plan.file("rust-toolchain.toml").is_none());
+}
+
+#[test]
+fn test_edition_and_msrv_in_package() {
+    let plan = Generator::new()
+        .with_edition(Edition::E2024)
+        .with_rust_version(Some("1.85".to_string()))
+        .plan(&config("library", &[]))
+        .unwrap();
+
+    let package = &manifest(&plan, "Cargo.toml")["package"];
+    assert_eq!(package["edition"].as_str(), Some("2024"));
+    assert_eq!(package["rust-version"].as_str(), Some("1.85"));
+
+    let toolchain = plan.file("rust-toolchain.toml").unwrap();
+    assert_eq!(toolchain.source, FileSource::Generated);
+    let toolchain = manifest(&plan, "rust-toolchain.toml");
+    assert_eq!(toolchain["toolchain"]["channel"].as_str(), Some("1.85"));
+    assert!(toolchain["toolchain"].get("targets").is_none());
+
+    // Embedded projects also install their compilation target
+    let plan = Generator::new()
+        .with_rust_version(Some("1.75".to_string()))
+        .plan(&config("embedded", &[]))
+        .unwrap();
+    let toolchain = manifest(&plan, "rust-toolchain.toml");
+    assert_eq!(
+        toolchain["toolchain"]["targets"][0].as_str(),
+        Some("thumbv7em-none-eabihf")
+    );
+}
+
+#[test]
+fn test_workspace_members_inherit_edition() {
+    let plan = Generator::new()
+        .with_edition(Edition::E2018)
+        .with_rust_version(Some("1.70".to_string()))
+        .plan(&config("workspace", &[]))
+        .unwrap();
+
+    let root = manifest(&plan, "Cargo.toml");
+    assert_eq!(root["workspace"]["resolver"].as_str(), Some("1"));
+    assert_eq!(
+        root["workspace"]["package"]["edition"].as_str(),
+        Some("2018")
+    );
+    assert_eq!(
+        root["workspace"]["package"]["rust-version"].as_str(),
+        Some("1.70")
+    );
+
+    for member in ["core", "api", "cli"] {
+        let package = &manifest(&plan, &format!("crates/{}/Cargo.toml", member))["package"];
+        assert_eq!(package["edition"]["workspace"].as_bool(), Some(true));
+        assert_eq!(package["rust-version"]["workspace"].as_bool(), Some(true));
+    }
+
+    let plan = Generator::new()
+        .with_edition(Edition::E2024)
+        .plan(&config("workspace", &[]))
+        .unwrap();
+    assert_eq!(
+        manifest(&plan, "Cargo.toml")["workspace"]["resolver"].as_str(),
+        Some("3")
+    );
+}
+
+#[test]
+fn test_edition_2024_code() {
+    let plan = Generator::new()
+        .with_edition(Edition::E2024)
+        .plan(&config("api-server", &["auth"]))
+        .unwrap();
+    let jwt = contents(&plan, "src/auth/jwt.rs");
+    assert!(jwt.contains("unsafe { std::env::set_var(\"JWT_EXPIRATION\", \"3600\") };"));
+    syn::parse_file(&jwt).unwrap();
+
+    let plan = Generator::new()
+        .plan(&config("api-server", &["auth"]))
+        .unwrap();
+    let jwt = contents(&plan, "src/auth/jwt.rs");
+    assert!(jwt.contains("    std::env::set_var(\"JWT_EXPIRATION\", \"3600\");"));
+    assert!(!jwt.contains("unsafe {"));
+}
+
+#[test]
+fn test_msrv_too_old_for_edition() {
+    let error = Generator::new()
+        .with_edition(Edition::E2024)
+        .with_rust_version(Some("1.70".to_string()))
+        .plan(&config("cli-tool", &[]))
+        .unwrap_err();
+    assert!(error.to_string().contains("does not support edition 2024"));
+}
+
+#[test]
+fn test_cli_tool_builds_with_each_edition() {
+    let temp_dir = TempDir::new().unwrap();
+    let target_dir = temp_dir.path().join("target");
+
+    for edition in Edition::ALL {
+        let project_dir = temp_dir.path().join(format!("edition-{}", edition));
+        Generator::new()
+            .with_edition(edition)
+            .generate(&config("cli-tool", &[]), &project_dir)
+            .unwrap();
+
+        let output = Command::new("cargo")
+            .args(["build", "--all-targets"])
+            .env("CARGO_TARGET_DIR", &target_dir)
+            .current_dir(&project_dir)
+            .output()
+            .expect("Failed to execute cargo build");
+        assert!(
+            output.status.success(),
+            "✗ cli-tool with edition {} failed: {}",
+            edition,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
@@ -31,8 +31,9 @@ mod generator_error_tests {
 
         let output_dir = temp_dir.path().join("empty-name-test");
         let result = generator.generate(&config, &output_dir);
-        // Empty name should work but create minimal content
-        assert!(result.is_ok());
+        // Names are validated before anything is written
+        assert!(result.is_err());
+        assert!(!output_dir.exists());
     }
 
     #[test]
@@ -267,8 +268,8 @@ mod generator_error_tests {
                     .collect::<String>()
             ));
             let result = generator.generate(&config, &output_dir);
-            // Should succeed for most unicode names
-            assert!(result.is_ok(), "Failed for unicode name: {}", unicode_name);
+            // Project names are limited to ASCII letters, numbers, '-' and '_'
+            assert!(result.is_err(), "Accepted unicode name: {}", unicode_name);
         }
     }
 
@@ -26,7 +26,7 @@ fn test_database_plugin_postgresql() {
 
     assert!(context.dependencies.contains_key("sqlx"));
     assert!(context.dependencies.contains_key("tokio"));
-    assert!(context.dependencies.contains_key("dotenv"));
+    assert!(context.dependencies.contains_key("dotenvy"));
     assert!(context.template_files.contains_key(".env.example"));
     assert!(context.template_files.contains_key("src/database.rs"));
 }
@@ -1,4 +1,5 @@
 use cargo_forge::{Forge, ProjectType};
+use std::fs;
 use std::io::Cursor;
 use tempfile::TempDir;
 
@@ -105,3 +106,21 @@ fn test_interactive_flow_creates_project_directory() {
     assert!(project_path.exists(), "Project directory should be created");
     assert!(project_path.is_dir(), "Project path should be a directory");
 }
+
+#[test]
+fn test_failed_new_leaves_no_directory() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // `tokio` clashes with a dependency of the api-server template
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_cargo-forge"))
+        .args(["new", "tokio", "--project-type", "api-server"])
+        .args(["--author", "Test Author", "--non-interactive"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("tokio-app"));
+    assert!(!temp_dir.path().join("tokio").exists());
+    assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+}
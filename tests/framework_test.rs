@@ -0,0 +1,329 @@
+use cargo_forge::plan::GenerationPlan;
+use cargo_forge::{Forge, Framework, Generator, ProjectConfig};
+use std::fs;
+use tempfile::TempDir;
+use toml::Value;
+
+fn config(project_type: &str, features: &[&str]) -> ProjectConfig {
+    ProjectConfig {
+        name: "framework-test".to_string(),
+        project_type: project_type.to_string(),
+        author: "Test Author".to_string(),
+        description: None,
+        features: features.iter().map(|f| f.to_string()).collect(),
+        target: None,
+        esp32_chip: None,
+    }
+}
+
+fn api_server(framework: Option<Framework>, features: &[&str]) -> GenerationPlan {
+    Generator::new()
+        .with_framework(framework)
+        .plan(&config("api-server", features))
+        .unwrap()
+}
+
+fn contents(plan: &GenerationPlan, path: &str) -> String {
+    String::from_utf8_lossy(&plan.file(path).unwrap().contents).into_owned()
+}
+
+fn dependencies(plan: &GenerationPlan) -> Value {
+    let manifest: Value = toml::from_str(&contents(plan, "Cargo.toml")).unwrap();
+    manifest["dependencies"].clone()
+}
+
+#[test]
+fn test_framework_parsing() {
+    for framework in Framework::ALL {
+        assert_eq!(
+            framework.to_string().parse::<Framework>().unwrap(),
+            framework
+        );
+    }
+    assert_eq!(Framework::default(), Framework::Axum);
+    assert_eq!(Framework::ActixWeb.to_string(), "actix-web");
+    assert!("warp"
+        .parse::<Framework>()
+        .unwrap_err()
+        .to_string()
+        .contains("expected axum, actix-web, poem or rocket"));
+}
+
+#[test]
+fn test_axum_by_default() {
+    for plan in [
+        api_server(None, &[]),
+        api_server(Some(Framework::Axum), &[]),
+    ] {
+        let dependencies = dependencies(&plan);
+        assert!(dependencies.get("axum").is_some());
+        assert!(dependencies.get("tower-http").is_some());
+        assert!(contents(&plan, "src/routes.rs").contains("pub fn create_router"));
+        assert!(contents(&plan, "src/lib.rs").contains("pub use routes::create_router;"));
+        assert!(contents(&plan, "tests/api.rs").contains("oneshot"));
+    }
+}
+
+#[test]
+fn test_framework_dependencies_and_files() {
+    let cases = [
+        (
+            Framework::ActixWeb,
+            "actix-web",
+            "#[actix_web::main]",
+            "#[actix_web::test]",
+        ),
+        (Framework::Poem, "poem", "#[tokio::main]", "TestClient"),
+        (
+            Framework::Rocket,
+            "rocket",
+            "#[rocket::main]",
+            "#[rocket::async_test]",
+        ),
+    ];
+    for (framework, dependency, main_attribute, test_idiom) in cases {
+        let plan = api_server(Some(framework), &[]);
+
+        let dependencies = dependencies(&plan);
+        assert!(dependencies.get(dependency).is_some(), "{}", framework);
+        for axum_crate in ["axum", "tower", "tower-http"] {
+            assert!(dependencies.get(axum_crate).is_none(), "{}", framework);
+        }
+
+        for path in [
+            "src/main.rs",
+            "src/routes.rs",
+            "src/handlers.rs",
+            "src/error.rs",
+            "tests/api.rs",
+        ] {
+            assert!(
+                !contents(&plan, path).contains("axum"),
+                "{} has axum in {}",
+                framework,
+                path
+            );
+        }
+        assert!(contents(&plan, "src/main.rs").contains(main_attribute));
+        assert!(contents(&plan, "src/routes.rs").contains("pub fn create_app"));
+        assert!(contents(&plan, "src/lib.rs").contains("pub use routes::create_app;"));
+        assert!(contents(&plan, "tests/api.rs").contains(test_idiom));
+        assert!(contents(&plan, "README.md").contains(framework.display_name()));
+    }
+}
+
+#[test]
+fn test_rocket_has_no_request_timeout() {
+    let plan = api_server(Some(Framework::Rocket), &[]);
+    assert!(!contents(&plan, "src/config.rs").contains("request_timeout"));
+    assert!(!contents(&plan, ".env.example").contains("REQUEST_TIMEOUT_SECS"));
+    assert!(contents(&plan, ".env.example").contains("RUST_LOG=framework_test=debug,rocket=info"));
+
+    let plan = api_server(Some(Framework::Poem), &[]);
+    assert!(contents(&plan, "src/config.rs").contains("request_timeout"));
+    assert!(contents(&plan, "src/routes.rs").contains("REQUEST_TIMEOUT"));
+}
+
+#[test]
+fn test_features_with_each_framework() {
+    for framework in Framework::ALL {
+        let plan = api_server(Some(framework), &["database", "auth", "docker"]);
+
+        let dependencies = dependencies(&plan);
+        assert!(dependencies.get("sqlx").is_some(), "{}", framework);
+        assert!(dependencies.get("jsonwebtoken").is_some(), "{}", framework);
+        assert!(contents(&plan, "src/main.rs").contains("sqlx::migrate!"));
+        assert!(contents(&plan, "src/handlers.rs").contains("pub async fn ready"));
+        assert!(contents(&plan, "Dockerfile").contains("COPY migrations ./migrations"));
+
+        assert!(dependencies.get("dotenvy").is_some(), "{}", framework);
+        assert!(dependencies.get("dotenv").is_none(), "{}", framework);
+        let env = contents(&plan, ".env.example");
+        assert!(env.contains("HOST=") && env.contains("JWT_SECRET="));
+
+        // Login and registration are handlers of the server itself, so the
+        // axum-only auth middleware and routes are left out
+        let auth = contents(&plan, "src/auth/mod.rs");
+        assert!(plan.file("src/auth/jwt.rs").is_some(), "{}", framework);
+        assert!(!auth.contains("middleware"), "{}", framework);
+        assert!(
+            plan.file("src/auth/middleware.rs").is_none(),
+            "{}",
+            framework
+        );
+        assert!(plan.file("src/auth/routes.rs").is_none(), "{}", framework);
+        assert!(plan.file("migrations/002_add_password_hash.sql").is_some());
+
+        let handlers = contents(&plan, "src/handlers.rs");
+        assert!(handlers.contains("accounts::register("), "{}", framework);
+        assert!(handlers.contains("accounts::login("), "{}", framework);
+        assert!(!handlers.contains("NotImplemented"), "{}", framework);
+        assert!(contents(&plan, "src/config.rs").contains("pub jwt_secret: String,"));
+
+        // Every source file is reachable from lib.rs, so none is left uncompiled
+        let lib = contents(&plan, "src/lib.rs");
+        for module in ["accounts", "auth", "database"] {
+            assert!(
+                lib.contains(&format!("pub mod {};", module)),
+                "{} does not declare {}",
+                framework,
+                module
+            );
+        }
+        for file in plan.files() {
+            let Some(name) = file.path.strip_prefix("src/auth/") else {
+                continue;
+            };
+            if name == "mod.rs" {
+                continue;
+            }
+            let module = name.trim_end_matches(".rs");
+            assert!(
+                auth.contains(&format!("pub mod {};", module)),
+                "{} writes src/auth/{} without declaring it",
+                framework,
+                name
+            );
+        }
+    }
+}
+
+#[test]
+fn test_database_backend_with_each_framework() {
+    let backends = [
+        (
+            "postgres",
+            "sqlx::PgPool",
+            "VALUES ($1, $2, $3) RETURNING id",
+        ),
+        (
+            "sqlite",
+            "sqlx::SqlitePool",
+            "VALUES (?, ?, ?) RETURNING id",
+        ),
+        ("mysql", "sqlx::MySqlPool", "last_insert_id()"),
+    ];
+    for framework in Framework::ALL {
+        for (backend, pool, insert) in backends {
+            let plan = api_server(Some(framework), &["auth", backend]);
+            let context = format!("{} with {}", framework, backend);
+
+            let sqlx = &dependencies(&plan)["sqlx"]["features"];
+            let sqlx: Vec<&str> = sqlx
+                .as_array()
+                .unwrap()
+                .iter()
+                .flat_map(|f| f.as_str())
+                .collect();
+            assert!(sqlx.contains(&backend), "{}: {:?}", context, sqlx);
+            for other in backends.iter().map(|(name, _, _)| *name) {
+                assert!(
+                    other == backend || !sqlx.contains(&other),
+                    "{}: {:?}",
+                    context,
+                    sqlx
+                );
+            }
+
+            assert!(
+                contents(&plan, "src/lib.rs").contains("pub mod database;"),
+                "{}",
+                context
+            );
+            assert!(
+                contents(&plan, "src/state.rs").contains(pool),
+                "{}",
+                context
+            );
+            assert!(
+                contents(&plan, "src/accounts.rs").contains(insert),
+                "{}",
+                context
+            );
+            assert!(
+                plan.file("migrations/002_add_password_hash.sql").is_some(),
+                "{}",
+                context
+            );
+        }
+    }
+}
+
+#[test]
+fn test_auth_without_database_has_no_login() {
+    let plan = api_server(None, &["auth"]);
+    assert!(plan.file("src/auth/password.rs").is_some());
+    assert!(plan.file("src/accounts.rs").is_none());
+    assert!(!contents(&plan, "src/routes.rs").contains("/auth/login"));
+    assert!(!contents(&plan, "src/handlers.rs").contains("pub async fn login"));
+}
+
+#[test]
+fn test_framework_only_for_api_servers() {
+    let error = Generator::new()
+        .with_framework(Some(Framework::Poem))
+        .plan(&config("cli-tool", &[]))
+        .unwrap_err();
+    assert!(error.to_string().contains("only be chosen for api-server"));
+
+    let temp_dir = TempDir::new().unwrap();
+    let error = Forge::new(temp_dir.path())
+        .with_framework(Some(Framework::Rocket))
+        .run_non_interactive(
+            Some("not-a-server".to_string()),
+            Some("library".to_string()),
+            Some("Test Author".to_string()),
+            None,
+            None,
+        )
+        .unwrap_err();
+    assert!(error.to_string().contains("only applies to api-server"));
+    assert!(!temp_dir.path().join("not-a-server").exists());
+}
+
+#[test]
+fn test_framework_from_config_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("forge.json");
+    fs::write(
+        &config_path,
+        r#"{
+            "default_author": "Test Author",
+            "default_license": "MIT",
+            "preferred_project_types": [],
+            "default_features": {},
+            "edition": null,
+            "framework": "poem"
+        }"#,
+    )
+    .unwrap();
+
+    let forge = Forge::new(temp_dir.path());
+    forge
+        .run_from_config(
+            config_path.clone(),
+            Some("from-config".to_string()),
+            Some("api-server".to_string()),
+            None,
+            None,
+        )
+        .unwrap();
+    let manifest = fs::read_to_string(temp_dir.path().join("from-config/Cargo.toml")).unwrap();
+    assert!(manifest.contains("poem"));
+    assert!(!manifest.contains("axum"));
+
+    // The command line wins over the config file
+    Forge::new(temp_dir.path())
+        .with_framework(Some(Framework::ActixWeb))
+        .run_from_config(
+            config_path,
+            Some("from-flag".to_string()),
+            Some("api-server".to_string()),
+            None,
+            None,
+        )
+        .unwrap();
+    let manifest = fs::read_to_string(temp_dir.path().join("from-flag/Cargo.toml")).unwrap();
+    assert!(manifest.contains("actix-web"));
+    assert!(!manifest.contains("poem"));
+}
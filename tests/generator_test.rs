@@ -290,3 +290,141 @@ fn test_readme_generation() {
         "README should contain description"
     );
 }
+
+#[test]
+fn test_templates_rendered_for_project_type() {
+    // Test that generated sources come from the embedded templates, not stubs
+    let temp_dir = TempDir::new().unwrap();
+    let output_dir = temp_dir.path().join("rendered-lib");
+
+    let config = ProjectConfig {
+        name: "rendered-lib".to_string(),
+        project_type: "library".to_string(),
+        author: "Test Author".to_string(),
+        description: Some("A rendered library".to_string()),
+        features: vec![],
+        target: None,
+        esp32_chip: None
+    };
+
+    let generator = Generator::new();
+    generator.generate(&config, &output_dir).unwrap();
+
+    let lib_content = fs::read_to_string(output_dir.join("src/lib.rs")).unwrap();
+    assert!(lib_content.contains("//! A rendered library"));
+    assert!(lib_content.contains("pub enum RenderedLibError"));
+    assert!(lib_content.contains("use rendered_lib::hello;"));
+    assert!(!lib_content.contains("{{"), "No unrendered Tera tags should remain");
+
+    let example = fs::read_to_string(output_dir.join("examples/basic.rs")).unwrap();
+    assert!(example.contains("use rendered_lib::{hello, ExampleStruct};"));
+    assert!(output_dir.join("benches/benchmarks.rs").exists());
+}
+
+#[test]
+fn test_custom_template_dir_overrides_embedded() {
+    // Test that a user template directory replaces same-named embedded templates
+    let temp_dir = TempDir::new().unwrap();
+    let template_dir = temp_dir.path().join("house-style");
+    fs::create_dir_all(template_dir.join("api_server/src")).unwrap();
+    fs::write(
+        template_dir.join("api_server/src/main.rs.tera"),
+        "// House style for {{ name }}\nfn main() {}\n",
+    )
+    .unwrap();
+
+    let output_dir = temp_dir.path().join("house-api");
+    let config = ProjectConfig {
+        name: "house-api".to_string(),
+        project_type: "api-server".to_string(),
+        author: "Test Author".to_string(),
+        description: None,
+        features: vec![],
+        target: None,
+        esp32_chip: None
+    };
+
+    let generator = Generator::new().with_template_dirs(vec![template_dir]);
+    generator.generate(&config, &output_dir).unwrap();
+
+    let main_content = fs::read_to_string(output_dir.join("src/main.rs")).unwrap();
+    assert_eq!(main_content, "// House style for house-api\nfn main() {}\n");
+
+    // Templates that were not overridden still come from the embedded set
+    let routes = fs::read_to_string(output_dir.join("src/routes.rs")).unwrap();
+    assert!(routes.contains("pub fn create_router"));
+}
+
+#[test]
+fn test_missing_custom_template_dir_fails() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_dir = temp_dir.path().join("missing-templates");
+
+    let config = ProjectConfig {
+        name: "missing-templates".to_string(),
+        project_type: "library".to_string(),
+        author: "Test Author".to_string(),
+        description: None,
+        features: vec![],
+        target: None,
+        esp32_chip: None
+    };
+
+    let generator =
+        Generator::new().with_template_dirs(vec![temp_dir.path().join("does-not-exist")]);
+    let err = generator.generate(&config, &output_dir).unwrap_err();
+    assert!(err.to_string().contains("Template directory not found"));
+}
+
+#[test]
+fn test_non_template_files_copied_verbatim() {
+    // Test that assets keep their bytes and permissions, unless marked for rendering
+    let temp_dir = TempDir::new().unwrap();
+    let template_dir = temp_dir.path().join("assets-style");
+    fs::create_dir_all(template_dir.join("library/assets")).unwrap();
+    fs::create_dir_all(template_dir.join("library/scripts")).unwrap();
+
+    let image = vec![0x89, b'P', b'N', b'G', 0x00, 0xff, 0xfe, b'{', b'{'];
+    fs::write(template_dir.join("library/assets/logo.png"), &image).unwrap();
+    fs::write(template_dir.join("library/scripts/release"), "#!/bin/sh\necho {{ name }}\n").unwrap();
+    fs::set_permissions(
+        template_dir.join("library/scripts/release"),
+        fs::Permissions::from_mode(0o750),
+    )
+    .unwrap();
+    fs::write(template_dir.join("library/NOTICE"), "Copyright {{ author }}\n").unwrap();
+    fs::write(
+        template_dir.join("forge-template.toml"),
+        "[template]\nname = \"assets\"\n\n[[files]]\npath = \"library/NOTICE\"\nrender = true\n",
+    )
+    .unwrap();
+
+    let output_dir = temp_dir.path().join("asset-lib");
+    let config = ProjectConfig {
+        name: "asset-lib".to_string(),
+        project_type: "library".to_string(),
+        author: "Test Author".to_string(),
+        description: None,
+        features: vec![],
+        target: None,
+        esp32_chip: None
+    };
+
+    let generator = Generator::new().with_template_dirs(vec![template_dir]);
+    generator.generate(&config, &output_dir).unwrap();
+
+    assert_eq!(fs::read(output_dir.join("assets/logo.png")).unwrap(), image);
+
+    let script = output_dir.join("scripts/release");
+    assert_eq!(
+        fs::read_to_string(&script).unwrap(),
+        "#!/bin/sh\necho {{ name }}\n"
+    );
+    let mode = fs::metadata(&script).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o750);
+
+    assert_eq!(
+        fs::read_to_string(output_dir.join("NOTICE")).unwrap(),
+        "Copyright Test Author\n"
+    );
+}
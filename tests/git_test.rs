@@ -0,0 +1,217 @@
+use cargo_forge::git::{
+    config_files, find_git_dir, init_repository, GitConfig, GitIdentity, Vcs, VcsOptions,
+};
+use cargo_forge::Forge;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn write(path: &Path, contents: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn test_identity_from_config_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = temp_dir.path().join(".gitconfig");
+    write(
+        &config,
+        "# global settings\n[core]\n\teditor = vim\n[User]\n\tname = \"Jane \\\"JD\\\" Doe\" ; nickname\n\temail = jane@example.com # work\n[user \"work\"]\n\tname = Not Me\n",
+    );
+
+    let identity = GitIdentity::from_files(std::slice::from_ref(&config));
+    assert_eq!(identity.name.as_deref(), Some("Jane \"JD\" Doe"));
+    assert_eq!(identity.email.as_deref(), Some("jane@example.com"));
+    assert_eq!(
+        identity.author().as_deref(),
+        Some("Jane \"JD\" Doe <jane@example.com>")
+    );
+
+    // Subsection names are read without their quotes
+    let config = GitConfig::from_files(&[config]);
+    assert_eq!(config.get("user.work.name"), Some("Not Me"));
+}
+
+#[test]
+fn test_later_files_take_precedence() {
+    let temp_dir = TempDir::new().unwrap();
+    let home = temp_dir.path().join("home");
+    let project = temp_dir.path().join("repo").join("crates").join("new");
+    fs::create_dir_all(&project).unwrap();
+    write(
+        &home.join(".config/git/config"),
+        "[user]\n  name = XDG Name\n  email = xdg@example.com\n",
+    );
+    write(&home.join(".gitconfig"), "[user]\n  name = Global Name\n");
+    write(
+        &temp_dir.path().join("repo/.git/config"),
+        "[core]\n  bare = false\n[user]\n  email = repo@example.com\n",
+    );
+
+    let files = config_files(&project, Some(&home), None);
+    assert_eq!(files.len(), 3);
+    assert!(files[0].ends_with(".config/git/config"));
+    assert!(files[2].ends_with("repo/.git/config"));
+
+    let identity = GitIdentity::from_files(&files);
+    assert_eq!(
+        identity.author().as_deref(),
+        Some("Global Name <repo@example.com>")
+    );
+
+    // An explicit XDG_CONFIG_HOME replaces ~/.config
+    let xdg = temp_dir.path().join("xdg");
+    let files = config_files(&project, Some(&home), Some(&xdg));
+    assert_eq!(files[0], xdg.join("git").join("config"));
+}
+
+#[test]
+fn test_included_files_are_read_in_place() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = temp_dir.path().join(".gitconfig");
+    write(
+        &config,
+        "[user]\n  name = Before Include\n[include]\n  path = identity.inc\n[user]\n  email = after@example.com\n",
+    );
+    write(
+        &temp_dir.path().join("identity.inc"),
+        "[user]\n  name = Included Name\n  email = included@example.com\n",
+    );
+
+    let identity = GitIdentity::from_files(&[config]);
+    assert_eq!(identity.name.as_deref(), Some("Included Name"));
+    assert_eq!(identity.email.as_deref(), Some("after@example.com"));
+}
+
+#[test]
+fn test_no_name_means_no_author() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = temp_dir.path().join(".gitconfig");
+    write(&config, "[user]\n  email = nobody@example.com\n  name =\n");
+
+    let identity = GitIdentity::from_files(&[config, temp_dir.path().join("missing")]);
+    assert_eq!(identity.name, None);
+    assert_eq!(identity.author(), None);
+    assert_eq!(
+        GitIdentity {
+            name: Some("Solo".to_string()),
+            email: None
+        }
+        .author()
+        .as_deref(),
+        Some("Solo")
+    );
+}
+
+#[test]
+fn test_find_git_dir_follows_worktrees() {
+    let temp_dir = TempDir::new().unwrap();
+    let main = temp_dir.path().join("main");
+    fs::create_dir_all(main.join(".git/worktrees/feature")).unwrap();
+    write(&main.join(".git/worktrees/feature/commondir"), "../..\n");
+
+    let worktree = temp_dir.path().join("feature");
+    write(
+        &worktree.join(".git"),
+        &format!(
+            "gitdir: {}\n",
+            main.join(".git/worktrees/feature").display()
+        ),
+    );
+    let git_dir = find_git_dir(&worktree.join("src")).unwrap();
+    assert_eq!(
+        fs::canonicalize(git_dir).unwrap(),
+        fs::canonicalize(main.join(".git")).unwrap()
+    );
+}
+
+fn git_output(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[test]
+fn test_init_repository_with_initial_commit() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    write(&dir.join(".gitignore"), "/target\n");
+    write(&dir.join("src/main.rs"), "fn main() {}\n");
+    write(&dir.join("target/debug/app"), "binary");
+
+    let options = VcsOptions {
+        vcs: Vcs::Git,
+        branch: "trunk".to_string(),
+        initial_commit: true,
+    };
+    init_repository(dir, &options, Some("Jane Doe <jane@example.com>")).unwrap();
+
+    assert_eq!(git_output(dir, &["branch", "--show-current"]), "trunk");
+    assert_eq!(
+        git_output(dir, &["log", "-1", "--format=%an <%ae>|%s"]),
+        "Jane Doe <jane@example.com>|Initial commit"
+    );
+    assert_eq!(git_output(dir, &["ls-files"]), ".gitignore\nsrc/main.rs");
+}
+
+#[test]
+fn test_init_repository_without_commit_stages_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    write(&dir.join("Cargo.toml"), "[package]\n");
+
+    init_repository(dir, &VcsOptions::default(), None).unwrap();
+    assert!(dir.join(".git").is_dir());
+    assert_eq!(git_output(dir, &["ls-files"]), "Cargo.toml");
+    assert!(git_output(dir, &["log", "--oneline"]).is_empty());
+
+    let invalid = VcsOptions {
+        branch: "bad..name".to_string(),
+        ..VcsOptions::default()
+    };
+    let other = TempDir::new().unwrap();
+    let error = init_repository(other.path(), &invalid, None).unwrap_err();
+    assert!(error.to_string().contains("Invalid branch name"));
+    assert!(!other.path().join(".git").exists());
+}
+
+#[test]
+fn test_new_project_repository_skipped_inside_work_tree() {
+    let temp_dir = TempDir::new().unwrap();
+    Forge::new(temp_dir.path())
+        .with_vcs(Some(Vcs::Git))
+        .with_initial_commit(true)
+        .run_non_interactive(
+            Some("standalone".to_string()),
+            Some("library".to_string()),
+            Some("Test Author <test@example.com>".to_string()),
+            None,
+            None,
+        )
+        .unwrap();
+    let project = temp_dir.path().join("standalone");
+    assert!(project.join(".git").is_dir());
+    assert_eq!(
+        git_output(&project, &["log", "-1", "--format=%an"]),
+        "Test Author"
+    );
+
+    // A project generated inside that repository does not get its own
+    Forge::new(&project)
+        .with_vcs(Some(Vcs::Git))
+        .run_non_interactive(
+            Some("nested".to_string()),
+            Some("library".to_string()),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    assert!(project.join("nested/Cargo.toml").exists());
+    assert!(!project.join("nested/.git").exists());
+}
@@ -512,7 +512,8 @@ fn test_project_name_sanitization() {
     let temp_dir = create_test_dir();
     let project_dir = temp_dir.path().join("special-name-test");
 
-    let config = ProjectConfig {
+    // Names are validated before generation, so '.' is rejected outright
+    let mut config = ProjectConfig {
         name: "my-special_project.123".to_string(),
         project_type: "library".to_string(),
         author: "Test Author".to_string(),
@@ -523,6 +524,9 @@ fn test_project_name_sanitization() {
     };
 
     let generator = Generator::new();
+    assert!(generator.generate(&config, &project_dir).is_err());
+
+    config.name = "my-special_project-123".to_string();
     generator
         .generate(&config, &project_dir)
         .expect("Failed to generate project with special name");
@@ -534,7 +538,7 @@ fn test_project_name_sanitization() {
     // Library names should have hyphens converted to underscores
     assert!(
         cargo_content.contains("name = \"my_special_project_123\"")
-            || cargo_content.contains("name = \"my-special_project.123\""),
+            || cargo_content.contains("name = \"my-special_project-123\""),
         "Library name should be in Cargo.toml"
     );
 }
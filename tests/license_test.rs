@@ -0,0 +1,165 @@
+use cargo_forge::license::License;
+use cargo_forge::plan::{FileSource, GenerationPlan};
+use cargo_forge::{Generator, ProjectConfig};
+use std::collections::HashMap;
+use toml::Value;
+
+fn config(project_type: &str) -> ProjectConfig {
+    ProjectConfig {
+        name: "license-test".to_string(),
+        project_type: project_type.to_string(),
+        author: "Jane Doe".to_string(),
+        description: None,
+        features: Vec::new(),
+        target: None,
+        esp32_chip: None,
+    }
+}
+
+fn plan(project_type: &str, license: &str) -> GenerationPlan {
+    let mut variables = HashMap::new();
+    variables.insert("year".to_string(), serde_json::json!(2031));
+    Generator::new()
+        .with_variables(variables)
+        .with_license(Some(license.to_string()))
+        .plan(&config(project_type))
+        .unwrap()
+}
+
+fn contents(plan: &GenerationPlan, path: &str) -> String {
+    String::from_utf8_lossy(&plan.file(path).unwrap().contents).into_owned()
+}
+
+fn manifest(plan: &GenerationPlan, path: &str) -> Value {
+    toml::from_str(&contents(plan, path)).unwrap()
+}
+
+#[test]
+fn test_license_expressions_are_canonicalized() {
+    assert_eq!(License::parse("mit").unwrap().to_string(), "MIT");
+    assert_eq!(
+        License::parse("mit OR apache-2.0").unwrap().to_string(),
+        "MIT OR Apache-2.0"
+    );
+    assert_eq!(
+        License::parse("( MIT OR Apache-2.0 ) AND bsd-3-clause")
+            .unwrap()
+            .to_string(),
+        "(MIT OR Apache-2.0) AND BSD-3-Clause"
+    );
+    assert_eq!(
+        License::parse("Apache-2.0 WITH llvm-exception")
+            .unwrap()
+            .to_string(),
+        "Apache-2.0 WITH LLVM-exception"
+    );
+    assert_eq!(
+        License::parse("LicenseRef-Acme-1.0").unwrap().to_string(),
+        "LicenseRef-Acme-1.0"
+    );
+
+    let proprietary = License::parse("Proprietary").unwrap();
+    assert!(proprietary.is_proprietary());
+    assert_eq!(proprietary.to_string(), "proprietary");
+}
+
+#[test]
+fn test_invalid_license_expressions() {
+    for expression in [
+        "",
+        "Foo-1.0",
+        "MIT or Apache-2.0",
+        "MIT OR",
+        "(MIT OR Apache-2.0",
+        "MIT Apache-2.0",
+        "MIT WITH Nothing",
+    ] {
+        assert!(License::parse(expression).is_err(), "{}", expression);
+    }
+
+    let error = Generator::new()
+        .with_license(Some("Foo-1.0".to_string()))
+        .plan(&config("library"))
+        .unwrap_err();
+    assert!(error.to_string().contains("Invalid license expression"));
+}
+
+#[test]
+fn test_license_files() {
+    let paths = |expression: &str| -> Vec<String> {
+        License::parse(expression)
+            .unwrap()
+            .files()
+            .into_iter()
+            .map(|f| f.path)
+            .collect()
+    };
+    assert_eq!(paths("MIT"), vec!["LICENSE"]);
+    assert_eq!(
+        paths("MIT OR Apache-2.0"),
+        vec!["LICENSE-MIT", "LICENSE-APACHE"]
+    );
+    assert_eq!(paths("GPL-3.0-or-later"), vec!["LICENSE"]);
+    // No bundled text, so no file
+    assert!(paths("ISC").is_empty());
+}
+
+#[test]
+fn test_mit_license_substitutes_author_and_year() {
+    let plan = plan("cli-tool", "MIT");
+
+    let license = plan.file("LICENSE").unwrap();
+    assert_eq!(
+        license.source,
+        FileSource::Template("licenses/MIT.tera".to_string())
+    );
+    assert!(contents(&plan, "LICENSE").contains("Copyright (c) 2031 Jane Doe"));
+    assert_eq!(
+        manifest(&plan, "Cargo.toml")["package"]["license"].as_str(),
+        Some("MIT")
+    );
+}
+
+#[test]
+fn test_dual_license_writes_both_texts() {
+    let plan = plan("library", "mit OR apache-2.0");
+
+    assert!(plan.file("LICENSE").is_none());
+    assert!(contents(&plan, "LICENSE-MIT").starts_with("MIT License"));
+    assert!(contents(&plan, "LICENSE-APACHE").contains("Apache License"));
+    assert_eq!(
+        manifest(&plan, "Cargo.toml")["package"]["license"].as_str(),
+        Some("MIT OR Apache-2.0")
+    );
+
+    let readme = contents(&plan, "README.md");
+    assert!(readme.contains("licensed under the MIT OR Apache-2.0 License"));
+    assert!(readme.contains("[LICENSE-APACHE](LICENSE-APACHE)"));
+}
+
+#[test]
+fn test_proprietary_license() {
+    let plan = plan("api-server", "proprietary");
+
+    assert!(contents(&plan, "LICENSE").contains("2031 Jane Doe. All rights reserved."));
+    let package = &manifest(&plan, "Cargo.toml")["package"];
+    assert!(package.get("license").is_none());
+    assert_eq!(package["license-file"].as_str(), Some("LICENSE"));
+    assert_eq!(package["publish"].as_bool(), Some(false));
+}
+
+#[test]
+fn test_workspace_members_inherit_license() {
+    let plan = plan("workspace", "Apache-2.0");
+
+    assert!(contents(&plan, "LICENSE").contains("Apache License"));
+    assert_eq!(
+        manifest(&plan, "Cargo.toml")["workspace"]["package"]["license"].as_str(),
+        Some("Apache-2.0")
+    );
+    let member = manifest(&plan, "crates/core/Cargo.toml");
+    assert_eq!(
+        member["package"]["license"]["workspace"].as_bool(),
+        Some(true)
+    );
+}
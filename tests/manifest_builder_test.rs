@@ -0,0 +1,348 @@
+use cargo_forge::cargo_toml::{DependencyKind, ManifestBuilder};
+use cargo_forge::{Generator, ProjectConfig};
+use toml::Value;
+
+const BASE: &str = r#"[package]
+name = "demo"
+version = "0.1.0"
+
+# Runtime
+[dependencies]
+tokio = { version = "1", features = ["macros"] }
+serde = "1.0"
+reqwest = { version = "0.11", default-features = false, features = ["json"] }
+"#;
+
+fn parse(manifest: &ManifestBuilder) -> Value {
+    toml::from_str(&manifest.to_string()).unwrap()
+}
+
+fn features(value: &Value) -> Vec<&str> {
+    value["features"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f.as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn test_new_dependencies_are_added() {
+    let mut manifest = ManifestBuilder::parse(BASE).unwrap();
+    manifest
+        .add_dependency(DependencyKind::Normal, None, "dotenv", r#""0.15""#)
+        .unwrap();
+    manifest
+        .add_dependency(DependencyKind::Normal, None, "anyhow", "1.0")
+        .unwrap();
+    manifest
+        .add_dependency(
+            DependencyKind::Dev,
+            None,
+            "tempfile",
+            r#"{ version = "3" }"#,
+        )
+        .unwrap();
+    manifest
+        .add_dependency(DependencyKind::Build, None, "cc", r#""1.0""#)
+        .unwrap();
+
+    let parsed = parse(&manifest);
+    assert_eq!(parsed["dependencies"]["dotenv"].as_str(), Some("0.15"));
+    assert_eq!(parsed["dependencies"]["anyhow"].as_str(), Some("1.0"));
+    assert_eq!(
+        parsed["dev-dependencies"]["tempfile"]["version"].as_str(),
+        Some("3")
+    );
+    assert_eq!(parsed["build-dependencies"]["cc"].as_str(), Some("1.0"));
+
+    // The rendered layout and comments are kept
+    assert!(manifest.to_string().starts_with(BASE));
+}
+
+#[test]
+fn test_duplicate_dependencies_union_features() {
+    let mut manifest = ManifestBuilder::parse(BASE).unwrap();
+    manifest
+        .add_dependency(
+            DependencyKind::Normal,
+            None,
+            "tokio",
+            r#"{ version = "1.30", features = ["full", "macros"] }"#,
+        )
+        .unwrap();
+    manifest
+        .add_dependency(
+            DependencyKind::Normal,
+            None,
+            "serde",
+            r#"{ version = "1.0", features = ["derive"], optional = true }"#,
+        )
+        .unwrap();
+    manifest
+        .add_dependency(DependencyKind::Normal, None, "tokio", r#""1""#)
+        .unwrap();
+
+    let parsed = parse(&manifest);
+    let tokio = &parsed["dependencies"]["tokio"];
+    // The existing version wins
+    assert_eq!(tokio["version"].as_str(), Some("1"));
+    assert_eq!(features(tokio), vec!["macros", "full"]);
+
+    let serde = &parsed["dependencies"]["serde"];
+    assert_eq!(serde["version"].as_str(), Some("1.0"));
+    assert_eq!(features(serde), vec!["derive"]);
+    assert_eq!(serde["optional"].as_bool(), Some(true));
+
+    assert_eq!(manifest.to_string().matches("tokio").count(), 1);
+}
+
+#[test]
+fn test_default_features_kept_only_when_both_disable_them() {
+    let mut manifest = ManifestBuilder::parse(BASE).unwrap();
+    manifest
+        .add_dependency(
+            DependencyKind::Normal,
+            None,
+            "reqwest",
+            r#"{ version = "0.11", default-features = false, features = ["rustls-tls"] }"#,
+        )
+        .unwrap();
+    let parsed = parse(&manifest);
+    assert_eq!(
+        parsed["dependencies"]["reqwest"]["default-features"].as_bool(),
+        Some(false)
+    );
+    assert_eq!(
+        features(&parsed["dependencies"]["reqwest"]),
+        vec!["json", "rustls-tls"]
+    );
+
+    manifest
+        .add_dependency(
+            DependencyKind::Normal,
+            None,
+            "reqwest",
+            r#"{ version = "0.11", features = ["blocking"] }"#,
+        )
+        .unwrap();
+    let parsed = parse(&manifest);
+    assert!(parsed["dependencies"]["reqwest"]
+        .get("default-features")
+        .is_none());
+}
+
+#[test]
+fn test_target_specific_dependencies() {
+    let mut manifest = ManifestBuilder::parse(BASE).unwrap();
+    manifest
+        .add_dependency(
+            DependencyKind::Normal,
+            Some("cfg(unix)"),
+            "nix",
+            r#""0.27""#,
+        )
+        .unwrap();
+    manifest
+        .add_dependency(
+            DependencyKind::Dev,
+            Some("cfg(windows)"),
+            "windows-sys",
+            r#"{ version = "0.52", features = ["Win32_Foundation"] }"#,
+        )
+        .unwrap();
+
+    let output = manifest.to_string();
+    assert!(output.contains("[target.'cfg(unix)'.dependencies]"));
+    assert!(!output.contains("[target]"));
+
+    let parsed = parse(&manifest);
+    assert_eq!(
+        parsed["target"]["cfg(unix)"]["dependencies"]["nix"].as_str(),
+        Some("0.27")
+    );
+    assert_eq!(
+        features(&parsed["target"]["cfg(windows)"]["dev-dependencies"]["windows-sys"]),
+        vec!["Win32_Foundation"]
+    );
+}
+
+#[test]
+fn test_invalid_spec_rejected() {
+    let mut manifest = ManifestBuilder::parse(BASE).unwrap();
+    assert!(manifest
+        .add_dependency(DependencyKind::Normal, None, "broken", "[1, 2]")
+        .is_err());
+    assert!(ManifestBuilder::parse("[package").is_err());
+}
+
+#[test]
+fn test_database_plugin_dependencies_written() {
+    let config = ProjectConfig {
+        name: "db-api".to_string(),
+        project_type: "api-server".to_string(),
+        author: "Test Author".to_string(),
+        description: None,
+        features: vec!["database".to_string()],
+        target: None,
+        esp32_chip: None,
+    };
+    let plan = Generator::new().plan(&config).unwrap();
+    let manifest: Value =
+        toml::from_str(std::str::from_utf8(&plan.file("Cargo.toml").unwrap().contents).unwrap())
+            .unwrap();
+
+    let dependencies = &manifest["dependencies"];
+    assert!(dependencies.get("dotenvy").is_some());
+    // The template and the plugin both ask for tokio and sqlx
+    assert_eq!(features(&dependencies["tokio"]), vec!["full"]);
+    assert_eq!(
+        features(&dependencies["sqlx"]),
+        vec!["runtime-tokio-rustls", "postgres"]
+    );
+}
+
+#[test]
+fn test_examples_with_required_features() {
+    let mut manifest = ManifestBuilder::parse(BASE).unwrap();
+    manifest.add_example("migrate", &["migrations"]).unwrap();
+    manifest
+        .add_example("migrate", &["migrations", "postgres"])
+        .unwrap();
+    manifest.add_example("plain", &[]).unwrap();
+
+    let output = manifest.to_string();
+    assert_eq!(output.matches("[[example]]").count(), 2);
+
+    let parsed = parse(&manifest);
+    let examples = parsed["example"].as_array().unwrap();
+    assert_eq!(examples[0]["name"].as_str(), Some("migrate"));
+    assert_eq!(
+        examples[0]["required-features"].as_array().unwrap().len(),
+        2
+    );
+    // Required features are declared so cargo accepts the manifest
+    assert!(parsed["features"]["migrations"]
+        .as_array()
+        .unwrap()
+        .is_empty());
+    assert!(parsed["features"].get("postgres").is_some());
+
+    assert_eq!(
+        manifest.example_required_features(),
+        vec![
+            (
+                "migrate".to_string(),
+                vec!["migrations".to_string(), "postgres".to_string()]
+            ),
+            ("plain".to_string(), vec![]),
+        ]
+    );
+}
+
+#[test]
+fn test_optional_dependencies_gated_by_features() {
+    let mut manifest = ManifestBuilder::parse(BASE).unwrap();
+    manifest.add_feature("default", &["database"]).unwrap();
+    manifest
+        .add_optional_dependency(
+            "database",
+            "sqlx",
+            r#"{ version = "0.7", features = ["postgres"] }"#,
+        )
+        .unwrap();
+    manifest
+        .add_optional_dependency("database", "dotenv", "0.15")
+        .unwrap();
+    // Already required by the manifest, so it stays required
+    manifest
+        .add_optional_dependency(
+            "database",
+            "tokio",
+            r#"{ version = "1", features = ["full"] }"#,
+        )
+        .unwrap();
+
+    // `optional` reads as if it had been written by hand
+    let written = manifest.to_string();
+    assert!(
+        written.contains(r#"sqlx = { version = "0.7", features = ["postgres"], optional = true }"#)
+    );
+    assert!(written.contains(r#"dotenv = { version = "0.15", optional = true }"#));
+
+    let parsed = parse(&manifest);
+    let dependencies = &parsed["dependencies"];
+    assert_eq!(dependencies["sqlx"]["optional"].as_bool(), Some(true));
+    assert_eq!(dependencies["dotenv"]["version"].as_str(), Some("0.15"));
+    assert_eq!(dependencies["dotenv"]["optional"].as_bool(), Some(true));
+    assert!(dependencies["tokio"].get("optional").is_none());
+    assert_eq!(features(&dependencies["tokio"]), vec!["macros", "full"]);
+
+    let declared = &parsed["features"];
+    assert_eq!(declared["default"].as_array().unwrap().len(), 1);
+    let database: Vec<&str> = declared["database"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f.as_str().unwrap())
+        .collect();
+    assert_eq!(database, vec!["dep:sqlx", "dep:dotenv"]);
+}
+
+#[test]
+fn test_generated_optional_features() {
+    let config = ProjectConfig {
+        name: "db-lib".to_string(),
+        project_type: "library".to_string(),
+        author: "Test Author".to_string(),
+        description: None,
+        features: vec!["database".to_string()],
+        target: None,
+        esp32_chip: None,
+    };
+    let plan = Generator::new()
+        .with_optional_features(true)
+        .plan(&config)
+        .unwrap();
+    let contents =
+        |path: &str| String::from_utf8_lossy(&plan.file(path).unwrap().contents).into_owned();
+    let manifest: Value = toml::from_str(&contents("Cargo.toml")).unwrap();
+
+    assert_eq!(
+        manifest["dependencies"]["sqlx"]["optional"].as_bool(),
+        Some(true)
+    );
+    let default = manifest["features"]["default"].as_array().unwrap();
+    assert!(default.iter().any(|f| f.as_str() == Some("database")));
+    assert!(default.iter().any(|f| f.as_str() == Some("migrations")));
+    assert_eq!(
+        manifest["features"]["migrations"].as_array().unwrap()[0].as_str(),
+        Some("database")
+    );
+    assert_eq!(
+        manifest["example"][0]["required-features"][0].as_str(),
+        Some("database")
+    );
+    assert!(contents("src/database.rs").starts_with("#![cfg(feature = \"database\")]"));
+
+    // Without the option the dependencies are required, but `migrations` is still declared
+    let plan = Generator::new().plan(&config).unwrap();
+    let manifest: Value =
+        toml::from_str(std::str::from_utf8(&plan.file("Cargo.toml").unwrap().contents).unwrap())
+            .unwrap();
+    assert!(manifest["dependencies"]["sqlx"].get("optional").is_none());
+    assert!(manifest["features"]["migrations"]
+        .as_array()
+        .unwrap()
+        .is_empty());
+
+    let server = ProjectConfig {
+        project_type: "api-server".to_string(),
+        ..config
+    };
+    let error = Generator::new()
+        .with_optional_features(true)
+        .plan(&server)
+        .unwrap_err();
+    assert!(error.to_string().contains("library and cli-tool"));
+}
@@ -0,0 +1,203 @@
+use cargo_forge::plan::GenerationPlan;
+use cargo_forge::{Generator, ProjectConfig};
+use std::collections::HashMap;
+use toml::Value;
+
+fn config(project_type: &str, features: &[&str]) -> ProjectConfig {
+    ProjectConfig {
+        name: "pack-tool".to_string(),
+        project_type: project_type.to_string(),
+        author: "Jane Doe <jane@example.com>".to_string(),
+        description: Some("Packs things".to_string()),
+        features: features.iter().map(|f| f.to_string()).collect(),
+        target: None,
+        esp32_chip: None,
+    }
+}
+
+fn generate(generator: Generator, project_type: &str, features: &[&str]) -> GenerationPlan {
+    generator.plan(&config(project_type, features)).unwrap()
+}
+
+fn contents(plan: &GenerationPlan, path: &str) -> String {
+    String::from_utf8_lossy(&plan.file(path).unwrap().contents).into_owned()
+}
+
+fn manifest(plan: &GenerationPlan, path: &str) -> Value {
+    toml::from_str(&contents(plan, path)).unwrap()
+}
+
+#[test]
+fn test_cli_tool_packaging_metadata() {
+    let plan = generate(Generator::new(), "cli-tool", &["packaging"]);
+    let manifest = manifest(&plan, "Cargo.toml");
+
+    let package = &manifest["package"];
+    assert_eq!(
+        package["repository"].as_str(),
+        Some("https://github.com/OWNER/pack-tool")
+    );
+    let metadata = &package["metadata"];
+    let deb_assets = metadata["deb"]["assets"].as_array().unwrap();
+    assert_eq!(deb_assets[0][0].as_str(), Some("target/release/pack-tool"));
+    assert_eq!(deb_assets[1][0].as_str(), Some("target/release/man/*.1"));
+    let rpm_assets = metadata["generate-rpm"]["assets"].as_array().unwrap();
+    assert_eq!(rpm_assets[0]["dest"].as_str(), Some("/usr/bin/pack-tool"));
+    assert_eq!(rpm_assets[1]["dest"].as_str(), Some("/usr/share/man/man1/"));
+    assert!(metadata["binstall"]["pkg-url"]
+        .as_str()
+        .unwrap()
+        .starts_with("{ repo }/releases/download/v{ version }/pack-tool-{ target }"));
+
+    let build_dependencies = &manifest["build-dependencies"];
+    assert!(build_dependencies.get("clap").is_some());
+    assert!(build_dependencies.get("clap_mangen").is_some());
+
+    let build = contents(&plan, "build.rs");
+    assert!(build.contains("#[path = \"src/cli.rs\"]"));
+    assert!(build.contains("clap_mangen::generate_to(cli::Cli::command(), &man_dir)"));
+
+    assert!(contents(&plan, "README.md").contains("## Packaging"));
+}
+
+#[test]
+fn test_packaging_files_use_project_metadata() {
+    let generator = Generator::new().with_license(Some("MIT OR Apache-2.0".to_string()));
+    let plan = generate(generator, "cli-tool", &["packaging"]);
+
+    let formula = contents(&plan, "packaging/homebrew/pack-tool.rb");
+    assert!(formula.starts_with("class PackTool < Formula"));
+    assert!(formula.contains("desc \"Packs things\""));
+    assert!(formula.contains("license any_of: [\"MIT\", \"Apache-2.0\"]"));
+    assert!(formula
+        .contains("url \"https://github.com/OWNER/pack-tool/archive/refs/tags/v0.1.0.tar.gz\""));
+    assert!(formula.contains("man1.install Dir[\"target/release/man/*.1\"]"));
+
+    let pkgbuild = contents(&plan, "packaging/aur/PKGBUILD");
+    assert!(pkgbuild.starts_with("# Maintainer: Jane Doe <jane@example.com>\n"));
+    assert!(pkgbuild.contains("pkgname=pack-tool\n"));
+    assert!(pkgbuild.contains("pkgdesc=\"Packs things\"\n"));
+    assert!(pkgbuild.contains("license=('MIT OR Apache-2.0')\n"));
+    assert!(pkgbuild
+        .contains("  install -Dm0644 -t \"$pkgdir/usr/share/licenses/$pkgname/\" LICENSE*\n"));
+
+    // Every package installs the license files
+    let cargo_toml = manifest(&plan, "Cargo.toml");
+    let metadata = &cargo_toml["package"]["metadata"];
+    let deb_assets = metadata["deb"]["assets"].as_array().unwrap();
+    assert_eq!(deb_assets[3][0].as_str(), Some("LICENSE-MIT"));
+    assert_eq!(
+        deb_assets[4][1].as_str(),
+        Some("usr/share/doc/pack-tool/LICENSE-APACHE")
+    );
+    let rpm_assets = metadata["generate-rpm"]["assets"].as_array().unwrap();
+    assert_eq!(
+        rpm_assets[3]["dest"].as_str(),
+        Some("/usr/share/licenses/pack-tool/LICENSE-MIT")
+    );
+
+    // The formula's description is a double-quoted Ruby string
+    let mut quoted = config("cli-tool", &["packaging"]);
+    quoted.description = Some("Packs \"#{things}\"".to_string());
+    let plan = Generator::new().plan(&quoted).unwrap();
+    assert!(contents(&plan, "packaging/homebrew/pack-tool.rb")
+        .contains("desc \"Packs \\\"\\#{things}\\\"\"\n"));
+
+    // Proprietary projects name their license for the package managers that need one
+    let generator = Generator::new().with_license(Some("proprietary".to_string()));
+    let plan = generate(generator, "cli-tool", &["packaging"]);
+    let manifest = manifest(&plan, "Cargo.toml");
+    assert_eq!(
+        manifest["package"]["metadata"]["generate-rpm"]["license"].as_str(),
+        Some("Proprietary")
+    );
+    assert!(!contents(&plan, "packaging/homebrew/pack-tool.rb").contains("license"));
+    assert!(
+        contents(&plan, "packaging/aur/PKGBUILD").contains("license=('LicenseRef-Proprietary')")
+    );
+}
+
+#[test]
+fn test_repository_variable() {
+    let variables = HashMap::from([(
+        "repository".to_string(),
+        serde_json::json!("https://git.example.com/tools/pack-tool"),
+    )]);
+    let plan = generate(
+        Generator::new().with_variables(variables),
+        "cli-tool",
+        &["packaging"],
+    );
+
+    let manifest = manifest(&plan, "Cargo.toml");
+    assert_eq!(
+        manifest["package"]["repository"].as_str(),
+        Some("https://git.example.com/tools/pack-tool")
+    );
+    assert!(contents(&plan, "packaging/homebrew/pack-tool.rb")
+        .contains("homepage \"https://git.example.com/tools/pack-tool\""));
+    assert!(contents(&plan, "packaging/aur/PKGBUILD")
+        .contains("url=\"https://git.example.com/tools/pack-tool\""));
+}
+
+#[test]
+fn test_workspace_packaging() {
+    let generator = Generator::new().with_license(Some("MIT".to_string()));
+    let plan = generate(generator, "workspace", &["clap", "packaging"]);
+
+    let root = manifest(&plan, "Cargo.toml");
+    assert_eq!(
+        root["workspace"]["package"]["repository"].as_str(),
+        Some("https://github.com/OWNER/pack-tool")
+    );
+
+    let cli = manifest(&plan, "crates/cli/Cargo.toml");
+    let package = &cli["package"];
+    assert_eq!(package["repository"]["workspace"].as_bool(), Some(true));
+    assert_eq!(
+        package["metadata"]["deb"]["name"].as_str(),
+        Some("pack-tool")
+    );
+    assert_eq!(
+        package["metadata"]["deb"]["assets"][2][0].as_str(),
+        Some("../../README.md")
+    );
+    assert_eq!(
+        package["metadata"]["deb"]["assets"][3][0].as_str(),
+        Some("../../LICENSE")
+    );
+    assert_eq!(
+        cli["build-dependencies"]["clap"]["workspace"].as_bool(),
+        Some(true)
+    );
+
+    assert!(contents(&plan, "crates/cli/src/main.rs")
+        .contains("use cli::{Cli, Commands, UserCommands};"));
+    assert!(contents(&plan, "crates/cli/src/cli.rs").contains("pub struct Cli"));
+    assert!(contents(&plan, "crates/cli/build.rs").contains("cli::Cli::command()"));
+    assert!(plan.file("build.rs").is_none());
+    assert!(contents(&plan, "packaging/homebrew/pack-tool.rb")
+        .contains("std_cargo_args(path: \"crates/cli\")"));
+    assert!(contents(&plan, "README.md").contains("cargo deb -p pack_tool_cli"));
+
+    // Without clap the man page only describes the binary
+    let plan = generate(Generator::new(), "workspace", &["packaging"]);
+    assert!(plan.file("crates/cli/src/cli.rs").is_none());
+    assert!(contents(&plan, "crates/cli/build.rs").contains("clap::Command::new(\"pack-tool\")"));
+}
+
+#[test]
+fn test_packaging_is_opt_in() {
+    let plan = generate(Generator::new(), "cli-tool", &[]);
+    let manifest = manifest(&plan, "Cargo.toml");
+    assert!(manifest["package"].get("repository").is_none());
+    assert!(manifest["package"].get("metadata").is_none());
+    assert!(manifest.get("build-dependencies").is_none());
+    assert!(plan.file("build.rs").is_none());
+    assert!(!contents(&plan, "README.md").contains("## Packaging"));
+
+    // Only CLI tools and workspaces have a binary to package
+    let plan = generate(Generator::new(), "library", &["packaging"]);
+    assert!(plan.file("build.rs").is_none());
+    assert!(plan.file("packaging/aur/PKGBUILD").is_none());
+}
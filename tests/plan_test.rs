@@ -0,0 +1,310 @@
+use cargo_forge::forge::{DryRunOptions, OutputFormat};
+use cargo_forge::plan::{FileSource, GenerationPlan, PlannedFile};
+use cargo_forge::{Forge, Generator, ProjectConfig};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn config(name: &str, project_type: &str, features: &[&str]) -> ProjectConfig {
+    ProjectConfig {
+        name: name.to_string(),
+        project_type: project_type.to_string(),
+        author: "Test Author".to_string(),
+        description: None,
+        features: features.iter().map(|f| f.to_string()).collect(),
+        target: None,
+        esp32_chip: None,
+    }
+}
+
+fn written_files(dir: &Path, relative: &str, files: &mut Vec<String>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let entry = entry.unwrap();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let path = if relative.is_empty() {
+            name
+        } else {
+            format!("{}/{}", relative, name)
+        };
+        if entry.file_type().unwrap().is_dir() {
+            written_files(&entry.path(), &path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+#[test]
+fn test_plan_matches_generated_files() {
+    let temp_dir = TempDir::new().unwrap();
+    for (project_type, features) in [
+        ("api-server", vec!["docker", "ci", "database"]),
+        ("cli-tool", vec![]),
+        ("library", vec!["ci"]),
+        ("workspace", vec![]),
+    ] {
+        let config = config("planned", project_type, &features);
+        let generator = Generator::new();
+        let plan = generator.plan(&config).unwrap();
+
+        let output_dir = temp_dir.path().join(project_type);
+        generator.generate(&config, &output_dir).unwrap();
+
+        let mut planned: Vec<String> = plan.files().iter().map(|f| f.path.clone()).collect();
+        planned.sort();
+        let mut written = Vec::new();
+        written_files(&output_dir, "", &mut written);
+        written.sort();
+        assert_eq!(planned, written, "{}", project_type);
+
+        for file in plan.files() {
+            assert_eq!(
+                fs::read(output_dir.join(&file.path)).unwrap(),
+                file.contents,
+                "{}",
+                file.path
+            );
+        }
+    }
+}
+
+#[test]
+fn test_plan_records_file_sources() {
+    let plan = Generator::new()
+        .plan(&config("sources", "api-server", &["docker", "ci"]))
+        .unwrap();
+
+    assert_eq!(
+        plan.file("Cargo.toml").unwrap().source,
+        FileSource::Template("api_server/Cargo.toml.tera".to_string())
+    );
+    assert_eq!(
+        plan.file("Dockerfile").unwrap().source,
+        FileSource::Plugin("Docker".to_string())
+    );
+    assert!(matches!(
+        plan.file(".github/workflows/ci.yml").unwrap().source,
+        FileSource::Plugin(_)
+    ));
+    assert_eq!(
+        plan.file(".gitignore").unwrap().source,
+        FileSource::Generated
+    );
+    // No license file is generated
+    assert!(plan.file("LICENSE").is_none());
+}
+
+#[test]
+fn test_plan_executable_scripts() {
+    let plan = Generator::new()
+        .plan(&config("scripts", "api-server", &["database"]))
+        .unwrap();
+    for file in plan.files() {
+        if file.path.ends_with(".sh") {
+            assert_eq!(file.mode, Some(0o755), "{}", file.path);
+        }
+    }
+}
+
+#[test]
+fn test_plan_tree() {
+    let mut plan = GenerationPlan::new();
+    plan.add_directory("tests");
+    for path in ["src/main.rs", "Cargo.toml", "src/bin/tool.rs"] {
+        plan.add_file(PlannedFile {
+            path: path.to_string(),
+            source: FileSource::Generated,
+            contents: Vec::new(),
+            mode: None,
+        });
+    }
+
+    let lines: Vec<String> = plan
+        .tree()
+        .into_iter()
+        .map(|line| {
+            format!(
+                "{}{}{}",
+                line.prefix,
+                line.name,
+                if line.is_dir { "/" } else { "" }
+            )
+        })
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            "├── Cargo.toml",
+            "├── src/",
+            "│   ├── bin/",
+            "│   │   └── tool.rs",
+            "│   └── main.rs",
+            "└── tests/",
+        ]
+    );
+}
+
+#[test]
+fn test_plan_esp32_not_supported() {
+    let mut config = config("chip", "embedded", &[]);
+    config.target = Some("esp32".to_string());
+    assert!(Generator::new().plan(&config).is_err());
+}
+
+#[test]
+fn test_plan_json() {
+    let mut plan = GenerationPlan::new();
+    plan.add_directory("tests");
+    plan.add_file(PlannedFile {
+        path: "scripts/run.sh".to_string(),
+        source: FileSource::Plugin("Database".to_string()),
+        contents: b"#!/bin/sh\n".to_vec(),
+        mode: Some(0o755),
+    });
+    plan.add_file(PlannedFile {
+        path: "assets/logo.png".to_string(),
+        source: FileSource::Template("game_engine/assets/logo.png".to_string()),
+        contents: vec![0x89, 0x50, 0xff, 0xfe],
+        mode: None,
+    });
+    plan.add_file(PlannedFile {
+        path: ".gitignore".to_string(),
+        source: FileSource::Generated,
+        contents: b"/target\n".to_vec(),
+        mode: None,
+    });
+
+    let summary = plan.to_json(false);
+    assert_eq!(summary["directories"][0], "tests");
+    assert_eq!(summary["files"][0]["path"], "scripts/run.sh");
+    assert_eq!(summary["files"][0]["size"], 10);
+    assert_eq!(summary["files"][0]["origin"], "plugin");
+    assert_eq!(summary["files"][0]["source"], "Database");
+    assert_eq!(summary["files"][0]["mode"], "755");
+    assert!(summary["files"][0].get("contents").is_none());
+    assert_eq!(summary["files"][2]["origin"], "built-in");
+    assert!(summary["files"][2]["source"].is_null());
+
+    let full = plan.to_json(true);
+    assert_eq!(full["files"][0]["contents"], "#!/bin/sh\n");
+    assert_eq!(full["files"][1]["binary"], true);
+    assert!(full["files"][1].get("contents").is_none());
+}
+
+#[test]
+fn test_dry_run_output_dir_matches_generation() {
+    let temp_dir = TempDir::new().unwrap();
+    let scratch = temp_dir.path().join("scratch");
+
+    Forge::new(temp_dir.path())
+        .with_dry_run_options(DryRunOptions {
+            format: OutputFormat::Json,
+            include_contents: false,
+            output_dir: Some(scratch.clone()),
+        })
+        .run_dry_run(
+            Some("inspect-me".to_string()),
+            Some("library".to_string()),
+            Some("Test Author".to_string()),
+            None,
+            true,
+            None,
+        )
+        .unwrap();
+
+    // The project itself is not created, only the scratch copy
+    assert!(!temp_dir.path().join("inspect-me").exists());
+    let mut rendered = Vec::new();
+    written_files(&scratch, "", &mut rendered);
+    rendered.sort();
+    assert!(rendered.contains(&"Cargo.toml".to_string()));
+    assert!(rendered.contains(&"src/lib.rs".to_string()));
+    let cargo_toml = fs::read_to_string(scratch.join("Cargo.toml")).unwrap();
+    assert!(cargo_toml.contains("name = \"inspect-me\""));
+}
+
+#[test]
+fn test_dry_run_output_dir_must_be_empty() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("keep.txt"), "mine").unwrap();
+
+    let result = Forge::new(temp_dir.path())
+        .with_dry_run_options(DryRunOptions {
+            output_dir: Some(temp_dir.path().to_path_buf()),
+            ..DryRunOptions::default()
+        })
+        .run_dry_run(
+            Some("inspect-me".to_string()),
+            Some("library".to_string()),
+            None,
+            None,
+            true,
+            None,
+        );
+
+    assert!(result.unwrap_err().to_string().contains("not empty"));
+}
+
+#[test]
+fn test_plugin_examples_written_and_listed() {
+    let plan = Generator::new()
+        .plan(&config("db-api", "api-server", &["database"]))
+        .unwrap();
+
+    let example = plan.file("examples/database_connection.rs").unwrap();
+    assert_eq!(example.source, FileSource::Plugin("Database".to_string()));
+    let code = String::from_utf8_lossy(&example.contents);
+    assert!(code.contains("#[path = \"../src/database.rs\"]"));
+
+    let readme = String::from_utf8_lossy(&plan.file("README.md").unwrap().contents).into_owned();
+    assert!(readme.contains("## Running Examples"));
+    assert!(readme.contains("cargo run --example database_connection\n"));
+}
+
+#[test]
+fn test_virtual_workspace_gets_no_plugin_code() {
+    // Nothing in a virtual workspace root would build plugin code or migrations
+    let plan = Generator::new()
+        .plan(&config(
+            "db-workspace",
+            "workspace",
+            &["database", "docker"],
+        ))
+        .unwrap();
+
+    for file in plan.files() {
+        assert!(
+            !file.path.starts_with("src/")
+                && !file.path.starts_with("examples/")
+                && !file.path.starts_with("migrations/"),
+            "{} written to the workspace root",
+            file.path
+        );
+    }
+    assert!(!plan.directories().iter().any(|dir| dir == "migrations"));
+    assert!(plan.file(".env.example").is_some());
+
+    let readme = String::from_utf8_lossy(&plan.file("README.md").unwrap().contents).into_owned();
+    assert!(!readme.contains("cargo run --example"));
+    let dockerfile =
+        String::from_utf8_lossy(&plan.file("Dockerfile").unwrap().contents).into_owned();
+    assert!(!dockerfile.contains("COPY migrations"));
+}
+
+#[test]
+fn test_template_examples_listed_in_readme() {
+    let plan = Generator::new()
+        .plan(&config("lib-with-examples", "library", &[]))
+        .unwrap();
+    let readme = String::from_utf8_lossy(&plan.file("README.md").unwrap().contents).into_owned();
+    assert!(readme
+        .trim_end()
+        .ends_with("cargo run --example basic\n```"));
+
+    // No examples, no section
+    let plan = Generator::new()
+        .plan(&config("no-examples", "cli-tool", &[]))
+        .unwrap();
+    let readme = String::from_utf8_lossy(&plan.file("README.md").unwrap().contents).into_owned();
+    assert!(!readme.contains("## Running Examples"));
+}
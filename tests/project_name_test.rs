@@ -0,0 +1,139 @@
+use cargo_forge::project_name::{check_dependencies, suggest, validate};
+use cargo_forge::{Forge, Generator, ProjectConfig};
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_rejects_names_that_break_the_build() {
+    for (name, expected) in [
+        ("async", "'async' is a Rust keyword"),
+        ("fn", "'fn' is a Rust keyword"),
+        ("gen", "'gen' is a Rust keyword"),
+        ("std", "standard library crate"),
+        ("proc-macro", "standard library crate"),
+        ("con", "reserved file name on Windows"),
+        ("nul", "reserved file name on Windows"),
+        ("lpt1", "reserved file name on Windows"),
+        ("_private", "cannot start with '-' or '_'"),
+    ] {
+        let error = validate(name).unwrap_err();
+        assert!(
+            error.message.contains(expected),
+            "{}: {}",
+            name,
+            error.message
+        );
+    }
+
+    // Only the whole name is reserved
+    for name in ["async-runtime", "console", "my-std"] {
+        assert!(validate(name).is_ok(), "{} should be valid", name);
+    }
+}
+
+#[test]
+fn test_errors_suggest_a_fix() {
+    for (name, expected) in [
+        ("My App", "my-app"),
+        ("my--app", "my-app"),
+        ("my_app_", "my_app"),
+        ("123 Rust Tool", "rust-tool"),
+        ("Café Bar", "caf-bar"),
+        ("test", "test-app"),
+        ("async", "async-app"),
+    ] {
+        let error = validate(name).unwrap_err();
+        assert_eq!(error.suggestion.as_deref(), Some(expected), "{}", name);
+        assert!(validate(expected).is_ok());
+    }
+
+    let error = validate("My App").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Project name cannot contain spaces (try 'my-app')"
+    );
+
+    assert_eq!(suggest("my-app"), None);
+    assert_eq!(suggest("---"), None);
+    assert_eq!(suggest("Café"), None);
+
+    // Suggestions are never truncated to fit
+    assert_eq!(suggest(&"A".repeat(64)).unwrap().len(), 64);
+    assert_eq!(suggest(&"A".repeat(80)), None);
+}
+
+#[test]
+fn test_dependency_clash() {
+    let error = check_dependencies("serde-json", ["serde", "serde_json"]).unwrap_err();
+    assert!(error.message.contains("'serde_json' dependency"));
+    assert_eq!(error.suggestion.as_deref(), Some("serde-json-app"));
+    assert!(check_dependencies("my-tokio", ["tokio"]).is_ok());
+
+    // The generated manifest is checked once plugins have added their dependencies
+    let temp_dir = TempDir::new().unwrap();
+    let config = |name: &str, features: &[&str]| ProjectConfig {
+        name: name.to_string(),
+        project_type: "api-server".to_string(),
+        author: "Test Author".to_string(),
+        description: None,
+        features: features.iter().map(|f| f.to_string()).collect(),
+        target: None,
+        esp32_chip: None,
+    };
+    let error = Generator::new()
+        .generate(&config("tokio", &[]), &temp_dir.path().join("tokio"))
+        .unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("clashes with the 'tokio' dependency"));
+    assert!(!temp_dir.path().join("tokio").exists());
+
+    let error = Generator::new()
+        .generate(
+            &config("sqlx", &["database"]),
+            &temp_dir.path().join("sqlx"),
+        )
+        .unwrap_err();
+    assert!(error.to_string().contains("'sqlx' dependency"));
+}
+
+#[test]
+fn test_forge_checks_dependency_clash_before_generating() {
+    let temp_dir = TempDir::new().unwrap();
+    let error = Forge::new(temp_dir.path())
+        .run_with_args(
+            Some("tokio".to_string()),
+            Some("api-server".to_string()),
+            Some("Test Author".to_string()),
+            None,
+        )
+        .unwrap_err();
+    assert!(error.to_string().contains("(try 'tokio-app')"));
+
+    // Features from the config file bring in their own dependencies
+    let config_path = temp_dir.path().join("forge.json");
+    fs::write(
+        &config_path,
+        r#"{
+            "default_author": "Test Author",
+            "default_license": "MIT",
+            "preferred_project_types": [],
+            "default_features": { "api-server": ["database"] },
+            "edition": null
+        }"#,
+    )
+    .unwrap();
+    let error = Forge::new(temp_dir.path())
+        .run_from_config(
+            config_path,
+            Some("sqlx".to_string()),
+            Some("api-server".to_string()),
+            None,
+            None,
+        )
+        .unwrap_err();
+    assert!(error.to_string().contains("'sqlx' dependency"));
+    assert!(error.to_string().contains("(try 'sqlx-app')"));
+
+    assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+}
@@ -70,6 +70,58 @@ fn test_api_server_project_type() {
     );
 }
 
+#[test]
+fn test_api_server_layout() {
+    let temp_dir = TempDir::new().unwrap();
+    let generate = |name: &str, features: &[&str]| {
+        let output_dir = temp_dir.path().join(name);
+        let config = ProjectConfig {
+            name: name.to_string(),
+            project_type: ProjectType::ApiServer.to_string(),
+            author: "Test Author".to_string(),
+            description: None,
+            features: features.iter().map(|f| f.to_string()).collect(),
+            target: None,
+            esp32_chip: None,
+        };
+        Generator::new().generate(&config, &output_dir).unwrap();
+        output_dir
+    };
+
+    let output_dir = generate("layout-api", &[]);
+    for file in ["lib.rs", "config.rs", "error.rs", "state.rs"] {
+        assert!(output_dir.join("src").join(file).exists(), "{}", file);
+    }
+
+    // main only wires the library's router to a socket
+    let main = fs::read_to_string(output_dir.join("src/main.rs")).unwrap();
+    assert!(main.contains("use layout_api::{config::Config, routes, state::AppState};"));
+    assert!(main.contains(".with_graceful_shutdown(shutdown_signal())"));
+    assert!(main.contains("SignalKind::terminate()"));
+
+    let routes = fs::read_to_string(output_dir.join("src/routes.rs")).unwrap();
+    assert!(routes.contains(".route(\"/ready\", get(handlers::ready))"));
+    assert!(routes.contains("TraceLayer::new_for_http()"));
+    let error = fs::read_to_string(output_dir.join("src/error.rs")).unwrap();
+    assert!(error.contains("impl IntoResponse for AppError"));
+
+    // Tests call the router in-process
+    let tests = fs::read_to_string(output_dir.join("tests/api.rs")).unwrap();
+    assert!(tests.contains("use tower::ServiceExt;"));
+    assert!(tests.contains("async fn ready_is_ok()"));
+    let cargo_toml = fs::read_to_string(output_dir.join("Cargo.toml")).unwrap();
+    assert!(cargo_toml.contains("http-body-util"));
+
+    // With a database, readiness checks the pool
+    let output_dir = generate("db-api", &["database"]);
+    let state = fs::read_to_string(output_dir.join("src/state.rs")).unwrap();
+    assert!(state.contains("pub db: sqlx::PgPool,"));
+    let handlers = fs::read_to_string(output_dir.join("src/handlers.rs")).unwrap();
+    assert!(handlers.contains("sqlx::query(\"SELECT 1\")"));
+    let tests = fs::read_to_string(output_dir.join("tests/api.rs")).unwrap();
+    assert!(tests.contains("async fn ready_reports_unreachable_database()"));
+}
+
 #[test]
 fn test_cli_tool_project_type() {
     // Test CLI tool project generation
@@ -129,6 +181,56 @@ fn test_cli_tool_project_type() {
     );
 }
 
+#[test]
+fn test_cli_tool_layout() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_dir = temp_dir.path().join("layout-cli");
+    let config = ProjectConfig {
+        name: "layout-cli".to_string(),
+        project_type: ProjectType::CliTool.to_string(),
+        author: "Test Author".to_string(),
+        description: None,
+        features: vec![],
+        target: None,
+        esp32_chip: None,
+    };
+    Generator::new().generate(&config, &output_dir).unwrap();
+
+    // Errors become exit codes instead of a panic-style report
+    let main = fs::read_to_string(output_dir.join("src/main.rs")).unwrap();
+    assert!(main.contains("fn main() -> ExitCode"));
+    assert!(main.contains("exit::code(&error)"));
+    assert!(main.contains(".filter_level(cli.log_level())"));
+    let exit = fs::read_to_string(output_dir.join("src/exit.rs")).unwrap();
+    assert!(exit.contains("pub const CONFIG: u8 = 78;"));
+
+    let cli = fs::read_to_string(output_dir.join("src/cli.rs")).unwrap();
+    assert!(cli.contains("#[derive(Debug, Parser)]"));
+    assert!(cli.contains("pub command: Commands,"));
+    assert!(cli.contains("#[arg(env = \"LAYOUT_CLI_CONFIG\")]"));
+    assert!(cli.contains("#[command(hide = true)]\n    Completions {"));
+
+    let config = fs::read_to_string(output_dir.join("src/config.rs")).unwrap();
+    assert!(config.contains("XDG_CONFIG_HOME"));
+    assert!(config.contains("ProjectDirs::from(\"\", \"\", \"layout-cli\")"));
+
+    // Tests run the binary
+    let tests = fs::read_to_string(output_dir.join("tests/cli.rs")).unwrap();
+    assert!(tests.contains("Command::cargo_bin(\"layout-cli\")"));
+    let manifest: toml::Value =
+        toml::from_str(&fs::read_to_string(output_dir.join("Cargo.toml")).unwrap()).unwrap();
+    for dependency in ["clap_complete", "serde", "toml", "directories"] {
+        assert!(manifest["dependencies"].get(dependency).is_some(), "{}", dependency);
+    }
+    for dependency in ["assert_cmd", "predicates", "tempfile"] {
+        assert!(
+            manifest["dev-dependencies"].get(dependency).is_some(),
+            "{}",
+            dependency
+        );
+    }
+}
+
 #[test]
 fn test_library_project_type() {
     // Test library project generation
@@ -560,6 +662,33 @@ fn test_workspace_project_type() {
     );
 }
 
+#[test]
+fn test_workspace_without_web_has_no_unused_code() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_dir = temp_dir.path().join("workspace-clap");
+    let config = ProjectConfig {
+        name: "workspace-clap".to_string(),
+        project_type: ProjectType::Workspace.to_string(),
+        author: "Test Author".to_string(),
+        description: None,
+        features: vec!["clap".to_string(), "packaging".to_string()],
+        target: None,
+        esp32_chip: None,
+    };
+    Generator::new().generate(&config, &output_dir).unwrap();
+
+    let core = fs::read_to_string(output_dir.join("crates/core/src/lib.rs")).unwrap();
+    assert!(!core.contains("use thiserror::Error;"));
+    let utils = fs::read_to_string(output_dir.join("crates/core/src/utils.rs")).unwrap();
+    assert!(!utils.contains("use serde::"));
+    assert!(!utils.contains("HashSet"));
+
+    // Without the web feature the serve arguments are never read
+    let main = fs::read_to_string(output_dir.join("crates/cli/src/main.rs")).unwrap();
+    assert!(main.contains("Commands::Serve { .. } => {"));
+    assert!(!main.contains("Commands::Serve { port, host }"));
+}
+
 #[test]
 fn test_project_type_enum_display() {
     // Test that ProjectType enum properly implements Display
@@ -0,0 +1,132 @@
+use cargo_forge::templates::check::TemplateChecker;
+use cargo_forge::ProjectType;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn write(root: &Path, name: &str, content: &str) {
+    let path = root.join(name);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn check_library(template_dir: &Path) -> cargo_forge::templates::check::CheckReport {
+    TemplateChecker::new()
+        .with_template_dirs(vec![template_dir.to_path_buf()])
+        .with_project_types(vec![ProjectType::Library])
+        .run()
+        .unwrap()
+}
+
+#[test]
+fn test_bundled_templates_pass() {
+    let report = TemplateChecker::new().run().unwrap();
+    for failure in &report.failures {
+        eprintln!("{}", failure);
+    }
+    assert!(report.is_clean());
+    assert!(report.combinations >= ProjectType::ALL.len() * 2);
+    assert!(report.files_checked > 0);
+}
+
+#[test]
+fn test_broken_toml_reported_with_feature_set_and_line() {
+    let temp_dir = TempDir::new().unwrap();
+    write(
+        temp_dir.path(),
+        "library/Cargo.toml.tera",
+        "[package]\nname = \"{{ name }}\"\n{% if has_feature(feature=\"metrics\") %}\nversion = 0.1.0\n{% endif %}",
+    );
+
+    let report = check_library(temp_dir.path());
+    let failure = report
+        .failures
+        .iter()
+        .find(|f| f.template == "library/Cargo.toml.tera")
+        .expect("Cargo.toml failure");
+    assert_eq!(failure.project_type, ProjectType::Library);
+    assert_eq!(failure.features, vec!["metrics"]);
+    // Line in the rendered file, after the blank line left by the tag
+    assert_eq!(failure.line, Some(4));
+    assert!(failure.to_string().contains("features: metrics"));
+
+    // Reported once even though several feature sets enable `metrics`
+    let count = report
+        .failures
+        .iter()
+        .filter(|f| f.template == "library/Cargo.toml.tera")
+        .count();
+    assert_eq!(count, 1);
+}
+
+#[test]
+fn test_broken_rust_and_yaml_reported() {
+    let temp_dir = TempDir::new().unwrap();
+    write(
+        temp_dir.path(),
+        "library/src/lib.rs.tera",
+        "pub fn answer() -> u32 {\n    42\n{% if has_feature(feature=\"serde\") %}}{% endif %}\n",
+    );
+    write(
+        temp_dir.path(),
+        "library/.github/workflows/ci.yml.tera",
+        "name: CI\non: [push\n",
+    );
+
+    let report = check_library(temp_dir.path());
+    let rust = report
+        .failures
+        .iter()
+        .find(|f| f.template == "library/src/lib.rs.tera")
+        .expect("lib.rs failure");
+    assert!(!rust.features.contains(&"serde".to_string()));
+    assert!(rust.line.is_some());
+
+    assert!(report
+        .failures
+        .iter()
+        .any(|f| f.template == "library/.github/workflows/ci.yml.tera"));
+}
+
+#[test]
+fn test_undefined_condition_variables_reported() {
+    let temp_dir = TempDir::new().unwrap();
+    write(
+        temp_dir.path(),
+        "library/README.md.tera",
+        "# {{ name }}\n\
+         {% if license %}licensed{% endif %}\n\
+         {% for feature in features %}{% if feature == \"x\" %}x{% endif %}{% endfor %}\n\
+         {% if tier is defined or port | default(value=0) %}ok{% endif %}\n\
+         {% if has_serde and has_feature(feature=\"serde\") %}serde{% endif %}\n\
+         {% if database %}database{% endif %}\n",
+    );
+
+    let report = check_library(temp_dir.path());
+    let failures: Vec<_> = report
+        .failures
+        .iter()
+        .filter(|f| f.template == "library/README.md.tera")
+        .collect();
+    assert_eq!(failures.len(), 1, "{:?}", failures);
+    assert_eq!(failures[0].line, Some(6));
+    assert!(failures[0].message.contains("`database`"));
+}
+
+#[test]
+fn test_render_errors_reported() {
+    let temp_dir = TempDir::new().unwrap();
+    write(
+        temp_dir.path(),
+        "library/src/lib.rs.tera",
+        "pub const NAME: &str = \"{{ missing_value }}\";\n",
+    );
+
+    let report = check_library(temp_dir.path());
+    let failure = report
+        .failures
+        .iter()
+        .find(|f| f.template == "library/src/lib.rs.tera")
+        .expect("render failure");
+    assert!(failure.message.contains("missing_value"));
+}
@@ -0,0 +1,170 @@
+use cargo_forge::templates::conditional::ConditionalRenderer;
+use cargo_forge::templates::TemplateEngine;
+use cargo_forge::{Generator, ProjectConfig};
+use std::fs;
+use tempfile::TempDir;
+use tera::Context;
+
+#[test]
+fn test_feature_functions_use_engine_features() {
+    let mut engine =
+        TemplateEngine::with_features(vec!["auth".to_string(), "database".to_string()]).unwrap();
+    engine
+        .add_template(
+            "flags",
+            "{{ has_feature(feature=\"auth\") }} {{ has_feature(feature=\"cache\") }} \
+             {{ has_any_feature(check=[\"cache\", \"database\"]) }} \
+             {{ has_all_features(check=[\"auth\", \"cache\"]) }}",
+        )
+        .unwrap();
+
+    let rendered = engine.render("flags", &Context::new()).unwrap();
+    assert_eq!(rendered, "true false true false");
+
+    // Features added later are seen by the functions too
+    engine.add_feature("cache".to_string());
+    let rendered = engine.render("flags", &Context::new()).unwrap();
+    assert_eq!(rendered, "true true true true");
+}
+
+#[test]
+fn test_explicit_features_argument_still_supported() {
+    let mut renderer = ConditionalRenderer::new(vec!["api".to_string()]).unwrap();
+    renderer
+        .add_template(
+            "flags",
+            "{{ has_feature(feature=\"api\", features=features) }} \
+             {{ has_feature(feature=\"api\", features=[\"cli\"]) }}",
+        )
+        .unwrap();
+
+    let rendered = renderer.render("flags", Context::new()).unwrap();
+    assert_eq!(rendered, "true false");
+}
+
+#[test]
+fn test_shared_partials_and_macros() {
+    let mut renderer = ConditionalRenderer::new(vec![]).unwrap();
+    renderer
+        .add_template(
+            "Cargo.toml",
+            "{% import \"macros/cargo.tera\" as cargo %}\
+             {% include \"partials/package.toml.tera\" %}\n[dependencies]\n\
+             {{ cargo::dependency(name=\"serde\", version=\"1.0\", features=[\"derive\"]) }}\n\
+             {{ cargo::dependency(name=\"anyhow\", version=\"1.0\") }}\n",
+        )
+        .unwrap();
+
+    let mut context = Context::new();
+    context.insert("name", "shared-parts");
+    context.insert("author", "Test Author");
+    context.insert("license", "MIT");
+    let rendered = renderer.render("Cargo.toml", context).unwrap();
+
+    let parsed: toml::Value = toml::from_str(&rendered).unwrap();
+    assert_eq!(parsed["package"]["name"].as_str(), Some("shared-parts"));
+    assert_eq!(parsed["package"]["license"].as_str(), Some("MIT"));
+    assert!(parsed["package"].get("description").is_none());
+    assert_eq!(
+        parsed["dependencies"]["serde"]["features"][0].as_str(),
+        Some("derive")
+    );
+    assert_eq!(parsed["dependencies"]["anyhow"].as_str(), Some("1.0"));
+}
+
+#[test]
+fn test_custom_templates_can_import_macros() {
+    let temp_dir = TempDir::new().unwrap();
+    let template_dir = temp_dir.path().join("house-style");
+    fs::create_dir_all(template_dir.join("library/src")).unwrap();
+    fs::create_dir_all(template_dir.join("macros")).unwrap();
+    // The importing template sorts before the macro file it needs
+    fs::write(
+        template_dir.join("library/src/lib.rs.tera"),
+        "{% import \"macros/house.tera\" as house %}{{ house::banner(name=name) }}\n",
+    )
+    .unwrap();
+    fs::write(
+        template_dir.join("macros/house.tera"),
+        "{% macro banner(name) %}//! {{ name }}, house style{% endmacro banner %}",
+    )
+    .unwrap();
+
+    let config = ProjectConfig {
+        name: "house-lib".to_string(),
+        project_type: "library".to_string(),
+        author: "Test Author".to_string(),
+        description: None,
+        features: vec!["auth".to_string()],
+        target: None,
+        esp32_chip: None,
+    };
+    let output_dir = temp_dir.path().join("house-lib");
+    Generator::new()
+        .with_template_dirs(vec![template_dir])
+        .generate(&config, &output_dir)
+        .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(output_dir.join("src/lib.rs")).unwrap(),
+        "//! house-lib, house style\n"
+    );
+    // Shared macros are not emitted into the project
+    assert!(!output_dir.join("macros").exists());
+}
+
+#[test]
+fn test_custom_assets_shadow_embedded_templates() {
+    let temp_dir = TempDir::new().unwrap();
+    let template_dir = temp_dir.path().join("house-style");
+    fs::create_dir_all(template_dir.join("library")).unwrap();
+    fs::write(template_dir.join("library/README.md"), "# {{ name }}\n").unwrap();
+
+    let engine = TemplateEngine::new()
+        .unwrap()
+        .with_template_dirs(&[template_dir])
+        .unwrap();
+    assert!(!engine
+        .list_templates()
+        .contains(&"library/README.md.tera".to_string()));
+    assert!(engine
+        .list_templates()
+        .contains(&"library/Cargo.toml.tera".to_string()));
+
+    let mut context = Context::new();
+    context.insert("name", "plain-lib");
+    let readme: Vec<_> = engine
+        .try_render_directory("library", &context)
+        .unwrap()
+        .into_iter()
+        .filter(|(name, _)| name.starts_with("library/README.md"))
+        .collect();
+    assert_eq!(readme.len(), 1);
+    assert_eq!(readme[0].1.as_ref().unwrap().contents, b"# {{ name }}\n");
+}
+
+#[test]
+fn test_auth_templates_follow_enabled_features() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let generate = |name: &str, features: Vec<&str>| {
+        let config = ProjectConfig {
+            name: name.to_string(),
+            project_type: "api-server".to_string(),
+            author: "Test Author".to_string(),
+            description: None,
+            features: features.into_iter().map(String::from).collect(),
+            target: None,
+            esp32_chip: None,
+        };
+        let output_dir = temp_dir.path().join(name);
+        Generator::new().generate(&config, &output_dir).unwrap();
+        fs::read_to_string(output_dir.join("src/auth/jwt.rs")).unwrap()
+    };
+
+    let tenant = generate("tenant-api", vec!["auth", "multi-tenant"]);
+    assert!(tenant.contains("tenant_id"));
+
+    let single = generate("single-api", vec!["auth"]);
+    assert!(!single.contains("tenant_id"));
+}
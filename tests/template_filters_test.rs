@@ -0,0 +1,141 @@
+use cargo_forge::templates::conditional::ConditionalRenderer;
+use cargo_forge::templates::filters::{
+    crate_ident, escape_ruby_string, escape_toml_string, kebab_case, pascal_case, shouty_snake,
+    snake_case,
+};
+use cargo_forge::{Generator, ProjectConfig};
+use std::fs;
+use tempfile::TempDir;
+use tera::Context;
+
+#[test]
+fn test_case_conversions() {
+    let cases = [
+        (
+            "my-project",
+            "my_project",
+            "MyProject",
+            "my-project",
+            "MY_PROJECT",
+        ),
+        (
+            "my_project",
+            "my_project",
+            "MyProject",
+            "my-project",
+            "MY_PROJECT",
+        ),
+        (
+            "MyProject",
+            "my_project",
+            "MyProject",
+            "my-project",
+            "MY_PROJECT",
+        ),
+        (
+            "myHTTPServer",
+            "my_http_server",
+            "MyHttpServer",
+            "my-http-server",
+            "MY_HTTP_SERVER",
+        ),
+        ("api v2", "api_v2", "ApiV2", "api-v2", "API_V2"),
+        (
+            "game2d-engine",
+            "game2d_engine",
+            "Game2dEngine",
+            "game2d-engine",
+            "GAME2D_ENGINE",
+        ),
+    ];
+
+    for (input, snake, pascal, kebab, shouty) in cases {
+        assert_eq!(snake_case(input), snake, "snake_case({})", input);
+        assert_eq!(pascal_case(input), pascal, "pascal_case({})", input);
+        assert_eq!(kebab_case(input), kebab, "kebab_case({})", input);
+        assert_eq!(shouty_snake(input), shouty, "shouty_snake({})", input);
+    }
+}
+
+#[test]
+fn test_crate_ident() {
+    assert_eq!(crate_ident("my-project"), "my_project");
+    assert_eq!(crate_ident("MyCrate"), "MyCrate");
+    assert_eq!(crate_ident("9lives"), "_9lives");
+    assert_eq!(crate_ident("dots.and spaces"), "dots_and_spaces");
+}
+
+#[test]
+fn test_escape_toml_string() {
+    assert_eq!(escape_toml_string("plain"), "plain");
+    assert_eq!(
+        escape_toml_string("say \"hi\" C:\\path\nnext\ttab"),
+        "say \\\"hi\\\" C:\\\\path\\nnext\\ttab"
+    );
+    assert_eq!(escape_toml_string("bell\u{7}"), "bell\\u0007");
+
+    let document = format!(
+        "value = \"{}\"",
+        escape_toml_string("a \"quoted\" \\ value")
+    );
+    let parsed: toml::Value = toml::from_str(&document).unwrap();
+    assert_eq!(parsed["value"].as_str(), Some("a \"quoted\" \\ value"));
+}
+
+#[test]
+fn test_escape_ruby_string() {
+    assert_eq!(escape_ruby_string("plain"), "plain");
+    assert_eq!(
+        escape_ruby_string("say \"#{hi}\" C:\\path\n"),
+        "say \\\"\\#{hi}\\\" C:\\\\path\\n"
+    );
+}
+
+#[test]
+fn test_filters_registered_on_conditional_renderer() {
+    let mut renderer = ConditionalRenderer::new(vec![]).unwrap();
+    renderer
+        .add_template(
+            "names",
+            "{{ name | snake_case }} {{ name | pascal_case }} {{ name | kebab_case }} \
+             {{ name | shouty_snake }} {{ name | crate_ident }}",
+        )
+        .unwrap();
+
+    let mut context = Context::new();
+    context.insert("name", "my-cool_app");
+    let rendered = renderer.render("names", context).unwrap();
+    assert_eq!(
+        rendered,
+        "my_cool_app MyCoolApp my-cool-app MY_COOL_APP my_cool_app"
+    );
+}
+
+#[test]
+fn test_generated_manifest_escapes_strings() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_dir = temp_dir.path().join("quoted-lib");
+
+    let config = ProjectConfig {
+        name: "quoted-lib".to_string(),
+        project_type: "library".to_string(),
+        author: "Jane \"JD\" Doe".to_string(),
+        description: Some("Parses C:\\paths and \"quotes\"".to_string()),
+        features: vec![],
+        target: None,
+        esp32_chip: None,
+    };
+
+    Generator::new().generate(&config, &output_dir).unwrap();
+
+    let manifest = fs::read_to_string(output_dir.join("Cargo.toml")).unwrap();
+    let parsed: toml::Value = toml::from_str(&manifest).unwrap();
+    assert_eq!(
+        parsed["package"]["description"].as_str(),
+        Some("Parses C:\\paths and \"quotes\"")
+    );
+    assert_eq!(
+        parsed["package"]["authors"][0].as_str(),
+        Some("Jane \"JD\" Doe")
+    );
+}
@@ -0,0 +1,349 @@
+use cargo_forge::templates::condition::Condition;
+use cargo_forge::templates::manifest::{
+    parse_assignment, resolve_variables, TemplateManifest, VariableType, MANIFEST_FILE,
+};
+use cargo_forge::{Generator, ProjectConfig, ProjectType};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use tempfile::TempDir;
+
+const HOUSE_MANIFEST: &str = r#"
+[template]
+name = "house-api"
+description = "Team API server layout"
+project_type = "api-server"
+prompts = ["service_name"]
+
+[variables.service_name]
+description = "Service name used in logs"
+validation = "^[a-z][a-z0-9-]*$"
+
+[variables.tier]
+choices = ["internal", "public"]
+default = "internal"
+
+[variables.replicas]
+type = "integer"
+default = 2
+"#;
+
+fn write_manifest(dir: &TempDir, content: &str) {
+    fs::write(dir.path().join(MANIFEST_FILE), content).unwrap();
+}
+
+#[test]
+fn test_manifest_load() {
+    let dir = TempDir::new().unwrap();
+    write_manifest(&dir, HOUSE_MANIFEST);
+
+    let manifest = TemplateManifest::load(dir.path().join(MANIFEST_FILE)).unwrap();
+    assert_eq!(manifest.template.name, "house-api");
+    assert_eq!(manifest.template.prompts, vec!["service_name"]);
+    assert_eq!(manifest.variables.len(), 3);
+    assert_eq!(
+        manifest.variables["replicas"].var_type,
+        VariableType::Integer
+    );
+    assert!(manifest.applies_to(ProjectType::ApiServer));
+    assert!(!manifest.applies_to(ProjectType::Library));
+}
+
+#[test]
+fn test_manifest_rejects_unknown_prompt() {
+    let dir = TempDir::new().unwrap();
+    write_manifest(
+        &dir,
+        "[template]\nname = \"broken\"\nprompts = [\"missing\"]\n",
+    );
+
+    let err = TemplateManifest::load(dir.path().join(MANIFEST_FILE)).unwrap_err();
+    assert!(format!("{:#}", err).contains("Prompt 'missing'"));
+}
+
+#[test]
+fn test_manifest_rejects_invalid_default() {
+    let dir = TempDir::new().unwrap();
+    write_manifest(
+        &dir,
+        "[template]\nname = \"broken\"\n\n[variables.tier]\nchoices = [\"a\", \"b\"]\ndefault = \"c\"\n",
+    );
+
+    assert!(TemplateManifest::load(dir.path().join(MANIFEST_FILE)).is_err());
+}
+
+#[test]
+fn test_resolve_variables_with_assignments_and_defaults() {
+    let dir = TempDir::new().unwrap();
+    write_manifest(&dir, HOUSE_MANIFEST);
+    let manifests =
+        TemplateManifest::discover(&[dir.path().to_path_buf()], ProjectType::ApiServer).unwrap();
+
+    let mut assignments = HashMap::new();
+    assignments.insert("service_name".to_string(), "billing".to_string());
+    assignments.insert("extra".to_string(), "passed-through".to_string());
+
+    let values = resolve_variables(&manifests, &assignments, |_, _| Ok(None)).unwrap();
+    assert_eq!(values["service_name"], "billing");
+    assert_eq!(values["tier"], "internal");
+    assert_eq!(values["replicas"], 2);
+    assert_eq!(values["extra"], "passed-through");
+}
+
+#[test]
+fn test_resolve_variables_validates_values() {
+    let dir = TempDir::new().unwrap();
+    write_manifest(&dir, HOUSE_MANIFEST);
+    let manifests =
+        TemplateManifest::discover(&[dir.path().to_path_buf()], ProjectType::ApiServer).unwrap();
+
+    let mut assignments = HashMap::new();
+    assignments.insert("service_name".to_string(), "Billing Service".to_string());
+    assert!(resolve_variables(&manifests, &assignments, |_, _| Ok(None)).is_err());
+
+    let mut assignments = HashMap::new();
+    assignments.insert("service_name".to_string(), "billing".to_string());
+    assignments.insert("replicas".to_string(), "many".to_string());
+    assert!(resolve_variables(&manifests, &assignments, |_, _| Ok(None)).is_err());
+}
+
+#[test]
+fn test_resolve_variables_prompts_and_requires_values() {
+    let dir = TempDir::new().unwrap();
+    write_manifest(&dir, HOUSE_MANIFEST);
+    let manifests =
+        TemplateManifest::discover(&[dir.path().to_path_buf()], ProjectType::ApiServer).unwrap();
+
+    // Non-interactive runs must pass variables without a default
+    let err = resolve_variables(&manifests, &HashMap::new(), |_, _| Ok(None)).unwrap_err();
+    assert!(err.to_string().contains("--var service_name=<value>"));
+
+    // Interactive runs are asked for the declared prompts only
+    let mut asked = Vec::new();
+    let values = resolve_variables(&manifests, &HashMap::new(), |name, _| {
+        asked.push(name.to_string());
+        Ok(Some("orders".to_string()))
+    })
+    .unwrap();
+    assert_eq!(asked, vec!["service_name"]);
+    assert_eq!(values["service_name"], "orders");
+}
+
+#[test]
+fn test_parse_assignment() {
+    assert_eq!(
+        parse_assignment("service_name=billing").unwrap(),
+        ("service_name".to_string(), "billing".to_string())
+    );
+    assert_eq!(
+        parse_assignment("filter=a=b").unwrap(),
+        ("filter".to_string(), "a=b".to_string())
+    );
+    assert!(parse_assignment("no-equals").is_err());
+    assert!(parse_assignment("=value").is_err());
+}
+
+#[test]
+fn test_manifest_variables_rendered() {
+    let temp_dir = TempDir::new().unwrap();
+    let template_dir = temp_dir.path().join("house-style");
+    fs::create_dir_all(template_dir.join("api_server/src")).unwrap();
+    fs::write(template_dir.join(MANIFEST_FILE), HOUSE_MANIFEST).unwrap();
+    fs::write(
+        template_dir.join("api_server/src/main.rs.tera"),
+        "// {{ service_name }} ({{ tier }}, {{ replicas }} replicas)\nfn main() {}\n",
+    )
+    .unwrap();
+
+    let manifests =
+        TemplateManifest::discover(std::slice::from_ref(&template_dir), ProjectType::ApiServer)
+            .unwrap();
+    let mut assignments = HashMap::new();
+    assignments.insert("service_name".to_string(), "billing".to_string());
+    let variables = resolve_variables(&manifests, &assignments, |_, _| Ok(None)).unwrap();
+
+    let config = ProjectConfig {
+        name: "house-api".to_string(),
+        project_type: "api-server".to_string(),
+        author: "Test Author".to_string(),
+        description: None,
+        features: vec![],
+        target: None,
+        esp32_chip: None,
+    };
+
+    let output_dir = temp_dir.path().join("house-api");
+    Generator::new()
+        .with_template_dirs(vec![template_dir])
+        .with_variables(variables)
+        .generate(&config, &output_dir)
+        .unwrap();
+
+    let main_content = fs::read_to_string(output_dir.join("src/main.rs")).unwrap();
+    assert_eq!(
+        main_content,
+        "// billing (internal, 2 replicas)\nfn main() {}\n"
+    );
+}
+
+#[test]
+fn test_include_if_conditions() {
+    let features: HashSet<String> = ["auth".to_string(), "web".to_string()].into();
+    let context = json!({ "project_type": "api-server", "tier": "public", "debug": false });
+
+    let cases = [
+        ("has_feature('auth')", true),
+        ("has_feature(\"database\")", false),
+        ("not has_feature('database')", true),
+        ("has_any_feature('database', 'web')", true),
+        ("has_all_features('auth', 'database')", false),
+        ("project_type == 'api-server' and tier != 'internal'", true),
+        (
+            "debug or (has_feature('auth') and not tier == 'public')",
+            false,
+        ),
+        ("missing", false),
+    ];
+    for (expr, expected) in cases {
+        let condition = Condition::parse(expr).unwrap();
+        assert_eq!(
+            condition.evaluate(&features, &context),
+            expected,
+            "{}",
+            expr
+        );
+    }
+
+    assert!(Condition::parse("has_feature('auth'").is_err());
+    assert!(Condition::parse("unknown_fn('x')").is_err());
+    assert!(Condition::parse("has_feature('a', 'b')").is_err());
+}
+
+#[test]
+fn test_templated_paths_and_include_if() {
+    let temp_dir = TempDir::new().unwrap();
+    let template_dir = temp_dir.path().join("house-style");
+    fs::create_dir_all(template_dir.join("library/src/{{ crate_ident }}")).unwrap();
+    fs::create_dir_all(template_dir.join("library/deploy")).unwrap();
+    fs::write(
+        template_dir.join("library/src/{{ crate_ident }}/mod.rs.tera"),
+        "pub fn name() -> &'static str { \"{{ name }}\" }\n",
+    )
+    .unwrap();
+    fs::write(
+        template_dir.join("library/deploy/app.yaml.tera"),
+        "name: {{ name }}\n",
+    )
+    .unwrap();
+    fs::write(
+        template_dir.join(MANIFEST_FILE),
+        "[template]\nname = \"paths\"\n\n[[files]]\npath = \"library/deploy\"\ninclude_if = \"has_feature('docker')\"\n",
+    )
+    .unwrap();
+
+    let generate = |name: &str, features: Vec<String>| {
+        let config = ProjectConfig {
+            name: name.to_string(),
+            project_type: "library".to_string(),
+            author: "Test Author".to_string(),
+            description: None,
+            features,
+            target: None,
+            esp32_chip: None,
+        };
+        let output_dir = temp_dir.path().join(name);
+        Generator::new()
+            .with_template_dirs(vec![template_dir.clone()])
+            .generate(&config, &output_dir)
+            .unwrap();
+        output_dir
+    };
+
+    let plain = generate("path-lib", vec![]);
+    assert!(plain.join("src/path_lib/mod.rs").exists());
+    assert!(!plain.join("deploy").exists());
+
+    let docker = generate("docker-lib", vec!["docker".to_string()]);
+    assert!(docker.join("src/docker_lib/mod.rs").exists());
+    assert_eq!(
+        fs::read_to_string(docker.join("deploy/app.yaml")).unwrap(),
+        "name: docker-lib\n"
+    );
+}
+
+#[test]
+fn test_templated_paths_use_engine_filters_and_functions() {
+    let temp_dir = TempDir::new().unwrap();
+    let template_dir = temp_dir.path().join("house-style");
+    let module_dir = "library/src/{{ name | snake_case }}";
+    fs::create_dir_all(template_dir.join(module_dir)).unwrap();
+    fs::write(
+        template_dir.join(module_dir).join("mod.rs.tera"),
+        "pub struct {{ name | pascal_case }};\n",
+    )
+    .unwrap();
+    fs::write(
+        template_dir
+            .join(module_dir)
+            .join("{% if has_feature(feature=\"auth\") %}auth{% else %}open{% endif %}.rs.tera"),
+        "\n",
+    )
+    .unwrap();
+
+    let config = ProjectConfig {
+        name: "my-lib".to_string(),
+        project_type: "library".to_string(),
+        author: "Test Author".to_string(),
+        description: None,
+        features: vec!["auth".to_string()],
+        target: None,
+        esp32_chip: None,
+    };
+    let output_dir = temp_dir.path().join("my-lib");
+    Generator::new()
+        .with_template_dirs(vec![template_dir])
+        .generate(&config, &output_dir)
+        .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(output_dir.join("src/my_lib/mod.rs")).unwrap(),
+        "pub struct MyLib;\n"
+    );
+    assert!(output_dir.join("src/my_lib/auth.rs").exists());
+    assert!(!output_dir.join("src/my_lib/open.rs").exists());
+}
+
+#[test]
+fn test_auth_feature_templates_placed_in_src_auth() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let generate = |name: &str, features: Vec<String>| {
+        let config = ProjectConfig {
+            name: name.to_string(),
+            project_type: "api-server".to_string(),
+            author: "Test Author".to_string(),
+            description: None,
+            features,
+            target: None,
+            esp32_chip: None,
+        };
+        let output_dir = temp_dir.path().join(name);
+        Generator::new().generate(&config, &output_dir).unwrap();
+        output_dir
+    };
+
+    let with_auth = generate("auth-api", vec!["auth".to_string()]);
+    for file in ["mod.rs", "jwt.rs", "password.rs"] {
+        assert!(with_auth.join("src/auth").join(file).exists(), "{}", file);
+    }
+    // API servers serve login from their own handlers
+    for file in ["middleware.rs", "routes.rs", "oauth.rs"] {
+        assert!(!with_auth.join("src/auth").join(file).exists(), "{}", file);
+    }
+    assert!(with_auth.join(".env.auth.example").exists());
+    assert!(!with_auth.join("src/auth/.env.auth.example").exists());
+
+    let without_auth = generate("plain-api", vec![]);
+    assert!(!without_auth.join("src/auth").exists());
+    assert!(!without_auth.join(".env.auth.example").exists());
+}
@@ -0,0 +1,191 @@
+use cargo_forge::transaction::InitJournal;
+use cargo_forge::{Generator, ProjectConfig};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn library_config(name: &str) -> ProjectConfig {
+    ProjectConfig {
+        name: name.to_string(),
+        project_type: "library".to_string(),
+        author: "Test Author".to_string(),
+        description: None,
+        features: vec![],
+        target: None,
+        esp32_chip: None,
+    }
+}
+
+fn entries(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_failed_generation_leaves_nothing_behind() {
+    let temp_dir = TempDir::new().unwrap();
+    let template_dir = temp_dir.path().join("broken");
+    fs::create_dir_all(template_dir.join("library/src")).unwrap();
+    fs::write(
+        template_dir.join("library/Cargo.toml.tera"),
+        "name = \"{{ name }}\"\n",
+    )
+    .unwrap();
+    fs::write(
+        template_dir.join("library/src/lib.rs.tera"),
+        "{{ missing_value }}\n",
+    )
+    .unwrap();
+
+    let output_dir = temp_dir.path().join("half-built");
+    let result = Generator::new()
+        .with_template_dirs(vec![template_dir])
+        .generate(&library_config("half-built"), &output_dir);
+
+    assert!(result.is_err());
+    assert!(!output_dir.exists());
+    // No staging directory is left next to the destination either
+    assert_eq!(entries(temp_dir.path()), vec!["broken"]);
+
+    // A retry with working templates succeeds
+    Generator::new()
+        .generate(&library_config("half-built"), &output_dir)
+        .unwrap();
+    assert!(output_dir.join("Cargo.toml").exists());
+    assert!(output_dir.join("src/lib.rs").exists());
+    assert_eq!(entries(temp_dir.path()), vec!["broken", "half-built"]);
+}
+
+#[test]
+fn test_generate_into_empty_existing_directory() {
+    let temp_dir = TempDir::new().unwrap();
+    let output_dir = temp_dir.path().join("empty");
+    fs::create_dir_all(&output_dir).unwrap();
+
+    Generator::new()
+        .generate(&library_config("empty"), &output_dir)
+        .unwrap();
+    assert!(output_dir.join("Cargo.toml").exists());
+}
+
+#[test]
+fn test_init_keeps_existing_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path().join("existing");
+    fs::create_dir_all(dir.join("notes")).unwrap();
+    fs::write(dir.join("notes/todo.txt"), "ship it").unwrap();
+
+    let summary = Generator::new()
+        .generate_in_place(&library_config("existing"), &dir)
+        .unwrap();
+
+    assert!(summary.created.contains(&"Cargo.toml".to_string()));
+    assert!(summary.created.contains(&"src/lib.rs".to_string()));
+    assert_eq!(
+        fs::read_to_string(dir.join("notes/todo.txt")).unwrap(),
+        "ship it"
+    );
+    // Scratch directories are cleaned up
+    assert!(entries(&dir).iter().all(|name| !name.contains(".forge-")));
+}
+
+#[test]
+fn test_init_conflict_changes_nothing() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path().join("conflict");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("README.md"), "my readme").unwrap();
+
+    let err = Generator::new()
+        .generate_in_place(&library_config("conflict"), &dir)
+        .unwrap_err();
+
+    assert!(err.to_string().contains("README.md"));
+    assert_eq!(entries(&dir), vec!["README.md"]);
+    assert_eq!(
+        fs::read_to_string(dir.join("README.md")).unwrap(),
+        "my readme"
+    );
+}
+
+#[test]
+fn test_init_journal_rollback_restores_directory() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(root.join("keep.txt"), "original").unwrap();
+    fs::create_dir_all(root.join("staged/nested")).unwrap();
+    fs::write(root.join("staged/keep.txt"), "replacement").unwrap();
+    fs::write(root.join("staged/nested/new.rs"), "fn main() {}").unwrap();
+
+    let mut journal = InitJournal::new(root).unwrap();
+    journal
+        .write_file(Path::new("keep.txt"), &root.join("staged/keep.txt"))
+        .unwrap();
+    journal
+        .write_file(
+            Path::new("src/bin/new.rs"),
+            &root.join("staged/nested/new.rs"),
+        )
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(root.join("keep.txt")).unwrap(),
+        "replacement"
+    );
+    assert_eq!(journal.written_files().len(), 2);
+
+    journal.rollback().unwrap();
+
+    assert_eq!(
+        fs::read_to_string(root.join("keep.txt")).unwrap(),
+        "original"
+    );
+    assert!(!root.join("src").exists());
+    assert_eq!(entries(root), vec!["keep.txt", "staged"]);
+}
+
+#[test]
+fn test_init_journal_rollback_after_failed_write() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(root.join("keep.txt"), "original").unwrap();
+
+    let mut journal = InitJournal::new(root).unwrap();
+    journal
+        .write_contents(Path::new("keep.txt"), b"replacement", None)
+        .unwrap();
+    let err = journal
+        .write_file(Path::new("src/lib.rs"), &root.join("missing.rs"))
+        .unwrap_err();
+    assert!(err.to_string().contains("Failed to write"));
+    assert_eq!(journal.written_files(), vec![Path::new("keep.txt")]);
+
+    // The failed write left nothing to undo, so the rollback succeeds
+    journal.rollback().unwrap();
+
+    assert_eq!(
+        fs::read_to_string(root.join("keep.txt")).unwrap(),
+        "original"
+    );
+    assert_eq!(entries(root), vec!["keep.txt"]);
+}
+
+#[test]
+fn test_init_journal_dropped_without_commit_rolls_back() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(root.join("source.txt"), "content").unwrap();
+
+    {
+        let mut journal = InitJournal::new(root).unwrap();
+        journal
+            .write_file(Path::new("copy.txt"), &root.join("source.txt"))
+            .unwrap();
+        assert!(root.join("copy.txt").exists());
+    }
+
+    assert_eq!(entries(root), vec!["source.txt"]);
+}
@@ -12,7 +12,6 @@ fn test_project_name_validation_comprehensive() {
         "my_project",
         "myproject123",
         "a",
-        "_project",
         "underscore_project",
         "dash-project",
         "alpha123numeric",
@@ -34,6 +33,7 @@ fn test_project_name_validation_comprehensive() {
         ("my/project", "Project name cannot contain slashes"),
         ("123project", "Project name cannot start with a number"),
         ("-project", "Project name cannot start with '-' or '_'"),
+        ("_project", "Project name cannot start with '-' or '_'"),
         ("project-", "Project name cannot end with '-' or '_'"),
         (
             "my--project",
@@ -0,0 +1,143 @@
+use cargo_forge::verify::{Verifier, VerifyStatus};
+use cargo_forge::{Generator, ProjectConfig};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn generate(dir: &Path, project_type: &str, features: &[&str]) {
+    let config = ProjectConfig {
+        name: "verified".to_string(),
+        project_type: project_type.to_string(),
+        author: "Test Author".to_string(),
+        description: None,
+        features: features.iter().map(|f| f.to_string()).collect(),
+        target: None,
+        esp32_chip: None,
+    };
+    Generator::new().generate(&config, dir).unwrap();
+}
+
+fn verifier() -> Verifier {
+    Verifier::new().with_cargo_check(false)
+}
+
+#[test]
+fn test_generated_projects_pass() {
+    let temp_dir = TempDir::new().unwrap();
+    for (project_type, features) in [
+        ("library", vec![]),
+        ("api-server", vec!["database", "ci"]),
+        ("workspace", vec![]),
+    ] {
+        let dir = temp_dir.path().join(project_type);
+        generate(&dir, project_type, &features);
+
+        let report = verifier().run(&dir);
+        assert!(report.passed(), "{}: {:?}", project_type, report.checks);
+        assert_eq!(
+            report.check("cargo metadata").unwrap().status,
+            VerifyStatus::Passed
+        );
+        assert!(report.check("Parse TOML").is_some());
+        assert_eq!(
+            report.check("cargo check").unwrap().status,
+            VerifyStatus::Skipped("disabled".to_string())
+        );
+    }
+
+    // CI workflows are YAML
+    let report = verifier().run(&temp_dir.path().join("api-server"));
+    assert_eq!(
+        report.check("Parse YAML").unwrap().status,
+        VerifyStatus::Passed
+    );
+}
+
+#[test]
+fn test_broken_files_fail() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    generate(dir, "cli-tool", &[]);
+    fs::create_dir_all(dir.join(".github/workflows")).unwrap();
+    fs::write(dir.join(".github/workflows/ci.yml"), "jobs: [unclosed\n").unwrap();
+    let manifest = fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+    fs::write(
+        dir.join("Cargo.toml"),
+        manifest.replace("[package]", "[package"),
+    )
+    .unwrap();
+    // Build output is not parsed
+    fs::create_dir_all(dir.join("target")).unwrap();
+    fs::write(dir.join("target/broken.toml"), "[oops").unwrap();
+
+    let report = Verifier::new().run(dir);
+    assert!(!report.passed());
+
+    let VerifyStatus::Failed(toml) = &report.check("Parse TOML").unwrap().status else {
+        panic!("TOML should fail");
+    };
+    assert!(toml.starts_with("Cargo.toml:1:"));
+    assert!(!toml.contains("target/"));
+    let VerifyStatus::Failed(yaml) = &report.check("Parse YAML").unwrap().status else {
+        panic!("YAML should fail");
+    };
+    assert!(yaml.contains(".github/workflows/ci.yml"));
+
+    assert!(matches!(
+        report.check("cargo metadata").unwrap().status,
+        VerifyStatus::Failed(_)
+    ));
+    // No point building a project whose manifest does not parse
+    assert_eq!(
+        report.check("cargo check").unwrap().status,
+        VerifyStatus::Skipped("cargo metadata failed".to_string())
+    );
+}
+
+#[test]
+fn test_unresolvable_dependencies_fail() {
+    // Offline resolution errors are only confirmed against the registry
+    // index when verification may go online
+    let temp_dir = TempDir::new().unwrap();
+    for (name, dependency, error) in [
+        (
+            "misspelled",
+            "forge-verify-no-such-crate = \"1\"",
+            "no matching package named",
+        ),
+        (
+            "impossible",
+            "serde = \"999\"",
+            "failed to select a version",
+        ),
+    ] {
+        let dir = temp_dir.path().join(name);
+        generate(&dir, "library", &[]);
+        let manifest = fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+        fs::write(
+            dir.join("Cargo.toml"),
+            manifest.replace("[dependencies]", &format!("[dependencies]\n{}", dependency)),
+        )
+        .unwrap();
+
+        let report = Verifier::new().run(&dir);
+        assert!(report.passed(), "{}: {:?}", name, report.checks);
+        assert!(
+            matches!(
+                report.check("cargo check").unwrap().status,
+                VerifyStatus::Skipped(_)
+            ),
+            "{}: {:?}",
+            name,
+            report.checks
+        );
+
+        let report = Verifier::new().with_online(true).run(&dir);
+        assert!(!report.passed(), "{}: {:?}", name, report.checks);
+        let VerifyStatus::Failed(output) = &report.check("cargo check").unwrap().status else {
+            panic!("{}: cargo check should fail", name);
+        };
+        assert!(output.contains(error), "{}: {}", name, output);
+        assert!(!dir.join("Cargo.lock").exists());
+    }
+}